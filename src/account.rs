@@ -0,0 +1,401 @@
+use std::{path::PathBuf, time::Duration};
+
+use clap::Args;
+use colored::*;
+use mpl_token_metadata::{accounts::MasterEdition, types::Key};
+use serde_json::json;
+use solana_account::Account;
+use solana_sdk::{nonce::State as NonceState, system_program};
+use spl_token::solana_program::{program_pack::Pack, pubkey::Pubkey};
+use spl_token_2022::extension::AccountType;
+
+use crate::{
+    config::Config,
+    input::{self, AccountInputArgs},
+    mint::{self, parse_metadata, MintWithExtensions, TokenMetadata},
+    multisig::{PrettyMultisig, MULTISIG_LEN},
+    nonce,
+    output::{self, OutputFormat},
+    source,
+    token_account::{self, TokenAccountWithExtensions},
+    token_program::TokenProgram,
+    utils,
+};
+
+/// Token-2022 stores the `AccountType` of mints and token accounts with
+/// extensions right after the 165 byte base token account.
+const ACCOUNT_TYPE_INDEX: usize = spl_token::state::Account::LEN;
+
+/// What an account holds, judged from its owner and data.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AccountKind {
+    /// A mint of either token program.
+    Mint,
+    /// A token account of either token program.
+    TokenAccount,
+    /// An SPL multisig of either token program.
+    Multisig,
+    /// A Metaplex metadata account.
+    Metadata,
+    /// A Metaplex master edition account.
+    MasterEdition,
+    /// A durable nonce account.
+    Nonce,
+    /// Anything else.
+    Unknown,
+}
+
+impl AccountKind {
+    /// Detects the kind of `account`. Token-2022 accounts with extensions are
+    /// told apart by their `AccountType`.
+    pub fn classify(account: &Account) -> Self {
+        let data = &account.data;
+        if TokenProgram::try_from(account.owner).is_ok() {
+            return match data.len() {
+                spl_token::state::Mint::LEN => AccountKind::Mint,
+                spl_token::state::Account::LEN => AccountKind::TokenAccount,
+                MULTISIG_LEN => AccountKind::Multisig,
+                len if len > ACCOUNT_TYPE_INDEX && account.owner == spl_token_2022::ID => {
+                    match data[ACCOUNT_TYPE_INDEX] {
+                        t if t == AccountType::Mint as u8 => AccountKind::Mint,
+                        t if t == AccountType::Account as u8 => AccountKind::TokenAccount,
+                        _ => AccountKind::Unknown,
+                    }
+                }
+                _ => AccountKind::Unknown,
+            };
+        }
+
+        if account.owner == mpl_token_metadata::ID {
+            return match data.first() {
+                Some(&key) if key == Key::MetadataV1 as u8 => AccountKind::Metadata,
+                Some(&key) if key == Key::MasterEditionV2 as u8 => AccountKind::MasterEdition,
+                _ => AccountKind::Unknown,
+            };
+        }
+
+        if account.owner == system_program::id() && data.len() == NonceState::size() {
+            return AccountKind::Nonce;
+        }
+
+        AccountKind::Unknown
+    }
+
+    /// The camelCase name used as the `kind` of JSON output.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AccountKind::Mint => "mint",
+            AccountKind::TokenAccount => "tokenAccount",
+            AccountKind::Multisig => "multisig",
+            AccountKind::Metadata => "metadata",
+            AccountKind::MasterEdition => "masterEdition",
+            AccountKind::Nonce => "nonce",
+            AccountKind::Unknown => "unknown",
+        }
+    }
+}
+
+/// A readable name for well-known owner programs.
+fn owner_name(owner: &Pubkey) -> Option<&'static str> {
+    match *owner {
+        id if id == system_program::id() => Some("System Program"),
+        id if id == spl_token::ID => Some("Token Program"),
+        id if id == spl_token_2022::ID => Some("Token-2022 Program"),
+        id if id == mpl_token_metadata::ID => Some("Metaplex Token Metadata Program"),
+        id if id == spl_associated_token_account::ID => Some("Associated Token Account Program"),
+        id if id == solana_sdk::bpf_loader_upgradeable::id() => Some("BPF Upgradeable Loader"),
+        _ => None,
+    }
+}
+
+/// Arguments of `surfer account`.
+#[derive(Debug, Args)]
+pub struct FetchAccount {
+    #[clap(
+        value_parser = utils::parse_pubkey,
+        required_unless_present_any = ["from_file", "from_stdin"],
+        help = "The account address to fetch. Must be a valid base58 encoded pubkey."
+    )]
+    pub(crate) pubkey: Option<Pubkey>,
+    #[clap(flatten)]
+    pub(crate) input: AccountInputArgs,
+    #[clap(
+        long,
+        help = "For token accounts, read the mint from a file too, in the same formats as --from-file. The mint is fetched over RPC otherwise."
+    )]
+    pub(crate) mint_file: Option<PathBuf>,
+}
+
+impl FetchAccount {
+    /// Fetches the account, detects its kind and prints it.
+    pub async fn process(&self, config: &Config) -> anyhow::Result<()> {
+        let output = config.output;
+        let spinner = utils::get_spinner("Fetching account...")?;
+        spinner.enable_steady_tick(Duration::from_millis(100));
+
+        // Accounts read from input are decoded without touching RPC.
+        let (pubkey, account, rpc_client) = match self.input.read()? {
+            Some((pubkey, account)) => (self.pubkey.or(pubkey), account, None),
+            None => {
+                let rpc_client = config.rpc_client()?;
+                let pubkey = input::required_pubkey(self.pubkey)?;
+                let account = source::require_account(&rpc_client, &pubkey).await?;
+                (Some(pubkey), account, Some(rpc_client))
+            }
+        };
+        let display_pubkey = input::display_pubkey(pubkey.as_ref());
+        let kind = AccountKind::classify(&account);
+
+        let json = match kind {
+            AccountKind::Mint => {
+                let token_metadata = match (&rpc_client, pubkey) {
+                    (Some(rpc_client), Some(pubkey)) => {
+                        TokenMetadata::fetch_and_parse(pubkey, rpc_client).await
+                    }
+                    _ => None,
+                };
+                let mint_data =
+                    MintWithExtensions::try_parse_mint_with_extensions(account, token_metadata)?;
+                let mint = mint::pretty_mint(&rpc_client, pubkey.as_ref(), mint_data).await?;
+                spinner.finish_and_clear();
+
+                if output == OutputFormat::Table {
+                    mint.print();
+                    return Ok(());
+                }
+                mint.to_json()
+            }
+            AccountKind::TokenAccount => {
+                let mint_pubkey =
+                    TokenAccountWithExtensions::try_parse_token_account_with_extensions(
+                        account.clone(),
+                    )?
+                    .base
+                    .get_mint();
+                let (mint, rpc_client) = match (&self.mint_file, rpc_client) {
+                    (Some(path), rpc_client) => {
+                        (input::read_account_file(path, None)?.1, rpc_client)
+                    }
+                    (None, rpc_client) => {
+                        let rpc_client = match rpc_client {
+                            Some(rpc_client) => rpc_client,
+                            None => config.rpc_client()?,
+                        };
+                        (
+                            source::require_account(&rpc_client, &mint_pubkey).await?,
+                            Some(rpc_client),
+                        )
+                    }
+                };
+                let (mint, token_account) = token_account::pretty_token_account(
+                    &rpc_client,
+                    pubkey.as_ref(),
+                    account,
+                    mint,
+                )
+                .await?;
+                spinner.finish_and_clear();
+
+                // The mint is shown alongside, just like `token-account fetch`.
+                if output == OutputFormat::Table {
+                    mint.print();
+                    token_account.print();
+                    return Ok(());
+                }
+                json!({
+                    "mint": mint.to_json(),
+                    "tokenAccount": token_account.to_json(),
+                })
+            }
+            AccountKind::Multisig => {
+                spinner.finish_and_clear();
+                let multisig = PrettyMultisig::try_parse(pubkey.as_ref(), &account)?;
+
+                if output == OutputFormat::Table {
+                    multisig.print();
+                    return Ok(());
+                }
+                multisig.to_json()
+            }
+            AccountKind::Metadata => {
+                spinner.finish_and_clear();
+                let metadata = TokenMetadata {
+                    metadata: Some(parse_metadata(&account.data).ok_or_else(|| {
+                        anyhow::anyhow!("Error parsing metadata account {}", display_pubkey)
+                    })?),
+                    master_edition: None,
+                };
+
+                if output == OutputFormat::Table {
+                    metadata.print();
+                    return Ok(());
+                }
+                metadata.metadata_to_json().unwrap_or_default()
+            }
+            AccountKind::MasterEdition => {
+                spinner.finish_and_clear();
+                let metadata = TokenMetadata {
+                    metadata: None,
+                    master_edition: Some(MasterEdition::from_bytes(&account.data).map_err(
+                        |e| {
+                            anyhow::anyhow!(
+                                "Error parsing master edition {}: {}",
+                                display_pubkey,
+                                e
+                            )
+                        },
+                    )?),
+                };
+
+                if output == OutputFormat::Table {
+                    metadata.print();
+                    return Ok(());
+                }
+                metadata.master_edition_to_json().unwrap_or_default()
+            }
+            AccountKind::Nonce => {
+                spinner.finish_and_clear();
+                let data = nonce::parse_nonce(&display_pubkey, &account)?;
+                let nonce_account =
+                    nonce::NonceAccount::new(display_pubkey.clone(), &account, &data);
+
+                if output == OutputFormat::Table {
+                    nonce_account.print();
+                    return Ok(());
+                }
+                serde_json::to_value(nonce_account)?
+            }
+            AccountKind::Unknown => {
+                spinner.finish_and_clear();
+                let owner = owner_name(&account.owner)
+                    .map(|name| format!("{} ({})", account.owner, name))
+                    .unwrap_or(account.owner.to_string());
+
+                if output == OutputFormat::Table {
+                    println!(
+                        "{} {} is owned by {}, which surfer can't decode. It holds {} lamports and {} bytes of data{}.",
+                        "Unknown account:".yellow().bold(),
+                        display_pubkey.cyan().bold(),
+                        owner.cyan().bold(),
+                        account.lamports,
+                        account.data.len(),
+                        if account.executable { " and is executable" } else { "" },
+                    );
+                    return Ok(());
+                }
+                json!({
+                    "owner": account.owner.to_string(),
+                    "ownerName": owner_name(&account.owner),
+                    "lamports": account.lamports.to_string(),
+                    "dataLength": account.data.len(),
+                    "executable": account.executable,
+                })
+            }
+        };
+
+        output::print_json(
+            &json!({
+                "pubkey": display_pubkey,
+                "kind": kind.name(),
+                "account": json,
+            }),
+            output,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{
+        master_edition_account, metadata_account, mint_account, multisig_account, nonce_account,
+        token_2022_mint_account, token_2022_token_account, token_account,
+    };
+
+    fn owned_by(account: Account, owner: Pubkey) -> Account {
+        Account { owner, ..account }
+    }
+
+    #[test]
+    fn classifies_accounts() {
+        let mint = Pubkey::new_unique();
+        let wallet = Pubkey::new_unique();
+        let mut unknown_type = token_2022_mint_account(6, 0);
+        unknown_type.data[ACCOUNT_TYPE_INDEX] = AccountType::Uninitialized as u8;
+
+        let cases = [
+            ("legacy mint", mint_account(6, 0), AccountKind::Mint),
+            (
+                "legacy token account",
+                token_account(&mint, &wallet, 1),
+                AccountKind::TokenAccount,
+            ),
+            (
+                "legacy multisig",
+                multisig_account(1, &[wallet]),
+                AccountKind::Multisig,
+            ),
+            (
+                "Token-2022 mint without extensions",
+                owned_by(mint_account(6, 0), spl_token_2022::ID),
+                AccountKind::Mint,
+            ),
+            (
+                "Token-2022 mint with extensions",
+                token_2022_mint_account(6, 0),
+                AccountKind::Mint,
+            ),
+            (
+                "Token-2022 token account with extensions",
+                token_2022_token_account(&mint, &wallet, 1),
+                AccountKind::TokenAccount,
+            ),
+            (
+                "Token-2022 account of unknown type",
+                unknown_type,
+                AccountKind::Unknown,
+            ),
+            (
+                "legacy Token account with extension sized data",
+                owned_by(token_2022_mint_account(6, 0), spl_token::ID),
+                AccountKind::Unknown,
+            ),
+            (
+                "Metaplex metadata",
+                metadata_account(&mint, "Surf", "SURF"),
+                AccountKind::Metadata,
+            ),
+            (
+                "Metaplex master edition",
+                master_edition_account(0, Some(1)),
+                AccountKind::MasterEdition,
+            ),
+            (
+                "Metaplex account of another kind",
+                owned_by(
+                    Account {
+                        data: vec![Key::EditionV1 as u8],
+                        ..Account::default()
+                    },
+                    mpl_token_metadata::ID,
+                ),
+                AccountKind::Unknown,
+            ),
+            ("nonce", nonce_account(&wallet), AccountKind::Nonce),
+            (
+                "system account",
+                owned_by(Account::default(), system_program::id()),
+                AccountKind::Unknown,
+            ),
+            (
+                "mint data with an unknown owner",
+                owned_by(mint_account(6, 0), Pubkey::new_unique()),
+                AccountKind::Unknown,
+            ),
+        ];
+
+        for (name, account, kind) in cases {
+            assert_eq!(AccountKind::classify(&account), kind, "{}", name);
+        }
+    }
+}
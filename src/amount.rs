@@ -0,0 +1,182 @@
+use std::str::FromStr;
+
+/// A token amount given on the command line.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Amount {
+    /// A decimal amount, e.g. `1.5`. Interpreted in UI units unless the
+    /// command is run in raw mode.
+    Decimal(String),
+    /// The full balance of the source token account.
+    All,
+}
+
+impl FromStr for Amount {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("ALL") {
+            return Ok(Amount::All);
+        }
+
+        let (whole, fraction) = s.split_once('.').unwrap_or((s, ""));
+        if (whole.is_empty() && fraction.is_empty())
+            || !whole.chars().all(|c| c.is_ascii_digit())
+            || !fraction.chars().all(|c| c.is_ascii_digit())
+        {
+            return Err(anyhow::anyhow!(
+                "Invalid amount `{}`, expected a decimal number or ALL",
+                s
+            ));
+        }
+
+        Ok(Amount::Decimal(s.to_string()))
+    }
+}
+
+impl Amount {
+    /// Converts the amount to base units.
+    ///
+    /// `balance` is the source account balance used for [`Amount::All`];
+    /// pass `None` for instructions without a source account. With `raw` set
+    /// the amount is taken as base units and must be a whole number.
+    pub fn to_raw_amount(
+        &self,
+        decimals: u8,
+        balance: Option<u64>,
+        raw: bool,
+    ) -> anyhow::Result<u64> {
+        match self {
+            Amount::All => balance.ok_or_else(|| {
+                anyhow::anyhow!("ALL can only be used with instructions that have a source account")
+            }),
+            Amount::Decimal(amount) if raw => amount
+                .parse::<u64>()
+                .map_err(|e| anyhow::anyhow!("Invalid raw amount `{}`: {}", amount, e)),
+            Amount::Decimal(amount) => parse_ui_amount(amount, decimals),
+        }
+    }
+}
+
+/// Parses a decimal UI amount into base units without going through floats.
+///
+/// Fails if `amount` has more fractional digits than `decimals` allows or if
+/// the result does not fit in a u64.
+pub fn parse_ui_amount(amount: &str, decimals: u8) -> anyhow::Result<u64> {
+    let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    if fraction.len() > decimals as usize {
+        return Err(anyhow::anyhow!(
+            "Amount `{}` has {} fractional digits but the mint only has {} decimals",
+            amount,
+            fraction.len(),
+            decimals
+        ));
+    }
+
+    let overflow = || anyhow::anyhow!("Amount `{}` is too large", amount);
+    let scale = 10u64.checked_pow(decimals as u32).ok_or_else(overflow)?;
+    let whole = match whole {
+        "" => 0,
+        whole => whole.parse::<u64>().map_err(|_| overflow())?,
+    };
+    let fraction = match fraction {
+        "" => 0,
+        fraction => {
+            fraction.parse::<u64>().map_err(|_| overflow())?
+                * 10u64.pow((decimals as usize - fraction.len()) as u32)
+        }
+    };
+
+    whole
+        .checked_mul(scale)
+        .and_then(|whole| whole.checked_add(fraction))
+        .ok_or_else(overflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_raw(amount: &str, decimals: u8) -> anyhow::Result<u64> {
+        amount
+            .parse::<Amount>()?
+            .to_raw_amount(decimals, None, false)
+    }
+
+    #[test]
+    fn parses_decimal_amounts() {
+        assert_eq!(to_raw("1.5", 6).unwrap(), 1_500_000);
+        assert_eq!(to_raw("1.", 2).unwrap(), 100);
+        assert_eq!(to_raw(".5", 1).unwrap(), 5);
+        assert_eq!(to_raw("0", 9).unwrap(), 0);
+        assert_eq!(to_raw(" 42 ", 0).unwrap(), 42);
+        assert_eq!(to_raw("0.000001", 6).unwrap(), 1);
+    }
+
+    #[test]
+    fn rejects_more_fraction_digits_than_decimals() {
+        let err = to_raw("1.1234567", 6).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Amount `1.1234567` has 7 fractional digits but the mint only has 6 decimals"
+        );
+        assert!(to_raw("1.5", 0).is_err());
+    }
+
+    #[test]
+    fn rejects_amounts_that_overflow_u64() {
+        assert_eq!(to_raw("18446744073709551615", 0).unwrap(), u64::MAX);
+        for (amount, decimals) in [
+            ("18446744073709551616", 0),
+            ("18446744073709.551616", 6),
+            ("100000000000", 9),
+            ("1", 20),
+        ] {
+            let err = to_raw(amount, decimals).unwrap_err();
+            assert!(err.to_string().ends_with("is too large"), "{}", amount);
+        }
+    }
+
+    #[test]
+    fn parses_all_in_any_case() {
+        for all in ["ALL", "all", "All", " aLl "] {
+            assert_eq!(all.parse::<Amount>().unwrap(), Amount::All);
+        }
+        assert_eq!(
+            Amount::All.to_raw_amount(6, Some(1_234), false).unwrap(),
+            1_234
+        );
+        let err = Amount::All.to_raw_amount(6, None, false).unwrap_err();
+        assert!(err.to_string().starts_with("ALL can only be used"));
+    }
+
+    #[test]
+    fn rejects_negative_empty_and_malformed_input() {
+        for amount in ["-1", "", " ", ".", "1.2.3", "1e9", "+1", "1,5", "allx"] {
+            let err = amount.parse::<Amount>().unwrap_err();
+            assert!(
+                err.to_string().starts_with("Invalid amount"),
+                "{:?}",
+                amount
+            );
+        }
+    }
+
+    #[test]
+    fn raw_mode_takes_whole_base_units() {
+        let amount = "1500".parse::<Amount>().unwrap();
+        assert_eq!(amount.to_raw_amount(6, None, true).unwrap(), 1_500);
+
+        let err = "1.5"
+            .parse::<Amount>()
+            .unwrap()
+            .to_raw_amount(6, None, true)
+            .unwrap_err();
+        assert!(err.to_string().starts_with("Invalid raw amount `1.5`"));
+        assert!("18446744073709551616"
+            .parse::<Amount>()
+            .unwrap()
+            .to_raw_amount(0, None, true)
+            .is_err());
+    }
+}
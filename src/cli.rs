@@ -1,18 +1,15 @@
 use clap::{Parser, Subcommand};
-use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_sdk::{
-    signature::{Keypair, Signature},
-    signer::Signer,
+use surfer::{
+    account::FetchAccount,
+    config::{ConfigCommands, GlobalArgs},
+    mint::MintCommands,
+    multisig::MultisigCommands,
+    nonce::NonceCommands,
+    token_account::TokenAccountCommands,
+    transaction::{SendArgs, TxCommands},
+    wallet::WalletCommands,
 };
 
-use std::str::FromStr;
-
-use spl_token::solana_program::pubkey::Pubkey;
-
-use crate::{mint::MintCommands, token_account::TokenAccountCommands};
-
-pub const DEFAULT_KEYPAIR_PATH: &str = ".config/solana/id.json";
-
 #[derive(Parser, Debug)]
 #[clap(author, version, about)]
 pub struct Cli {
@@ -20,135 +17,59 @@ pub struct Cli {
     #[clap(short, long, global = true)]
     pub log_level: Option<String>,
 
-    /// RPC URL for the Solana cluster
-    #[clap(short, long, env = "SOLANA_RPC_URL", global = true)]
-    pub solana_rpc_url: Option<String>,
+    #[clap(flatten)]
+    pub global: GlobalArgs,
 
     #[clap(subcommand)]
     pub command: Commands,
 }
 
-impl Cli {
-    pub fn validate_args(&self) -> anyhow::Result<()> {
-        self._check_rpc_url()?;
-        Ok(())
-    }
-
-    fn _check_rpc_url(&self) -> anyhow::Result<()> {
-        match &self.solana_rpc_url {
-            Some(url) => {
-                if url.starts_with("https://") {
-                    Ok(())
-                } else {
-                    Err(anyhow::anyhow!("Invalid RPC URL: {}", url))
-                }
-            }
-            None => Err(anyhow::anyhow!(
-                "RPC URL not provided use --solana-rpc-url or set `SOLANA_RPC_URL` env variable"
-            )),
-        }
-    }
-
-    pub fn parse_pubkey(pubkey_str: &str) -> anyhow::Result<Pubkey> {
-        match Pubkey::from_str(pubkey_str) {
-            Ok(pubkey) => Ok(pubkey),
-            Err(err) => Err(anyhow::anyhow!("Invalid pubkey: {}", err)),
-        }
-    }
-}
-#[derive(Clone, Parser, Debug)]
-pub struct SolanaRpcArgs {
-    #[arg(long, short, env)]
-    pub solana_rpc_url: String,
-}
-
 #[derive(Subcommand, Debug)]
 pub enum Commands {
+    /// Fetch any account and display it according to its detected kind
+    Account(FetchAccount),
     #[clap(subcommand)]
     Mint(MintCommands),
     #[clap(subcommand)]
     TokenAccount(TokenAccountCommands),
+    #[clap(subcommand)]
+    Config(ConfigCommands),
+    #[clap(subcommand)]
+    Multisig(MultisigCommands),
+    #[clap(subcommand)]
+    Nonce(NonceCommands),
+    #[clap(subcommand)]
+    Tx(TxCommands),
+    #[clap(subcommand)]
+    Wallet(WalletCommands),
 }
 
-#[derive(Debug, Clone)]
-pub enum TokenProgram {
-    Token2022,
-    LegacyToken,
-}
-
-impl Into<Pubkey> for TokenProgram {
-    fn into(self) -> Pubkey {
+impl Commands {
+    /// The signer and transaction flags, which only the commands that send a
+    /// transaction take.
+    pub fn send_args(&self) -> Option<&SendArgs> {
         match self {
-            TokenProgram::Token2022 => spl_token::ID,
-            TokenProgram::LegacyToken => spl_token_2022::ID,
-        }
-    }
-}
-
-impl TryFrom<Pubkey> for TokenProgram {
-    type Error = anyhow::Error;
-    fn try_from(pubkey: Pubkey) -> anyhow::Result<Self> {
-        match pubkey {
-            spl_token::ID => Ok(TokenProgram::LegacyToken),
-            spl_token_2022::ID => Ok(TokenProgram::Token2022),
-            _ => Err(anyhow::anyhow!("Invalid token program pubkey: {}", pubkey)),
+            Commands::Mint(MintCommands::Create(args)) => Some(&args.send),
+            Commands::Mint(MintCommands::Ix(args)) => Some(&args.send),
+            Commands::TokenAccount(TokenAccountCommands::Ix(args)) => Some(&args.send),
+            Commands::Multisig(MultisigCommands::Create(args)) => Some(&args.send),
+            Commands::Nonce(NonceCommands::Create(args)) => Some(&args.send),
+            Commands::Nonce(NonceCommands::Advance(args)) => Some(&args.send),
+            Commands::Nonce(NonceCommands::Withdraw(args)) => Some(&args.send),
+            Commands::Tx(TxCommands::Sign(args) | TxCommands::Send(args)) => Some(&args.send),
+            _ => None,
         }
     }
 }
 
-impl TokenProgram {
-    pub fn _to_select_vec() -> Vec<&'static str> {
-        vec!["Token2022", "LegacyToken"]
-    }
-
-    pub fn _from_select_str(select_str: &str) -> anyhow::Result<Self> {
-        match select_str {
-            "Token2022" => Ok(TokenProgram::Token2022),
-            "LegacyToken" => Ok(TokenProgram::LegacyToken),
-            _ => Err(anyhow::anyhow!("Invalid token program: {}", select_str)),
-        }
-    }
-}
-
-pub struct LocalWallet {
-    pub keypair: Keypair,
-}
-
-impl LocalWallet {
-    pub fn fetch() -> anyhow::Result<LocalWallet> {
-        let home_dir = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Failed to get home dir"))?;
-        let keypair = solana_sdk::signature::read_keypair_file(home_dir.join(DEFAULT_KEYPAIR_PATH))
-            .map_err(|e| {
-                anyhow::anyhow!(
-                    "Failed to read keypair file from path {}: {}",
-                    DEFAULT_KEYPAIR_PATH,
-                    e
-                )
-            })?;
-        Ok(LocalWallet { keypair })
-    }
-
-    pub fn pubkey(&self) -> Pubkey {
-        self.keypair.pubkey()
-    }
-
-    pub async fn sign_and_send_ixs(
-        &self,
-        ixs: Vec<solana_sdk::instruction::Instruction>,
-        rpc_url: &str,
-    ) -> anyhow::Result<Signature> {
-        let mut tx = solana_sdk::transaction::Transaction::new_with_payer(
-            &ixs,
-            Some(&self.keypair.pubkey()),
-        );
-        let rpc_client = RpcClient::new(rpc_url.to_string());
-        let recent_blockhash = rpc_client.get_latest_blockhash().await?;
-        tx.sign(&[&self.keypair], recent_blockhash);
-
-        let sig = rpc_client.send_and_confirm_transaction(&tx).await?;
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
 
-        println!("Transaction signature: {}", sig);
+    use super::*;
 
-        Ok(sig)
+    #[test]
+    fn cli_is_well_formed() {
+        Cli::command().debug_assert();
     }
 }
@@ -0,0 +1,717 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use clap::{Args, Subcommand, ValueEnum};
+use colored::*;
+use prettytable::{format, row, Table};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use url::Url;
+
+use crate::{
+    output::{self, OutputFormat},
+    signer::{SignerArgs, SignerSource},
+    transaction::{SendArgs, TransactionArgs},
+    utils,
+};
+
+/// The Solana CLI config file, relative to the home directory.
+pub const SOLANA_CLI_CONFIG_PATH: &str = ".config/solana/cli/config.yml";
+/// The surfer config file, relative to the home directory.
+pub const SURFER_CONFIG_PATH: &str = ".config/surfer/config.toml";
+const DEFAULT_PROFILE: &str = "default";
+
+/// The subset of the Solana CLI config file surfer reads.
+#[derive(Debug, Default, Deserialize)]
+pub struct SolanaCliConfig {
+    /// RPC URL set with `solana config set --url`.
+    pub json_rpc_url: Option<String>,
+    /// Path of the default keypair.
+    pub keypair_path: Option<String>,
+    /// Default commitment level.
+    pub commitment: Option<String>,
+}
+
+impl SolanaCliConfig {
+    /// Loads the config, or returns `None` if the Solana CLI was never
+    /// configured.
+    pub fn load(path: &Path) -> anyhow::Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let contents = fs::read_to_string(path)?;
+        let config = serde_yaml::from_str(&contents).map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to parse Solana CLI config {}: {}",
+                path.display(),
+                e
+            )
+        })?;
+
+        Ok(Some(config))
+    }
+}
+
+/// The surfer config file, holding named profiles such as `devnet` or
+/// `local`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SurferConfig {
+    /// Profile used when `--profile` is not given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_profile: Option<String>,
+    /// Profiles by name.
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// Settings of a surfer profile. Unset keys fall back to the Solana CLI
+/// config.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    /// RPC URL or cluster moniker.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rpc_url: Option<String>,
+    /// Wallet keypair, in the same formats as `--keypair`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keypair: Option<String>,
+    /// Commitment level.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<String>,
+}
+
+impl SurferConfig {
+    /// Loads the config, or an empty one if the file doesn't exist yet.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(SurferConfig::default());
+        }
+
+        let contents = fs::read_to_string(path)?;
+        toml::from_str(&contents)
+            .map_err(|e| anyhow::anyhow!("Failed to parse config {}: {}", path.display(), e))
+    }
+
+    /// Writes the config, creating its directory if needed.
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, toml::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+
+    /// Returns the named profile, creating it if needed. The first profile
+    /// written becomes the default so it takes effect without `--profile`.
+    fn profile_mut(&mut self, name: &str) -> &mut Profile {
+        self.default_profile.get_or_insert(name.to_string());
+        self.profiles.entry(name.to_string()).or_default()
+    }
+
+    /// Validates `value` and stores it under `key` of the named profile.
+    /// `default-profile` must name a profile that already exists.
+    fn set(&mut self, profile_name: &str, key: &ConfigKey, value: &str) -> anyhow::Result<()> {
+        match key {
+            ConfigKey::RpcUrl => {
+                parse_rpc_url(value)?;
+            }
+            ConfigKey::Keypair => {
+                SignerSource::from_str(value)?;
+            }
+            ConfigKey::Commitment => {
+                parse_commitment(value)?;
+            }
+            ConfigKey::DefaultProfile => {
+                self.selected_profile(Some(value))?;
+            }
+        }
+
+        let value = Some(value.to_string());
+        match key {
+            ConfigKey::RpcUrl => self.profile_mut(profile_name).rpc_url = value,
+            ConfigKey::Keypair => self.profile_mut(profile_name).keypair = value,
+            ConfigKey::Commitment => self.profile_mut(profile_name).commitment = value,
+            ConfigKey::DefaultProfile => self.default_profile = value,
+        }
+
+        Ok(())
+    }
+
+    /// Returns the profile selected by `--profile`, falling back to the
+    /// default profile of the file.
+    fn selected_profile(&self, name: Option<&str>) -> anyhow::Result<Option<(String, Profile)>> {
+        let Some(name) = name.or(self.default_profile.as_deref()) else {
+            return Ok(None);
+        };
+
+        match self.profiles.get(name) {
+            Some(profile) => Ok(Some((name.to_string(), profile.clone()))),
+            None if self.profiles.is_empty() => Err(anyhow::anyhow!(
+                "Profile `{}` not found, ~/{} has no profiles",
+                name,
+                SURFER_CONFIG_PATH
+            )),
+            None => Err(anyhow::anyhow!(
+                "Profile `{}` not found in ~/{}, available profiles: {}",
+                name,
+                SURFER_CONFIG_PATH,
+                self.profiles.keys().cloned().collect::<Vec<_>>().join(", ")
+            )),
+        }
+    }
+}
+
+/// The cluster and output flags every command takes.
+#[derive(Debug, Clone, Args)]
+#[clap(about = None, long_about = None)]
+pub struct GlobalArgs {
+    /// RPC URL for the Solana cluster, or one of localhost, devnet, testnet, mainnet-beta
+    #[clap(
+        short,
+        long,
+        env = "SOLANA_RPC_URL",
+        global = true,
+        value_parser = parse_rpc_url
+    )]
+    pub solana_rpc_url: Option<String>,
+
+    /// Commitment level: processed, confirmed, finalized
+    #[clap(long, global = true, value_parser = parse_commitment)]
+    pub commitment: Option<CommitmentConfig>,
+
+    /// Profile of ~/.config/surfer/config.toml to use
+    #[clap(long, env = "SURFER_PROFILE", global = true)]
+    pub profile: Option<String>,
+
+    /// Output format
+    #[clap(short, long, value_enum, default_value = "table", global = true)]
+    pub output: OutputFormat,
+}
+
+/// Settings resolved from command line flags and env vars, then the selected
+/// surfer profile, then the Solana CLI config.
+#[derive(Debug)]
+pub struct Config {
+    /// RPC URL of the cluster, if any was configured.
+    pub rpc_url: Option<String>,
+    /// Commitment level for RPC requests.
+    pub commitment: CommitmentConfig,
+    /// Signer flags, with the wallet keypair filled in from the config files.
+    pub signer: SignerArgs,
+    /// Transaction flags of the command.
+    pub transaction: TransactionArgs,
+    /// Output format of the command.
+    pub output: OutputFormat,
+    /// Name of the surfer profile in use, if any.
+    pub profile: Option<String>,
+}
+
+impl Config {
+    /// Resolves the settings of a command. `send_args` are the signer and
+    /// transaction flags of commands that send a transaction.
+    pub fn resolve(global: &GlobalArgs, send_args: Option<&SendArgs>) -> anyhow::Result<Config> {
+        Self::resolve_in(global, send_args, &home_dir()?)
+    }
+
+    /// Resolves the settings with the config files under `home_dir`.
+    fn resolve_in(
+        global: &GlobalArgs,
+        send_args: Option<&SendArgs>,
+        home_dir: &Path,
+    ) -> anyhow::Result<Config> {
+        let send_args = send_args.cloned().unwrap_or_default();
+        let surfer_config = SurferConfig::load(&home_dir.join(SURFER_CONFIG_PATH))?;
+        let (profile_name, profile) =
+            match surfer_config.selected_profile(global.profile.as_deref())? {
+                Some((name, profile)) => (Some(name), profile),
+                None => (None, Profile::default()),
+            };
+        let solana_config =
+            SolanaCliConfig::load(&home_dir.join(SOLANA_CLI_CONFIG_PATH))?.unwrap_or_default();
+
+        let rpc_url = match (
+            &global.solana_rpc_url,
+            profile.rpc_url,
+            solana_config.json_rpc_url,
+        ) {
+            (Some(url), _, _) => Some(url.clone()),
+            (None, Some(url), _) | (None, None, Some(url)) => Some(parse_rpc_url(&url)?),
+            (None, None, None) => None,
+        };
+
+        let commitment = match (
+            global.commitment,
+            profile.commitment,
+            solana_config.commitment,
+        ) {
+            (Some(commitment), _, _) => commitment,
+            (None, Some(commitment), _) | (None, None, Some(commitment)) => {
+                parse_commitment(&commitment)?
+            }
+            (None, None, None) => CommitmentConfig::confirmed(),
+        };
+
+        let keypair = match (
+            &send_args.signer.keypair,
+            profile.keypair,
+            solana_config.keypair_path,
+        ) {
+            (Some(keypair), _, _) => Some(keypair.clone()),
+            (None, Some(keypair), _) | (None, None, Some(keypair)) => {
+                Some(expand_home(SignerSource::from_str(&keypair)?, home_dir))
+            }
+            (None, None, None) => None,
+        };
+
+        Ok(Config {
+            rpc_url,
+            commitment,
+            signer: SignerArgs {
+                keypair,
+                ..send_args.signer
+            },
+            transaction: send_args.transaction,
+            output: global.output,
+            profile: profile_name,
+        })
+    }
+
+    /// The RPC URL, or an error telling how to set one.
+    pub fn rpc_url(&self) -> anyhow::Result<&str> {
+        self.rpc_url.as_deref().ok_or_else(|| {
+            anyhow::anyhow!(
+                "RPC URL not provided use --solana-rpc-url, set `SOLANA_RPC_URL` env variable or run `surfer config set rpc-url <URL>`"
+            )
+        })
+    }
+
+    /// An RPC client for the configured URL and commitment.
+    pub fn rpc_client(&self) -> anyhow::Result<RpcClient> {
+        Ok(RpcClient::new_with_commitment(
+            self.rpc_url()?.to_string(),
+            self.commitment,
+        ))
+    }
+}
+
+/// Expands cluster monikers to their public endpoints and validates the
+/// result as an http or https URL.
+pub fn parse_rpc_url(url: &str) -> anyhow::Result<String> {
+    let url = match url.trim() {
+        "localhost" | "l" => "http://localhost:8899",
+        "devnet" | "d" => "https://api.devnet.solana.com",
+        "testnet" | "t" => "https://api.testnet.solana.com",
+        "mainnet-beta" | "m" => "https://api.mainnet-beta.solana.com",
+        url => url,
+    };
+
+    let parsed =
+        Url::parse(url).map_err(|e| anyhow::anyhow!("Invalid RPC URL `{}`: {}", url, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host().is_none() {
+        return Err(anyhow::anyhow!(
+            "Invalid RPC URL `{}`, expected an http(s) URL or one of localhost, devnet, testnet, mainnet-beta",
+            url
+        ));
+    }
+
+    Ok(url.to_string())
+}
+
+/// Parses `processed`, `confirmed` or `finalized`.
+pub fn parse_commitment(commitment: &str) -> anyhow::Result<CommitmentConfig> {
+    CommitmentConfig::from_str(commitment).map_err(|_| {
+        anyhow::anyhow!(
+            "Invalid commitment `{}`, expected processed, confirmed or finalized",
+            commitment
+        )
+    })
+}
+
+fn home_dir() -> anyhow::Result<PathBuf> {
+    dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Failed to get home dir"))
+}
+
+/// Expands a leading `~/` in keypair file paths read from config files.
+fn expand_home(source: SignerSource, home_dir: &Path) -> SignerSource {
+    match source {
+        SignerSource::File(path) => match path.strip_prefix("~") {
+            Ok(rest) => SignerSource::File(home_dir.join(rest)),
+            Err(_) => SignerSource::File(path),
+        },
+        source => source,
+    }
+}
+
+/// Show and edit the surfer config file
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommands {
+    /// Show the resolved settings, or the value of a single key
+    Get(GetConfigArgs),
+    /// Set a key in the selected profile of the surfer config file
+    Set(SetConfigArgs),
+}
+
+/// Arguments of `surfer config get`.
+#[derive(Debug, Args)]
+pub struct GetConfigArgs {
+    #[clap(
+        value_enum,
+        help = "The key to print. Prints every setting if not provided."
+    )]
+    pub(crate) key: Option<ConfigKey>,
+}
+
+/// Arguments of `surfer config set`.
+#[derive(Debug, Args)]
+pub struct SetConfigArgs {
+    #[clap(value_enum, help = "The key to set.")]
+    pub(crate) key: ConfigKey,
+    #[clap(help = "The new value.")]
+    pub(crate) value: String,
+}
+
+/// A setting of the surfer config file.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ConfigKey {
+    /// RPC URL or cluster moniker of the profile
+    RpcUrl,
+    /// Wallet keypair of the profile
+    Keypair,
+    /// Commitment level of the profile
+    Commitment,
+    /// Profile used when --profile is not given
+    DefaultProfile,
+}
+
+impl ConfigCommands {
+    /// Unlike other commands this takes the raw [`GlobalArgs`], so `set`
+    /// works even when the selected profile does not exist yet.
+    pub fn process(&self, global: &GlobalArgs) -> anyhow::Result<()> {
+        let config_path = home_dir()?.join(SURFER_CONFIG_PATH);
+
+        match self {
+            ConfigCommands::Get(args) => {
+                let config = Config::resolve(global, None)?;
+                let surfer_config = SurferConfig::load(&config_path)?;
+                let keypair = config.signer.keypair.as_ref().map(|k| k.to_string());
+                let commitment = config.commitment.commitment.to_string();
+                let value = |key: ConfigKey| match key {
+                    ConfigKey::RpcUrl => config.rpc_url.clone(),
+                    ConfigKey::Keypair => keypair.clone(),
+                    ConfigKey::Commitment => Some(commitment.clone()),
+                    ConfigKey::DefaultProfile => surfer_config.default_profile.clone(),
+                };
+
+                if let Some(key) = args.key {
+                    println!("{}", value(key).unwrap_or_default());
+                    return Ok(());
+                }
+
+                match config.output {
+                    OutputFormat::Table => {
+                        let mut table = Table::new();
+                        table.set_format(*format::consts::FORMAT_CLEAN);
+                        table.add_row(row![
+                            utils::to_key_cell("Config File"),
+                            utils::to_value_cell(&config_path.display().to_string()),
+                        ]);
+                        table.add_row(row![
+                            utils::to_key_cell("Profile"),
+                            utils::to_value_cell(config.profile.as_deref().unwrap_or("None")),
+                        ]);
+                        for (name, key) in [
+                            ("RPC URL", ConfigKey::RpcUrl),
+                            ("Keypair", ConfigKey::Keypair),
+                            ("Commitment", ConfigKey::Commitment),
+                        ] {
+                            table.add_row(row![
+                                utils::to_key_cell(name),
+                                utils::to_value_cell(&value(key).unwrap_or("None".to_string())),
+                            ]);
+                        }
+                        table.printstd();
+                    }
+                    _ => output::print_json(
+                        &json!({
+                            "configFile": config_path.display().to_string(),
+                            "profile": config.profile,
+                            "rpcUrl": config.rpc_url,
+                            "keypair": keypair,
+                            "commitment": commitment,
+                        }),
+                        config.output,
+                    )?,
+                }
+            }
+            ConfigCommands::Set(args) => {
+                let mut surfer_config = SurferConfig::load(&config_path)?;
+                let profile_name = global
+                    .profile
+                    .clone()
+                    .or(surfer_config.default_profile.clone())
+                    .unwrap_or(DEFAULT_PROFILE.to_string());
+
+                surfer_config.set(&profile_name, &args.key, &args.value)?;
+                surfer_config.save(&config_path)?;
+
+                let key = args
+                    .key
+                    .to_possible_value()
+                    .map(|v| v.get_name().to_string());
+                match global.output {
+                    OutputFormat::Table => println!(
+                        "{} {} = {} ({})",
+                        "Set".purple().bold(),
+                        key.unwrap_or_default().bold(),
+                        args.value.cyan().bold(),
+                        config_path.display()
+                    ),
+                    _ => output::print_json(
+                        &json!({
+                            "key": key,
+                            "value": args.value,
+                            "profile": profile_name,
+                        }),
+                        global.output,
+                    )?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::pubkey::Pubkey;
+
+    use super::*;
+
+    /// A fresh home dir holding the given surfer and Solana CLI configs.
+    fn home_with(surfer_config: Option<&str>, solana_config: Option<&str>) -> PathBuf {
+        let home_dir = std::env::temp_dir().join(format!("surfer-test-{}", Pubkey::new_unique()));
+        for (path, contents) in [
+            (SURFER_CONFIG_PATH, surfer_config),
+            (SOLANA_CLI_CONFIG_PATH, solana_config),
+        ] {
+            if let Some(contents) = contents {
+                let path = home_dir.join(path);
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(path, contents).unwrap();
+            }
+        }
+
+        home_dir
+    }
+
+    fn resolve(
+        global: &GlobalArgs,
+        send_args: Option<&SendArgs>,
+        home_dir: &Path,
+    ) -> anyhow::Result<Config> {
+        let config = Config::resolve_in(global, send_args, home_dir);
+        fs::remove_dir_all(home_dir).ok();
+        config
+    }
+
+    fn global(profile: Option<&str>) -> GlobalArgs {
+        GlobalArgs {
+            solana_rpc_url: None,
+            commitment: None,
+            profile: profile.map(str::to_string),
+            output: OutputFormat::Table,
+        }
+    }
+
+    const SURFER_CONFIG: &str = r#"
+default_profile = "dev"
+
+[profiles.dev]
+rpc_url = "d"
+keypair = "~/keys/dev.json"
+
+[profiles.local]
+rpc_url = "l"
+commitment = "finalized"
+"#;
+
+    const SOLANA_CONFIG: &str = r#"
+json_rpc_url: "https://api.testnet.solana.com"
+keypair_path: /keys/solana.json
+commitment: processed
+"#;
+
+    #[test]
+    fn flags_override_profile_and_solana_config() {
+        let keypair = SignerSource::File(PathBuf::from("/keys/flag.json"));
+        let mut send = SendArgs::default();
+        send.signer.keypair = Some(keypair.clone());
+        let global = GlobalArgs {
+            solana_rpc_url: Some("http://127.0.0.1:8899".to_string()),
+            commitment: Some(CommitmentConfig::finalized()),
+            ..global(None)
+        };
+
+        let home_dir = home_with(Some(SURFER_CONFIG), Some(SOLANA_CONFIG));
+        let config = resolve(&global, Some(&send), &home_dir).unwrap();
+        assert_eq!(config.rpc_url.as_deref(), Some("http://127.0.0.1:8899"));
+        assert_eq!(config.commitment, CommitmentConfig::finalized());
+        assert_eq!(config.signer.keypair, Some(keypair));
+        assert_eq!(config.profile.as_deref(), Some("dev"));
+    }
+
+    #[test]
+    fn profile_overrides_solana_config() {
+        let home_dir = home_with(Some(SURFER_CONFIG), Some(SOLANA_CONFIG));
+        let config = resolve(&global(None), None, &home_dir).unwrap();
+        assert_eq!(
+            config.rpc_url.as_deref(),
+            Some("https://api.devnet.solana.com")
+        );
+        assert_eq!(
+            config.signer.keypair,
+            Some(SignerSource::File(home_dir.join("keys/dev.json")))
+        );
+        // The default profile sets no commitment, so the Solana config's applies.
+        assert_eq!(config.commitment, CommitmentConfig::processed());
+
+        let home_dir = home_with(Some(SURFER_CONFIG), Some(SOLANA_CONFIG));
+        let config = resolve(&global(Some("local")), None, &home_dir).unwrap();
+        assert_eq!(config.rpc_url.as_deref(), Some("http://localhost:8899"));
+        assert_eq!(config.commitment, CommitmentConfig::finalized());
+        assert_eq!(
+            config.signer.keypair,
+            Some(SignerSource::File(PathBuf::from("/keys/solana.json")))
+        );
+    }
+
+    #[test]
+    fn falls_back_to_solana_config_and_defaults() {
+        let config = resolve(&global(None), None, &home_with(None, Some(SOLANA_CONFIG))).unwrap();
+        assert_eq!(
+            config.rpc_url.as_deref(),
+            Some("https://api.testnet.solana.com")
+        );
+        assert_eq!(config.commitment, CommitmentConfig::processed());
+        assert_eq!(config.profile, None);
+
+        let config = resolve(&global(None), None, &home_with(None, None)).unwrap();
+        assert_eq!(config.rpc_url, None);
+        assert_eq!(config.commitment, CommitmentConfig::confirmed());
+        assert_eq!(config.signer.keypair, None);
+    }
+
+    #[test]
+    fn missing_profile_is_an_error() {
+        let err = resolve(
+            &global(Some("prod")),
+            None,
+            &home_with(Some(SURFER_CONFIG), None),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Profile `prod` not found in ~/.config/surfer/config.toml, available profiles: dev, local"
+        );
+
+        let err = resolve(&global(Some("prod")), None, &home_with(None, None)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Profile `prod` not found, ~/.config/surfer/config.toml has no profiles"
+        );
+    }
+
+    #[test]
+    fn default_profile_must_exist() {
+        let mut surfer_config = SurferConfig::default();
+        let err = surfer_config
+            .set("dev", &ConfigKey::DefaultProfile, "prod")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Profile `prod` not found, ~/.config/surfer/config.toml has no profiles"
+        );
+
+        surfer_config
+            .set("dev", &ConfigKey::RpcUrl, "devnet")
+            .unwrap();
+        surfer_config
+            .set("local", &ConfigKey::RpcUrl, "localhost")
+            .unwrap();
+        let err = surfer_config
+            .set("dev", &ConfigKey::DefaultProfile, "prod")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Profile `prod` not found in ~/.config/surfer/config.toml, available profiles: dev, local"
+        );
+        assert_eq!(surfer_config.default_profile.as_deref(), Some("dev"));
+
+        surfer_config
+            .set("dev", &ConfigKey::DefaultProfile, "local")
+            .unwrap();
+        assert_eq!(surfer_config.default_profile.as_deref(), Some("local"));
+    }
+
+    #[test]
+    fn expands_cluster_monikers() {
+        for (monikers, url) in [
+            (["l", "localhost"], "http://localhost:8899"),
+            (["d", "devnet"], "https://api.devnet.solana.com"),
+            (["t", "testnet"], "https://api.testnet.solana.com"),
+            (["m", "mainnet-beta"], "https://api.mainnet-beta.solana.com"),
+        ] {
+            for moniker in monikers {
+                assert_eq!(parse_rpc_url(moniker).unwrap(), url);
+            }
+        }
+    }
+
+    #[test]
+    fn accepts_http_urls() {
+        assert_eq!(
+            parse_rpc_url("http://127.0.0.1:8899").unwrap(),
+            "http://127.0.0.1:8899"
+        );
+        assert_eq!(
+            parse_rpc_url(" https://rpc.example.com/key ").unwrap(),
+            "https://rpc.example.com/key"
+        );
+    }
+
+    #[test]
+    fn rejects_other_urls_and_garbage() {
+        let expected = |url: &str| {
+            format!(
+                "Invalid RPC URL `{}`, expected an http(s) URL or one of localhost, devnet, testnet, mainnet-beta",
+                url
+            )
+        };
+        assert_eq!(
+            parse_rpc_url("ftp://example.com").unwrap_err().to_string(),
+            expected("ftp://example.com")
+        );
+        assert_eq!(
+            parse_rpc_url("unix:/var/run/rpc.sock")
+                .unwrap_err()
+                .to_string(),
+            expected("unix:/var/run/rpc.sock")
+        );
+        assert_eq!(
+            parse_rpc_url("http://").unwrap_err().to_string(),
+            "Invalid RPC URL `http://`: empty host"
+        );
+        assert_eq!(
+            parse_rpc_url("not a url").unwrap_err().to_string(),
+            "Invalid RPC URL `not a url`: relative URL without a base"
+        );
+    }
+}
@@ -1,17 +1,26 @@
+use std::{collections::HashMap, str::FromStr};
+
 use bytemuck::Pod;
-use spl_pod::bytemuck::pod_from_bytes;
+use colored::*;
+use prettytable::{Cell, Row, Table};
+use serde_json::json;
+use spl_pod::{bytemuck::pod_from_bytes, optional_keys::OptionalNonZeroPubkey};
 use spl_token::solana_program::program_pack::Pack;
 use spl_token_2022::{
     extension::{
-        self, BaseState, BaseStateWithExtensions, Extension, ExtensionType, StateWithExtensions,
+        self, transfer_fee::TransferFee, BaseState, BaseStateWithExtensions, Extension,
+        ExtensionType, StateWithExtensions,
     },
-    solana_program::program_error::ProgramError,
-    state::{Account, Mint},
+    solana_program::{instruction::Instruction, program_error::ProgramError, pubkey::Pubkey},
+    solana_zk_sdk::encryption::pod::elgamal::PodElGamalPubkey,
+    state::{Account, AccountState, Mint},
 };
 use spl_token_group_interface::state::{TokenGroup, TokenGroupMember};
 use spl_token_metadata_interface::state::TokenMetadata;
 use spl_type_length_value::variable_len_pack::VariableLenPack;
 
+use crate::{output::UiAmount, utils};
+
 fn get_extension_data_bytes<'data, T: BaseState + Pack>(
     state_with_ex: &'data StateWithExtensions<T>,
     extension_type: ExtensionType,
@@ -47,6 +56,7 @@ fn get_extension_data_bytes<'data, T: BaseState + Pack>(
     Ok(extension_data)
 }
 
+/// The raw bytes of the `extension_type` extension of a token account.
 pub fn token_account_extensions_data_bytes<'data>(
     state_with_ex: &'data StateWithExtensions<Account>,
     extension_type: ExtensionType,
@@ -54,6 +64,7 @@ pub fn token_account_extensions_data_bytes<'data>(
     get_extension_data_bytes(state_with_ex, extension_type)
 }
 
+/// The raw bytes of the `extension_type` extension of a mint.
 pub fn mint_account_extensions_data_bytes<'data>(
     state_with_ex: &'data StateWithExtensions<Mint>,
     extension_type: ExtensionType,
@@ -61,45 +72,72 @@ pub fn mint_account_extensions_data_bytes<'data>(
     get_extension_data_bytes(state_with_ex, extension_type)
 }
 
+/// Decodes a fixed size extension from its raw bytes.
 pub fn parse_extension_data<E: Extension + Pod>(data_bytes: &[u8]) -> Result<E, ProgramError> {
     let extension = pod_from_bytes::<E>(data_bytes)?;
     Ok(extension.to_owned())
 }
 
+/// Decodes the variable size `TokenMetadata` extension from its raw bytes.
 pub fn parse_token_metadata_extension(data_bytes: &[u8]) -> Result<TokenMetadata, ProgramError> {
     let token_metadata = TokenMetadata::unpack_from_slice(data_bytes)?;
     Ok(token_metadata.clone())
 }
 
+/// A decoded Token-2022 mint or account extension.
 #[derive(Debug, PartialEq, Clone)]
 pub enum ExtensionData {
+    /// Account: the owner can never be changed.
     ImmutableOwner(extension::immutable_owner::ImmutableOwner),
+    /// Account: transfer fees withheld in the account.
     TransferFeeAmount(extension::transfer_fee::TransferFeeAmount),
+    /// Account: encrypted balances for confidential transfers.
     ConfidentialTransferAccount(extension::confidential_transfer::ConfidentialTransferAccount),
+    /// Account: whether incoming transfers need a memo.
     MemoTransfer(extension::memo_transfer::MemoTransfer),
+    /// Account: marks an account of a non-transferable mint.
     NonTransferableAccount(extension::non_transferable::NonTransferableAccount),
+    /// Account: transfer hook state of the account.
     TransferHookAccount(extension::transfer_hook::TransferHookAccount),
+    /// Account: whether the owner's privileged actions are blocked in CPIs.
     CpiGuard(extension::cpi_guard::CpiGuard),
+    /// Account: encrypted withheld confidential transfer fees.
     ConfidentialTransferFeeAmount(
         extension::confidential_transfer_fee::ConfidentialTransferFeeAmount,
     ),
+    /// Mint: transfer fee rates and their authorities.
     TransferFeeConfig(extension::transfer_fee::TransferFeeConfig),
+    /// Mint: who can close the mint.
     MintCloseAuthority(extension::mint_close_authority::MintCloseAuthority),
+    /// Mint: confidential transfer settings.
     ConfidentialTransferMint(extension::confidential_transfer::ConfidentialTransferMint),
+    /// Mint: state new token accounts start in.
     DefaultAccountState(extension::default_account_state::DefaultAccountState),
+    /// Mint: tokens can't be transferred.
     NonTransferable(extension::non_transferable::NonTransferable),
+    /// Mint: interest rate UI amounts accrue at.
     InterestBearingConfig(extension::interest_bearing_mint::InterestBearingConfig),
+    /// Mint: delegate allowed to transfer or burn from any account.
     PermanentDelegate(extension::permanent_delegate::PermanentDelegate),
+    /// Mint: program invoked on every transfer.
     TransferHook(extension::transfer_hook::TransferHook),
+    /// Mint: confidential transfer fee settings.
     ConfidentialTransferFeeConfig(
         extension::confidential_transfer_fee::ConfidentialTransferFeeConfig,
     ),
+    /// Mint: where the token metadata lives.
     MetadataPointer(extension::metadata_pointer::MetadataPointer),
+    /// Mint: token metadata stored in the mint itself.
     TokenMetadata(TokenMetadata),
+    /// Mint: where the group configuration lives.
     GroupPointer(extension::group_pointer::GroupPointer),
+    /// Mint: group configuration stored in the mint itself.
     TokenGroup(TokenGroup),
+    /// Mint: where the group membership lives.
     GroupMemberPointer(extension::group_member_pointer::GroupMemberPointer),
+    /// Mint: group membership stored in the mint itself.
     TokenGroupMember(TokenGroupMember),
+    /// Mint: confidential mint and burn settings.
     ConfidentialMintBurn(extension::confidential_mint_burn::ConfidentialMintBurn),
 }
 
@@ -185,3 +223,1125 @@ impl TryFrom<(ExtensionType, &[u8])> for ExtensionData {
         }
     }
 }
+
+/// A mint extension together with the parameters needed to initialize it.
+///
+/// Parsed from `<ExtensionType>[:key=value,...]`, e.g.
+/// `TransferFeeConfig:basis-points=50,maximum-fee=5000`. Authorities left out
+/// default to the mint authority and addresses default to the mint itself.
+#[derive(Debug, Clone)]
+pub enum MintExtensionInit {
+    /// Charge a fee on every transfer.
+    TransferFeeConfig {
+        /// Who can change the fee.
+        authority: Option<Pubkey>,
+        /// Who can withdraw withheld fees.
+        withdraw_withheld_authority: Option<Pubkey>,
+        /// Fee in basis points of the transferred amount.
+        basis_points: u16,
+        /// Fee cap in base units.
+        maximum_fee: u64,
+    },
+    /// Allow closing the mint once its supply is zero.
+    MintCloseAuthority {
+        /// Who can close the mint.
+        authority: Option<Pubkey>,
+    },
+    /// Create new token accounts initialized or frozen.
+    DefaultAccountState {
+        /// State of new token accounts.
+        state: AccountState,
+    },
+    /// Make the tokens non-transferable.
+    NonTransferable,
+    /// Accrue interest on UI amounts.
+    InterestBearingConfig {
+        /// Who can change the rate.
+        authority: Option<Pubkey>,
+        /// Rate in basis points per year.
+        rate: i16,
+    },
+    /// Let a delegate transfer or burn from any token account.
+    PermanentDelegate {
+        /// The delegate.
+        delegate: Option<Pubkey>,
+    },
+    /// Invoke a program on every transfer.
+    TransferHook {
+        /// Who can change the program.
+        authority: Option<Pubkey>,
+        /// The program to invoke.
+        program_id: Option<Pubkey>,
+    },
+    /// Point to the account holding the token metadata.
+    MetadataPointer {
+        /// Who can change the pointer.
+        authority: Option<Pubkey>,
+        /// The metadata account.
+        address: Option<Pubkey>,
+    },
+    /// Point to the account holding the group configuration.
+    GroupPointer {
+        /// Who can change the pointer.
+        authority: Option<Pubkey>,
+        /// The group account.
+        address: Option<Pubkey>,
+    },
+    /// Point to the account holding the group membership.
+    GroupMemberPointer {
+        /// Who can change the pointer.
+        authority: Option<Pubkey>,
+        /// The group member account.
+        address: Option<Pubkey>,
+    },
+}
+
+impl MintExtensionInit {
+    /// The extension this initializes.
+    pub fn extension_type(&self) -> ExtensionType {
+        match self {
+            MintExtensionInit::TransferFeeConfig { .. } => ExtensionType::TransferFeeConfig,
+            MintExtensionInit::MintCloseAuthority { .. } => ExtensionType::MintCloseAuthority,
+            MintExtensionInit::DefaultAccountState { .. } => ExtensionType::DefaultAccountState,
+            MintExtensionInit::NonTransferable => ExtensionType::NonTransferable,
+            MintExtensionInit::InterestBearingConfig { .. } => ExtensionType::InterestBearingConfig,
+            MintExtensionInit::PermanentDelegate { .. } => ExtensionType::PermanentDelegate,
+            MintExtensionInit::TransferHook { .. } => ExtensionType::TransferHook,
+            MintExtensionInit::MetadataPointer { .. } => ExtensionType::MetadataPointer,
+            MintExtensionInit::GroupPointer { .. } => ExtensionType::GroupPointer,
+            MintExtensionInit::GroupMemberPointer { .. } => ExtensionType::GroupMemberPointer,
+        }
+    }
+
+    /// Builds the instruction initializing this extension. It must be sent
+    /// after the mint account is allocated and before `InitializeMint`.
+    pub fn init_instruction(
+        &self,
+        program_id: &Pubkey,
+        mint: &Pubkey,
+        mint_authority: &Pubkey,
+    ) -> Result<Instruction, ProgramError> {
+        match self {
+            MintExtensionInit::TransferFeeConfig {
+                authority,
+                withdraw_withheld_authority,
+                basis_points,
+                maximum_fee,
+            } => extension::transfer_fee::instruction::initialize_transfer_fee_config(
+                program_id,
+                mint,
+                Some(authority.as_ref().unwrap_or(mint_authority)),
+                Some(
+                    withdraw_withheld_authority
+                        .as_ref()
+                        .unwrap_or(mint_authority),
+                ),
+                *basis_points,
+                *maximum_fee,
+            ),
+            MintExtensionInit::MintCloseAuthority { authority } => {
+                spl_token_2022::instruction::initialize_mint_close_authority(
+                    program_id,
+                    mint,
+                    Some(authority.as_ref().unwrap_or(mint_authority)),
+                )
+            }
+            MintExtensionInit::DefaultAccountState { state } => {
+                extension::default_account_state::instruction::initialize_default_account_state(
+                    program_id, mint, state,
+                )
+            }
+            MintExtensionInit::NonTransferable => {
+                spl_token_2022::instruction::initialize_non_transferable_mint(program_id, mint)
+            }
+            MintExtensionInit::InterestBearingConfig { authority, rate } => {
+                extension::interest_bearing_mint::instruction::initialize(
+                    program_id,
+                    mint,
+                    Some(authority.unwrap_or(*mint_authority)),
+                    *rate,
+                )
+            }
+            MintExtensionInit::PermanentDelegate { delegate } => {
+                spl_token_2022::instruction::initialize_permanent_delegate(
+                    program_id,
+                    mint,
+                    delegate.as_ref().unwrap_or(mint_authority),
+                )
+            }
+            MintExtensionInit::TransferHook {
+                authority,
+                program_id: hook_program_id,
+            } => extension::transfer_hook::instruction::initialize(
+                program_id,
+                mint,
+                Some(authority.unwrap_or(*mint_authority)),
+                *hook_program_id,
+            ),
+            MintExtensionInit::MetadataPointer { authority, address } => {
+                extension::metadata_pointer::instruction::initialize(
+                    program_id,
+                    mint,
+                    Some(authority.unwrap_or(*mint_authority)),
+                    Some(address.unwrap_or(*mint)),
+                )
+            }
+            MintExtensionInit::GroupPointer { authority, address } => {
+                extension::group_pointer::instruction::initialize(
+                    program_id,
+                    mint,
+                    Some(authority.unwrap_or(*mint_authority)),
+                    Some(address.unwrap_or(*mint)),
+                )
+            }
+            MintExtensionInit::GroupMemberPointer { authority, address } => {
+                extension::group_member_pointer::instruction::initialize(
+                    program_id,
+                    mint,
+                    Some(authority.unwrap_or(*mint_authority)),
+                    Some(address.unwrap_or(*mint)),
+                )
+            }
+        }
+    }
+}
+
+impl FromStr for MintExtensionInit {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let (name, params) = s.split_once(':').unwrap_or((s, ""));
+        let mut params = params
+            .split(',')
+            .filter(|p| !p.is_empty())
+            .map(|p| {
+                p.split_once('=')
+                    .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+                    .ok_or_else(|| anyhow::anyhow!("Invalid extension parameter: {}", p))
+            })
+            .try_fold(HashMap::new(), |mut params, param| {
+                let (key, value) = param?;
+                if params.contains_key(&key) {
+                    return Err(anyhow::anyhow!("Parameter `{}` given more than once", key));
+                }
+                params.insert(key, value);
+                Ok(params)
+            })?;
+
+        let extension = match extension_type_from_str(name.trim())? {
+            ExtensionType::TransferFeeConfig => MintExtensionInit::TransferFeeConfig {
+                authority: take_pubkey_param(&mut params, "authority")?,
+                withdraw_withheld_authority: take_pubkey_param(
+                    &mut params,
+                    "withdraw-withheld-authority",
+                )?,
+                basis_points: take_required_param(&mut params, "basis-points")?,
+                maximum_fee: take_required_param(&mut params, "maximum-fee")?,
+            },
+            ExtensionType::MintCloseAuthority => MintExtensionInit::MintCloseAuthority {
+                authority: take_pubkey_param(&mut params, "authority")?,
+            },
+            ExtensionType::DefaultAccountState => MintExtensionInit::DefaultAccountState {
+                state: match params.remove("state").as_deref() {
+                    Some("initialized") => AccountState::Initialized,
+                    Some("frozen") => AccountState::Frozen,
+                    Some(state) => {
+                        return Err(anyhow::anyhow!("Invalid default account state: {}", state))
+                    }
+                    None => {
+                        return Err(anyhow::anyhow!(
+                            "DefaultAccountState requires `state=initialized|frozen`"
+                        ))
+                    }
+                },
+            },
+            ExtensionType::NonTransferable => MintExtensionInit::NonTransferable,
+            ExtensionType::InterestBearingConfig => MintExtensionInit::InterestBearingConfig {
+                authority: take_pubkey_param(&mut params, "authority")?,
+                rate: take_required_param(&mut params, "rate")?,
+            },
+            ExtensionType::PermanentDelegate => MintExtensionInit::PermanentDelegate {
+                delegate: take_pubkey_param(&mut params, "delegate")?,
+            },
+            ExtensionType::TransferHook => MintExtensionInit::TransferHook {
+                authority: take_pubkey_param(&mut params, "authority")?,
+                program_id: take_pubkey_param(&mut params, "program-id")?,
+            },
+            ExtensionType::MetadataPointer => MintExtensionInit::MetadataPointer {
+                authority: take_pubkey_param(&mut params, "authority")?,
+                address: take_pubkey_param(&mut params, "address")?,
+            },
+            ExtensionType::GroupPointer => MintExtensionInit::GroupPointer {
+                authority: take_pubkey_param(&mut params, "authority")?,
+                address: take_pubkey_param(&mut params, "address")?,
+            },
+            ExtensionType::GroupMemberPointer => MintExtensionInit::GroupMemberPointer {
+                authority: take_pubkey_param(&mut params, "authority")?,
+                address: take_pubkey_param(&mut params, "address")?,
+            },
+            extension_type => {
+                return Err(anyhow::anyhow!(
+                    "{:?} cannot be initialized when creating a mint",
+                    extension_type
+                ))
+            }
+        };
+
+        if let Some(key) = params.keys().next() {
+            return Err(anyhow::anyhow!(
+                "Unknown parameter `{}` for {:?}",
+                key,
+                extension.extension_type()
+            ));
+        }
+
+        Ok(extension)
+    }
+}
+
+/// Checks the extensions of a new mint and returns their types. Each may be
+/// given once, and a frozen default account state needs a freeze authority
+/// or the mint's accounts could never be thawed.
+pub fn check_mint_extensions(
+    extensions: &[MintExtensionInit],
+    freeze_authority: Option<&Pubkey>,
+) -> anyhow::Result<Vec<ExtensionType>> {
+    let extension_types = extensions
+        .iter()
+        .map(|e| e.extension_type())
+        .collect::<Vec<_>>();
+    if let Some(duplicate) = extension_types
+        .iter()
+        .enumerate()
+        .find_map(|(i, e)| extension_types[..i].contains(e).then_some(e))
+    {
+        return Err(anyhow::anyhow!(
+            "Extension {:?} given more than once",
+            duplicate
+        ));
+    }
+
+    let frozen_by_default = extensions.iter().any(|e| {
+        matches!(
+            e,
+            MintExtensionInit::DefaultAccountState {
+                state: AccountState::Frozen
+            }
+        )
+    });
+    if frozen_by_default && freeze_authority.is_none() {
+        return Err(anyhow::anyhow!(
+            "DefaultAccountState:state=frozen requires --freeze-authority, accounts could never be thawed otherwise"
+        ));
+    }
+
+    Ok(extension_types)
+}
+
+/// Parses an extension type by its name, e.g. `TransferFeeConfig`.
+pub fn extension_type_from_str(name: &str) -> anyhow::Result<ExtensionType> {
+    ExtensionData::extension_types()
+        .find(|extension_type| format!("{:?}", extension_type) == name)
+        .ok_or_else(|| anyhow::anyhow!("Invalid extension type: {}", name))
+}
+
+fn take_pubkey_param(
+    params: &mut HashMap<String, String>,
+    key: &str,
+) -> anyhow::Result<Option<Pubkey>> {
+    params
+        .remove(key)
+        .map(|v| {
+            Pubkey::from_str(&v).map_err(|e| anyhow::anyhow!("Invalid pubkey for `{}`: {}", key, e))
+        })
+        .transpose()
+}
+
+fn take_required_param<T: FromStr>(
+    params: &mut HashMap<String, String>,
+    key: &str,
+) -> anyhow::Result<T>
+where
+    T::Err: std::fmt::Display,
+{
+    let value = params
+        .remove(key)
+        .ok_or_else(|| anyhow::anyhow!("Missing required parameter `{}`", key))?;
+
+    value
+        .parse::<T>()
+        .map_err(|e| anyhow::anyhow!("Invalid value for `{}`: {}", key, e))
+}
+
+fn optional_pubkey_to_json(pubkey: &OptionalNonZeroPubkey) -> serde_json::Value {
+    Option::<Pubkey>::from(*pubkey).map_or(serde_json::Value::Null, |pk| pk.to_string().into())
+}
+
+fn transfer_fee_to_json(fee: &TransferFee, decimals: u8) -> serde_json::Value {
+    json!({
+        "epoch": u64::from(fee.epoch),
+        "maximumFee": UiAmount::new(fee.maximum_fee.into(), decimals),
+        "transferFeeBasisPoints": u16::from(fee.transfer_fee_basis_points),
+    })
+}
+
+impl ExtensionData {
+    /// Every extension type there is a variant for, which is all of them but
+    /// `Uninitialized`.
+    pub fn extension_types() -> impl Iterator<Item = ExtensionType> {
+        (1..).map_while(|n: u16| ExtensionType::try_from(n).ok())
+    }
+
+    /// The extension type of the decoded extension.
+    pub fn extension_type(&self) -> ExtensionType {
+        match self {
+            ExtensionData::ImmutableOwner(_) => ExtensionType::ImmutableOwner,
+            ExtensionData::TransferFeeAmount(_) => ExtensionType::TransferFeeAmount,
+            ExtensionData::ConfidentialTransferAccount(_) => {
+                ExtensionType::ConfidentialTransferAccount
+            }
+            ExtensionData::MemoTransfer(_) => ExtensionType::MemoTransfer,
+            ExtensionData::NonTransferableAccount(_) => ExtensionType::NonTransferableAccount,
+            ExtensionData::TransferHookAccount(_) => ExtensionType::TransferHookAccount,
+            ExtensionData::CpiGuard(_) => ExtensionType::CpiGuard,
+            ExtensionData::ConfidentialTransferFeeAmount(_) => {
+                ExtensionType::ConfidentialTransferFeeAmount
+            }
+            ExtensionData::TransferFeeConfig(_) => ExtensionType::TransferFeeConfig,
+            ExtensionData::MintCloseAuthority(_) => ExtensionType::MintCloseAuthority,
+            ExtensionData::ConfidentialTransferMint(_) => ExtensionType::ConfidentialTransferMint,
+            ExtensionData::DefaultAccountState(_) => ExtensionType::DefaultAccountState,
+            ExtensionData::NonTransferable(_) => ExtensionType::NonTransferable,
+            ExtensionData::InterestBearingConfig(_) => ExtensionType::InterestBearingConfig,
+            ExtensionData::PermanentDelegate(_) => ExtensionType::PermanentDelegate,
+            ExtensionData::TransferHook(_) => ExtensionType::TransferHook,
+            ExtensionData::ConfidentialTransferFeeConfig(_) => {
+                ExtensionType::ConfidentialTransferFeeConfig
+            }
+            ExtensionData::MetadataPointer(_) => ExtensionType::MetadataPointer,
+            ExtensionData::TokenMetadata(_) => ExtensionType::TokenMetadata,
+            ExtensionData::GroupPointer(_) => ExtensionType::GroupPointer,
+            ExtensionData::TokenGroup(_) => ExtensionType::TokenGroup,
+            ExtensionData::GroupMemberPointer(_) => ExtensionType::GroupMemberPointer,
+            ExtensionData::TokenGroupMember(_) => ExtensionType::TokenGroupMember,
+            ExtensionData::ConfidentialMintBurn(_) => ExtensionType::ConfidentialMintBurn,
+        }
+    }
+
+    /// Serializes the decoded extension as
+    /// `{ "extension": "<ExtensionType>", "state": { ... } }`.
+    ///
+    /// `decimals` are those of the mint and are used for token amounts.
+    pub fn to_json(&self, decimals: u8) -> serde_json::Value {
+        let state = match self {
+            ExtensionData::ImmutableOwner(_)
+            | ExtensionData::NonTransferableAccount(_)
+            | ExtensionData::NonTransferable(_) => json!({}),
+            ExtensionData::TransferFeeAmount(ext) => json!({
+                "withheldAmount": UiAmount::new(ext.withheld_amount.into(), decimals),
+            }),
+            ExtensionData::ConfidentialTransferAccount(ext) => json!({
+                "approved": bool::from(ext.approved),
+                "elgamalPubkey": ext.elgamal_pubkey.to_string(),
+                "pendingBalanceLo": ext.pending_balance_lo.to_string(),
+                "pendingBalanceHi": ext.pending_balance_hi.to_string(),
+                "availableBalance": ext.available_balance.to_string(),
+                "decryptableAvailableBalance": ext.decryptable_available_balance.to_string(),
+                "allowConfidentialCredits": bool::from(ext.allow_confidential_credits),
+                "allowNonConfidentialCredits": bool::from(ext.allow_non_confidential_credits),
+                "pendingBalanceCreditCounter": u64::from(ext.pending_balance_credit_counter),
+                "maximumPendingBalanceCreditCounter":
+                    u64::from(ext.maximum_pending_balance_credit_counter),
+                "expectedPendingBalanceCreditCounter":
+                    u64::from(ext.expected_pending_balance_credit_counter),
+                "actualPendingBalanceCreditCounter":
+                    u64::from(ext.actual_pending_balance_credit_counter),
+            }),
+            ExtensionData::MemoTransfer(ext) => json!({
+                "requireIncomingTransferMemos": bool::from(ext.require_incoming_transfer_memos),
+            }),
+            ExtensionData::TransferHookAccount(ext) => json!({
+                "transferring": bool::from(ext.transferring),
+            }),
+            ExtensionData::CpiGuard(ext) => json!({
+                "lockCpi": bool::from(ext.lock_cpi),
+            }),
+            ExtensionData::ConfidentialTransferFeeAmount(ext) => json!({
+                "withheldAmount": ext.withheld_amount.to_string(),
+            }),
+            ExtensionData::TransferFeeConfig(ext) => json!({
+                "transferFeeConfigAuthority":
+                    optional_pubkey_to_json(&ext.transfer_fee_config_authority),
+                "withdrawWithheldAuthority":
+                    optional_pubkey_to_json(&ext.withdraw_withheld_authority),
+                "withheldAmount": UiAmount::new(ext.withheld_amount.into(), decimals),
+                "olderTransferFee": transfer_fee_to_json(&ext.older_transfer_fee, decimals),
+                "newerTransferFee": transfer_fee_to_json(&ext.newer_transfer_fee, decimals),
+            }),
+            ExtensionData::MintCloseAuthority(ext) => json!({
+                "closeAuthority": optional_pubkey_to_json(&ext.close_authority),
+            }),
+            ExtensionData::ConfidentialTransferMint(ext) => json!({
+                "authority": optional_pubkey_to_json(&ext.authority),
+                "autoApproveNewAccounts": bool::from(ext.auto_approve_new_accounts),
+                "auditorElgamalPubkey": Option::<PodElGamalPubkey>::from(ext.auditor_elgamal_pubkey)
+                    .map(|pk| pk.to_string()),
+            }),
+            ExtensionData::DefaultAccountState(ext) => json!({
+                "accountState": AccountState::try_from(ext.state)
+                    .map_or("Unknown".to_string(), |state| format!("{:?}", state)),
+            }),
+            ExtensionData::InterestBearingConfig(ext) => json!({
+                "rateAuthority": optional_pubkey_to_json(&ext.rate_authority),
+                "initializationTimestamp": i64::from(ext.initialization_timestamp),
+                "preUpdateAverageRate": i16::from(ext.pre_update_average_rate),
+                "lastUpdateTimestamp": i64::from(ext.last_update_timestamp),
+                "currentRate": i16::from(ext.current_rate),
+            }),
+            ExtensionData::PermanentDelegate(ext) => json!({
+                "delegate": optional_pubkey_to_json(&ext.delegate),
+            }),
+            ExtensionData::TransferHook(ext) => json!({
+                "authority": optional_pubkey_to_json(&ext.authority),
+                "programId": optional_pubkey_to_json(&ext.program_id),
+            }),
+            ExtensionData::ConfidentialTransferFeeConfig(ext) => json!({
+                "authority": optional_pubkey_to_json(&ext.authority),
+                "withdrawWithheldAuthorityElgamalPubkey":
+                    ext.withdraw_withheld_authority_elgamal_pubkey.to_string(),
+                "harvestToMintEnabled": bool::from(ext.harvest_to_mint_enabled),
+                "withheldAmount": ext.withheld_amount.to_string(),
+            }),
+            ExtensionData::MetadataPointer(ext) => json!({
+                "authority": optional_pubkey_to_json(&ext.authority),
+                "metadataAddress": optional_pubkey_to_json(&ext.metadata_address),
+            }),
+            ExtensionData::TokenMetadata(ext) => json!({
+                "updateAuthority": optional_pubkey_to_json(&ext.update_authority),
+                "mint": ext.mint.to_string(),
+                "name": ext.name,
+                "symbol": ext.symbol,
+                "uri": ext.uri,
+                "additionalMetadata": ext
+                    .additional_metadata
+                    .iter()
+                    .map(|(key, value)| json!({ "key": key, "value": value }))
+                    .collect::<Vec<_>>(),
+            }),
+            ExtensionData::GroupPointer(ext) => json!({
+                "authority": optional_pubkey_to_json(&ext.authority),
+                "groupAddress": optional_pubkey_to_json(&ext.group_address),
+            }),
+            ExtensionData::TokenGroup(ext) => json!({
+                "updateAuthority": optional_pubkey_to_json(&ext.update_authority),
+                "mint": ext.mint.to_string(),
+                "size": u64::from(ext.size),
+                "maxSize": u64::from(ext.max_size),
+            }),
+            ExtensionData::GroupMemberPointer(ext) => json!({
+                "authority": optional_pubkey_to_json(&ext.authority),
+                "memberAddress": optional_pubkey_to_json(&ext.member_address),
+            }),
+            ExtensionData::TokenGroupMember(ext) => json!({
+                "mint": ext.mint.to_string(),
+                "group": ext.group.to_string(),
+                "memberNumber": u64::from(ext.member_number),
+            }),
+            ExtensionData::ConfidentialMintBurn(ext) => json!({
+                "confidentialSupply": ext.confidential_supply.to_string(),
+                "decryptableSupply": ext.decryptable_supply.to_string(),
+                "supplyElgamalPubkey": ext.supply_elgamal_pubkey.to_string(),
+            }),
+        };
+
+        json!({
+            "extension": format!("{:?}", self.extension_type()),
+            "state": state,
+        })
+    }
+}
+
+fn optional_pubkey_to_string(pubkey: &OptionalNonZeroPubkey) -> String {
+    Option::<Pubkey>::from(*pubkey).map_or("None".to_string(), |pk| pk.to_string())
+}
+
+fn transfer_fee_rows(label: &str, fee: &TransferFee, decimals: u8) -> Vec<(String, String)> {
+    vec![
+        (format!("{} Epoch", label), u64::from(fee.epoch).to_string()),
+        (
+            format!("{} Fee", label),
+            utils::format_basis_points(u16::from(fee.transfer_fee_basis_points).into()),
+        ),
+        (
+            format!("{} Maximum Fee", label),
+            UiAmount::new(fee.maximum_fee.into(), decimals).to_string(),
+        ),
+    ]
+}
+
+impl ExtensionData {
+    /// Decoded extension fields as `(label, value)` rows for table output.
+    ///
+    /// `decimals` are those of the mint and are used for token amounts.
+    pub fn to_rows(&self, decimals: u8) -> Vec<(String, String)> {
+        let rows: Vec<(&str, String)> = match self {
+            ExtensionData::ImmutableOwner(_) => vec![("Immutable Owner", "true".to_string())],
+            ExtensionData::NonTransferableAccount(_) => {
+                vec![("Non Transferable", "true".to_string())]
+            }
+            ExtensionData::NonTransferable(_) => vec![("Non Transferable", "true".to_string())],
+            ExtensionData::TransferFeeAmount(ext) => vec![(
+                "Withheld Amount",
+                UiAmount::new(ext.withheld_amount.into(), decimals).to_string(),
+            )],
+            ExtensionData::ConfidentialTransferAccount(ext) => vec![
+                ("Approved", bool::from(ext.approved).to_string()),
+                ("ElGamal Pubkey", ext.elgamal_pubkey.to_string()),
+                ("Pending Balance Lo", ext.pending_balance_lo.to_string()),
+                ("Pending Balance Hi", ext.pending_balance_hi.to_string()),
+                ("Available Balance", ext.available_balance.to_string()),
+                (
+                    "Decryptable Available Balance",
+                    ext.decryptable_available_balance.to_string(),
+                ),
+                (
+                    "Allow Confidential Credits",
+                    bool::from(ext.allow_confidential_credits).to_string(),
+                ),
+                (
+                    "Allow Non Confidential Credits",
+                    bool::from(ext.allow_non_confidential_credits).to_string(),
+                ),
+                (
+                    "Pending Balance Credit Counter",
+                    u64::from(ext.pending_balance_credit_counter).to_string(),
+                ),
+                (
+                    "Maximum Pending Balance Credit Counter",
+                    u64::from(ext.maximum_pending_balance_credit_counter).to_string(),
+                ),
+                (
+                    "Expected Pending Balance Credit Counter",
+                    u64::from(ext.expected_pending_balance_credit_counter).to_string(),
+                ),
+                (
+                    "Actual Pending Balance Credit Counter",
+                    u64::from(ext.actual_pending_balance_credit_counter).to_string(),
+                ),
+            ],
+            ExtensionData::MemoTransfer(ext) => vec![(
+                "Require Incoming Transfer Memos",
+                bool::from(ext.require_incoming_transfer_memos).to_string(),
+            )],
+            ExtensionData::TransferHookAccount(ext) => {
+                vec![("Transferring", bool::from(ext.transferring).to_string())]
+            }
+            ExtensionData::CpiGuard(ext) => {
+                vec![("Lock CPI", bool::from(ext.lock_cpi).to_string())]
+            }
+            ExtensionData::ConfidentialTransferFeeAmount(ext) => {
+                vec![(
+                    "Withheld Amount (encrypted)",
+                    ext.withheld_amount.to_string(),
+                )]
+            }
+            ExtensionData::TransferFeeConfig(ext) => {
+                let mut rows = vec![
+                    (
+                        "Transfer Fee Config Authority".to_string(),
+                        optional_pubkey_to_string(&ext.transfer_fee_config_authority),
+                    ),
+                    (
+                        "Withdraw Withheld Authority".to_string(),
+                        optional_pubkey_to_string(&ext.withdraw_withheld_authority),
+                    ),
+                    (
+                        "Withheld Amount".to_string(),
+                        UiAmount::new(ext.withheld_amount.into(), decimals).to_string(),
+                    ),
+                ];
+                rows.extend(transfer_fee_rows(
+                    "Older",
+                    &ext.older_transfer_fee,
+                    decimals,
+                ));
+                rows.extend(transfer_fee_rows(
+                    "Newer",
+                    &ext.newer_transfer_fee,
+                    decimals,
+                ));
+
+                return rows;
+            }
+            ExtensionData::MintCloseAuthority(ext) => vec![(
+                "Close Authority",
+                optional_pubkey_to_string(&ext.close_authority),
+            )],
+            ExtensionData::ConfidentialTransferMint(ext) => vec![
+                ("Authority", optional_pubkey_to_string(&ext.authority)),
+                (
+                    "Auto Approve New Accounts",
+                    bool::from(ext.auto_approve_new_accounts).to_string(),
+                ),
+                (
+                    "Auditor ElGamal Pubkey",
+                    Option::<PodElGamalPubkey>::from(ext.auditor_elgamal_pubkey)
+                        .map_or("None".to_string(), |pk| pk.to_string()),
+                ),
+            ],
+            ExtensionData::DefaultAccountState(ext) => vec![(
+                "Account State",
+                AccountState::try_from(ext.state)
+                    .map_or("Unknown".to_string(), |state| format!("{:?}", state)),
+            )],
+            ExtensionData::InterestBearingConfig(ext) => vec![
+                (
+                    "Rate Authority",
+                    optional_pubkey_to_string(&ext.rate_authority),
+                ),
+                (
+                    "Current Rate",
+                    utils::format_basis_points(i16::from(ext.current_rate).into()),
+                ),
+                (
+                    "Pre Update Average Rate",
+                    utils::format_basis_points(i16::from(ext.pre_update_average_rate).into()),
+                ),
+                (
+                    "Initialization Timestamp",
+                    utils::format_unix_timestamp(ext.initialization_timestamp.into()),
+                ),
+                (
+                    "Last Update Timestamp",
+                    utils::format_unix_timestamp(ext.last_update_timestamp.into()),
+                ),
+            ],
+            ExtensionData::PermanentDelegate(ext) => {
+                vec![("Delegate", optional_pubkey_to_string(&ext.delegate))]
+            }
+            ExtensionData::TransferHook(ext) => vec![
+                ("Authority", optional_pubkey_to_string(&ext.authority)),
+                ("Program Id", optional_pubkey_to_string(&ext.program_id)),
+            ],
+            ExtensionData::ConfidentialTransferFeeConfig(ext) => vec![
+                ("Authority", optional_pubkey_to_string(&ext.authority)),
+                (
+                    "Withdraw Withheld Authority ElGamal Pubkey",
+                    ext.withdraw_withheld_authority_elgamal_pubkey.to_string(),
+                ),
+                (
+                    "Harvest To Mint Enabled",
+                    bool::from(ext.harvest_to_mint_enabled).to_string(),
+                ),
+                (
+                    "Withheld Amount (encrypted)",
+                    ext.withheld_amount.to_string(),
+                ),
+            ],
+            ExtensionData::MetadataPointer(ext) => vec![
+                ("Authority", optional_pubkey_to_string(&ext.authority)),
+                (
+                    "Metadata Address",
+                    optional_pubkey_to_string(&ext.metadata_address),
+                ),
+            ],
+            ExtensionData::TokenMetadata(ext) => vec![
+                (
+                    "Update Authority",
+                    optional_pubkey_to_string(&ext.update_authority),
+                ),
+                ("Mint", ext.mint.to_string()),
+                ("Name", ext.name.clone()),
+                ("Symbol", ext.symbol.clone()),
+                ("Uri", ext.uri.clone()),
+            ],
+            ExtensionData::GroupPointer(ext) => vec![
+                ("Authority", optional_pubkey_to_string(&ext.authority)),
+                (
+                    "Group Address",
+                    optional_pubkey_to_string(&ext.group_address),
+                ),
+            ],
+            ExtensionData::TokenGroup(ext) => vec![
+                (
+                    "Update Authority",
+                    optional_pubkey_to_string(&ext.update_authority),
+                ),
+                ("Mint", ext.mint.to_string()),
+                ("Size", u64::from(ext.size).to_string()),
+                ("Max Size", u64::from(ext.max_size).to_string()),
+            ],
+            ExtensionData::GroupMemberPointer(ext) => vec![
+                ("Authority", optional_pubkey_to_string(&ext.authority)),
+                (
+                    "Member Address",
+                    optional_pubkey_to_string(&ext.member_address),
+                ),
+            ],
+            ExtensionData::TokenGroupMember(ext) => vec![
+                ("Mint", ext.mint.to_string()),
+                ("Group", ext.group.to_string()),
+                ("Member Number", u64::from(ext.member_number).to_string()),
+            ],
+            ExtensionData::ConfidentialMintBurn(ext) => vec![
+                (
+                    "Confidential Supply (encrypted)",
+                    ext.confidential_supply.to_string(),
+                ),
+                ("Decryptable Supply", ext.decryptable_supply.to_string()),
+                (
+                    "Supply ElGamal Pubkey",
+                    ext.supply_elgamal_pubkey.to_string(),
+                ),
+            ],
+        };
+
+        rows.into_iter()
+            .map(|(label, value)| (label.to_string(), value))
+            .collect()
+    }
+}
+
+/// Prints every extension as its own table of decoded fields under `title`.
+pub fn print_extensions(title: &str, extensions: &[ExtensionData], decimals: u8) {
+    if extensions.is_empty() {
+        return;
+    }
+
+    println!(" {}", title.purple().bold());
+
+    for extension in extensions {
+        println!();
+        extension_table(extension, decimals).printstd();
+    }
+}
+
+/// The table of one extension: its rows, plus a sub-table for the additional
+/// metadata of a `TokenMetadata` extension.
+fn extension_table(extension: &ExtensionData, decimals: u8) -> Table {
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![utils::to_header_cell(&format!(
+        "{:?}",
+        extension.extension_type()
+    ))]));
+
+    for (label, value) in extension.to_rows(decimals) {
+        table.add_row(Row::new(vec![
+            utils::to_key_cell(&label),
+            utils::to_value_cell(&value),
+        ]));
+    }
+
+    if let ExtensionData::TokenMetadata(metadata) = extension {
+        if !metadata.additional_metadata.is_empty() {
+            let mut additional = Table::new();
+            additional.add_row(Row::new(vec![
+                utils::to_header_cell("Key"),
+                utils::to_header_cell("Value"),
+            ]));
+            for (key, value) in &metadata.additional_metadata {
+                additional.add_row(Row::new(vec![
+                    utils::to_key_cell(key),
+                    utils::to_value_cell(value),
+                ]));
+            }
+            additional.set_format(*prettytable::format::consts::FORMAT_BOX_CHARS);
+
+            table.add_row(Row::new(vec![
+                utils::to_key_cell("Additional Metadata"),
+                Cell::new(&additional.to_string()),
+            ]));
+        }
+    }
+
+    table.set_format(*prettytable::format::consts::FORMAT_CLEAN);
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use spl_token_2022::extension::{
+        immutable_owner::ImmutableOwner, mint_close_authority::MintCloseAuthority,
+        transfer_fee::TransferFeeConfig,
+    };
+
+    use super::*;
+
+    fn parse(s: &str) -> anyhow::Result<MintExtensionInit> {
+        s.parse()
+    }
+
+    fn rows(rows: &[(&str, &str)]) -> Vec<(String, String)> {
+        rows.iter()
+            .map(|(label, value)| (label.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn transfer_fee(epoch: u64, basis_points: u16, maximum_fee: u64) -> TransferFee {
+        TransferFee {
+            epoch: epoch.into(),
+            maximum_fee: maximum_fee.into(),
+            transfer_fee_basis_points: basis_points.into(),
+        }
+    }
+
+    #[test]
+    fn renders_transfer_fee_config_with_scaled_amounts() {
+        let authority = Pubkey::new_unique();
+        let extension = ExtensionData::TransferFeeConfig(TransferFeeConfig {
+            transfer_fee_config_authority: Some(authority).try_into().unwrap(),
+            withdraw_withheld_authority: OptionalNonZeroPubkey::default(),
+            withheld_amount: 12_345.into(),
+            older_transfer_fee: transfer_fee(3, 25, 1_000),
+            newer_transfer_fee: transfer_fee(4, 50, 5_000),
+        });
+
+        assert_eq!(
+            extension.to_rows(2),
+            rows(&[
+                ("Transfer Fee Config Authority", &authority.to_string()),
+                ("Withdraw Withheld Authority", "None"),
+                ("Withheld Amount", "123.45 (raw: 12345)"),
+                ("Older Epoch", "3"),
+                ("Older Fee", "0.25% (25 bps)"),
+                ("Older Maximum Fee", "10 (raw: 1000)"),
+                ("Newer Epoch", "4"),
+                ("Newer Fee", "0.50% (50 bps)"),
+                ("Newer Maximum Fee", "50 (raw: 5000)"),
+            ])
+        );
+        assert_eq!(
+            extension.to_json(2),
+            json!({
+                "extension": "TransferFeeConfig",
+                "state": {
+                    "transferFeeConfigAuthority": authority.to_string(),
+                    "withdrawWithheldAuthority": null,
+                    "withheldAmount": UiAmount::new(12_345, 2),
+                    "olderTransferFee": {
+                        "epoch": 3,
+                        "maximumFee": UiAmount::new(1_000, 2),
+                        "transferFeeBasisPoints": 25,
+                    },
+                    "newerTransferFee": {
+                        "epoch": 4,
+                        "maximumFee": UiAmount::new(5_000, 2),
+                        "transferFeeBasisPoints": 50,
+                    },
+                },
+            })
+        );
+        assert_eq!(
+            json!(UiAmount::new(12_345, 2)),
+            json!({ "amount": "12345", "decimals": 2, "uiAmountString": "123.45" })
+        );
+    }
+
+    #[test]
+    fn renders_missing_optional_pubkeys() {
+        let extension = ExtensionData::MintCloseAuthority(MintCloseAuthority {
+            close_authority: OptionalNonZeroPubkey::default(),
+        });
+        assert_eq!(extension.to_rows(0), rows(&[("Close Authority", "None")]));
+        assert_eq!(
+            extension.to_json(0),
+            json!({ "extension": "MintCloseAuthority", "state": { "closeAuthority": null } })
+        );
+
+        let extension = ExtensionData::ImmutableOwner(ImmutableOwner);
+        assert_eq!(extension.to_rows(0), rows(&[("Immutable Owner", "true")]));
+        assert_eq!(
+            extension.to_json(0),
+            json!({ "extension": "ImmutableOwner", "state": {} })
+        );
+    }
+
+    #[test]
+    fn renders_token_metadata_with_additional_metadata() {
+        let mint = Pubkey::new_unique();
+        let extension = ExtensionData::TokenMetadata(TokenMetadata {
+            update_authority: OptionalNonZeroPubkey::default(),
+            mint,
+            name: "Surfer".to_string(),
+            symbol: "SURF".to_string(),
+            uri: "https://example.com/surf.json".to_string(),
+            additional_metadata: vec![
+                ("wave".to_string(), "big".to_string()),
+                ("board".to_string(), "long".to_string()),
+            ],
+        });
+
+        assert_eq!(
+            extension.to_rows(6),
+            rows(&[
+                ("Update Authority", "None"),
+                ("Mint", &mint.to_string()),
+                ("Name", "Surfer"),
+                ("Symbol", "SURF"),
+                ("Uri", "https://example.com/surf.json"),
+            ])
+        );
+        assert_eq!(
+            extension.to_json(6),
+            json!({
+                "extension": "TokenMetadata",
+                "state": {
+                    "updateAuthority": null,
+                    "mint": mint.to_string(),
+                    "name": "Surfer",
+                    "symbol": "SURF",
+                    "uri": "https://example.com/surf.json",
+                    "additionalMetadata": [
+                        { "key": "wave", "value": "big" },
+                        { "key": "board", "value": "long" },
+                    ],
+                },
+            })
+        );
+
+        let table = extension_table(&extension, 6);
+        assert_eq!(table.len(), 7);
+        let additional = table.get_row(6).unwrap();
+        assert_eq!(
+            additional.get_cell(0).unwrap().get_content(),
+            "Additional Metadata"
+        );
+        assert_eq!(
+            additional.get_cell(1).unwrap().get_content(),
+            [
+                "┌───────┬───────┐",
+                "│ Key   │ Value │",
+                "├───────┼───────┤",
+                "│ wave  │ big   │",
+                "├───────┼───────┤",
+                "│ board │ long  │",
+                "└───────┴───────┘",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn every_extension_type_has_a_name() {
+        let extension_types: Vec<_> = ExtensionData::extension_types().collect();
+        assert_eq!(extension_types.len(), 24);
+        assert!(!extension_types.contains(&ExtensionType::Uninitialized));
+        for extension_type in extension_types {
+            let name = format!("{:?}", extension_type);
+            assert_eq!(extension_type_from_str(&name).unwrap(), extension_type);
+        }
+    }
+
+    #[test]
+    fn parse_extensions_with_params() {
+        let authority = Pubkey::new_unique();
+        match parse(&format!(
+            "TransferFeeConfig: basis-points=50, maximum-fee=5000,authority={}",
+            authority
+        ))
+        .unwrap()
+        {
+            MintExtensionInit::TransferFeeConfig {
+                authority: Some(a),
+                withdraw_withheld_authority: None,
+                basis_points: 50,
+                maximum_fee: 5000,
+            } => assert_eq!(a, authority),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(matches!(
+            parse("DefaultAccountState:state=frozen").unwrap(),
+            MintExtensionInit::DefaultAccountState {
+                state: AccountState::Frozen
+            }
+        ));
+        assert!(matches!(
+            parse("MetadataPointer").unwrap(),
+            MintExtensionInit::MetadataPointer {
+                authority: None,
+                address: None
+            }
+        ));
+        assert!(matches!(
+            parse("InterestBearingConfig:rate=-25").unwrap(),
+            MintExtensionInit::InterestBearingConfig {
+                authority: None,
+                rate: -25
+            }
+        ));
+    }
+
+    #[test]
+    fn parse_rejects_unknown_and_account_only_types() {
+        let err = parse("TransferFees:basis-points=1").unwrap_err();
+        assert_eq!(err.to_string(), "Invalid extension type: TransferFees");
+        // Names are matched exactly.
+        assert!(parse("metadataPointer").is_err());
+
+        let err = parse("ImmutableOwner").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ImmutableOwner cannot be initialized when creating a mint"
+        );
+    }
+
+    #[test]
+    fn parse_rejects_missing_invalid_and_unknown_params() {
+        let err = parse("TransferFeeConfig:basis-points=50").unwrap_err();
+        assert_eq!(err.to_string(), "Missing required parameter `maximum-fee`");
+
+        let err = parse("InterestBearingConfig:rate=high").unwrap_err();
+        assert!(err.to_string().starts_with("Invalid value for `rate`"));
+
+        let err = parse("DefaultAccountState").unwrap_err();
+        assert!(err.to_string().contains("state=initialized|frozen"));
+
+        let err = parse("MetadataPointer:address").unwrap_err();
+        assert_eq!(err.to_string(), "Invalid extension parameter: address");
+
+        let err = parse("MetadataPointer:owner=x").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown parameter `owner` for MetadataPointer"
+        );
+    }
+
+    #[test]
+    fn parse_rejects_duplicate_params() {
+        let err = parse("InterestBearingConfig:rate=1,rate=2").unwrap_err();
+        assert_eq!(err.to_string(), "Parameter `rate` given more than once");
+    }
+
+    #[test]
+    fn check_rejects_duplicate_extensions() {
+        let extensions = [
+            parse("MetadataPointer").unwrap(),
+            parse("NonTransferable").unwrap(),
+            parse("MetadataPointer:address=11111111111111111111111111111111").unwrap(),
+        ];
+
+        let err = check_mint_extensions(&extensions, None).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Extension MetadataPointer given more than once"
+        );
+        assert_eq!(
+            check_mint_extensions(&extensions[..2], None).unwrap(),
+            vec![
+                ExtensionType::MetadataPointer,
+                ExtensionType::NonTransferable
+            ]
+        );
+    }
+
+    #[test]
+    fn check_requires_freeze_authority_for_frozen_accounts() {
+        let frozen = [parse("DefaultAccountState:state=frozen").unwrap()];
+        let initialized = [parse("DefaultAccountState:state=initialized").unwrap()];
+
+        let err = check_mint_extensions(&frozen, None).unwrap_err();
+        assert!(err.to_string().contains("requires --freeze-authority"));
+        assert!(check_mint_extensions(&frozen, Some(&Pubkey::new_unique())).is_ok());
+        assert!(check_mint_extensions(&initialized, None).is_ok());
+    }
+}
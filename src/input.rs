@@ -0,0 +1,303 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use clap::Args;
+use serde::Deserialize;
+use solana_account::Account;
+use solana_account_decoder_client_types::UiAccount;
+use spl_token::solana_program::{program_pack::Pack, pubkey::Pubkey};
+
+use crate::{multisig::MULTISIG_LEN, utils};
+
+/// Reads an account from a local file or stdin instead of fetching it over
+/// RPC.
+#[derive(Debug, Clone, Default, Args)]
+pub struct AccountInputArgs {
+    #[clap(
+        long,
+        conflicts_with = "from_stdin",
+        help = "Read the account from a file instead of RPC: `solana account --output json`, base64 or a binary dump."
+    )]
+    pub(crate) from_file: Option<PathBuf>,
+    #[clap(
+        long,
+        help = "Read the account from stdin, in the same formats as --from-file."
+    )]
+    pub(crate) from_stdin: bool,
+    #[clap(
+        long,
+        value_parser = utils::parse_pubkey,
+        help = "Owner program of base64 or binary input. Defaults to the Token program, or Token-2022 for data longer than a token account. Required for multisigs."
+    )]
+    pub(crate) owner: Option<Pubkey>,
+}
+
+impl AccountInputArgs {
+    /// Whether --from-file or --from-stdin was given.
+    pub fn is_set(&self) -> bool {
+        self.from_file.is_some() || self.from_stdin
+    }
+
+    /// Reads the account along with its pubkey when the input records it,
+    /// or `None` if neither --from-file nor --from-stdin was given.
+    pub fn read(&self) -> anyhow::Result<Option<(Option<Pubkey>, Account)>> {
+        if let Some(path) = &self.from_file {
+            return read_account_file(path, self.owner).map(Some);
+        }
+        if self.from_stdin {
+            let mut bytes = Vec::new();
+            io::stdin().read_to_end(&mut bytes)?;
+            return parse_account(&bytes, self.owner)
+                .map(Some)
+                .map_err(|e| anyhow::anyhow!("Failed to parse account from stdin: {}", e));
+        }
+
+        Ok(None)
+    }
+}
+
+/// The address to fetch over RPC, which clap only leaves out when the
+/// account comes from --from-file or --from-stdin.
+pub fn required_pubkey(pubkey: Option<Pubkey>) -> anyhow::Result<Pubkey> {
+    pubkey.ok_or_else(|| {
+        anyhow::anyhow!("Missing account address, pass one or use --from-file or --from-stdin")
+    })
+}
+
+/// The one address a command that handles a single account was given, if
+/// any.
+pub fn single_pubkey(pubkeys: &[Pubkey]) -> anyhow::Result<Option<Pubkey>> {
+    match pubkeys {
+        [] => Ok(None),
+        [pubkey] => Ok(Some(*pubkey)),
+        _ => Err(anyhow::anyhow!(
+            "Expected a single address but got {}",
+            pubkeys.len()
+        )),
+    }
+}
+
+/// The addresses given on the command line followed by those in
+/// `pubkeys_file`, which holds one pubkey per line. Blank lines and lines
+/// starting with `#` are skipped.
+pub fn collect_pubkeys(
+    pubkeys: &[Pubkey],
+    pubkeys_file: Option<&Path>,
+) -> anyhow::Result<Vec<Pubkey>> {
+    let mut all = pubkeys.to_vec();
+    let Some(path) = pubkeys_file else {
+        return Ok(all);
+    };
+
+    let contents = fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read pubkeys from {}: {}", path.display(), e))?;
+    for (line_number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let pubkey = Pubkey::from_str(line).map_err(|e| {
+            anyhow::anyhow!(
+                "Invalid pubkey `{}` on line {} of {}: {}",
+                line,
+                line_number + 1,
+                path.display(),
+                e
+            )
+        })?;
+        all.push(pubkey);
+    }
+
+    Ok(all)
+}
+
+/// Displays a pubkey that raw base64 or binary input may not record.
+pub fn display_pubkey(pubkey: Option<&Pubkey>) -> String {
+    pubkey.map_or("Unknown".to_string(), |pubkey| pubkey.to_string())
+}
+
+/// The layout of `solana account --output json` and of test-validator
+/// `--account` files.
+#[derive(Deserialize)]
+struct KeyedUiAccount {
+    pubkey: String,
+    account: UiAccount,
+}
+
+/// Reads an account file in any of the --from-file formats, along with its
+/// pubkey when the file records it.
+pub fn read_account_file(
+    path: &Path,
+    owner: Option<Pubkey>,
+) -> anyhow::Result<(Option<Pubkey>, Account)> {
+    let bytes = fs::read(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read account from {}: {}", path.display(), e))?;
+
+    parse_account(&bytes, owner)
+        .map_err(|e| anyhow::anyhow!("Failed to parse account from {}: {}", path.display(), e))
+}
+
+/// Parses an account from JSON (either keyed as printed by `solana account`
+/// or a bare RPC `UiAccount`), base64 or raw binary data, detected from the
+/// contents. Only JSON records the owner and pubkey; raw data gets `owner`
+/// or a token program guessed from its length. Multisigs have the same
+/// length under both token programs, so their owner cannot be guessed.
+pub fn parse_account(
+    bytes: &[u8],
+    owner: Option<Pubkey>,
+) -> anyhow::Result<(Option<Pubkey>, Account)> {
+    let text = std::str::from_utf8(bytes).ok().map(str::trim);
+
+    if let Some(json) = text.filter(|text| text.starts_with('{')) {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        let (pubkey, ui_account) = match value.get("account") {
+            Some(_) => {
+                let keyed: KeyedUiAccount = serde_json::from_value(value)?;
+                let pubkey = Pubkey::from_str(&keyed.pubkey)
+                    .map_err(|e| anyhow::anyhow!("Invalid pubkey `{}`: {}", keyed.pubkey, e))?;
+                (Some(pubkey), keyed.account)
+            }
+            None => (None, serde_json::from_value::<UiAccount>(value)?),
+        };
+        Pubkey::from_str(&ui_account.owner)
+            .map_err(|e| anyhow::anyhow!("Invalid owner `{}`: {}", ui_account.owner, e))?;
+        let account = ui_account.decode::<Account>().ok_or_else(|| {
+            anyhow::anyhow!("Unsupported account data encoding, use base64 or base58")
+        })?;
+
+        return Ok((pubkey, account));
+    }
+
+    let data = text
+        .filter(|text| !text.is_empty() && is_base64(text))
+        .and_then(|text| BASE64_STANDARD.decode(strip_whitespace(text)).ok())
+        .unwrap_or_else(|| bytes.to_vec());
+    let owner = match (owner, data.len()) {
+        (Some(owner), _) => owner,
+        (None, MULTISIG_LEN) => {
+            return Err(anyhow::anyhow!(
+                "{} bytes is the size of a multisig of either token program, pass --owner",
+                MULTISIG_LEN
+            ))
+        }
+        (None, len) if len > spl_token::state::Account::LEN => spl_token_2022::ID,
+        (None, _) => spl_token::ID,
+    };
+
+    Ok((
+        None,
+        Account {
+            data,
+            owner,
+            ..Account::default()
+        },
+    ))
+}
+
+fn is_base64(text: &str) -> bool {
+    text.chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '=') || c.is_whitespace())
+}
+
+fn strip_whitespace(text: &str) -> String {
+    text.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_fixtures::{mint_account, multisig_account, token_2022_mint_account};
+
+    fn ui_account_json(account: &Account) -> serde_json::Value {
+        json!({
+            "lamports": account.lamports,
+            "data": [BASE64_STANDARD.encode(&account.data), "base64"],
+            "owner": account.owner.to_string(),
+            "executable": false,
+            "rentEpoch": 0,
+            "space": account.data.len(),
+        })
+    }
+
+    #[test]
+    fn parses_keyed_json() {
+        let pubkey = Pubkey::new_unique();
+        let account = token_2022_mint_account(6, 100);
+        let json = json!({ "pubkey": pubkey.to_string(), "account": ui_account_json(&account) });
+
+        let (parsed_pubkey, parsed) = parse_account(json.to_string().as_bytes(), None).unwrap();
+        assert_eq!(parsed_pubkey, Some(pubkey));
+        assert_eq!(parsed.data, account.data);
+        assert_eq!(parsed.owner, spl_token_2022::ID);
+    }
+
+    #[test]
+    fn parses_bare_ui_account() {
+        // The owner recorded in JSON wins over --owner.
+        let account = mint_account(6, 100);
+        let json = ui_account_json(&account).to_string();
+
+        let (pubkey, parsed) = parse_account(json.as_bytes(), Some(spl_token_2022::ID)).unwrap();
+        assert_eq!(pubkey, None);
+        assert_eq!(parsed.data, account.data);
+        assert_eq!(parsed.owner, spl_token::ID);
+    }
+
+    #[test]
+    fn parses_base64_with_whitespace() {
+        let account = mint_account(6, 100);
+        let encoded = BASE64_STANDARD.encode(&account.data);
+        let (head, tail) = encoded.split_at(40);
+        let text = format!("  {}\n{}\n", head, tail);
+
+        let (pubkey, parsed) = parse_account(text.as_bytes(), None).unwrap();
+        assert_eq!(pubkey, None);
+        assert_eq!(parsed.data, account.data);
+    }
+
+    #[test]
+    fn parses_binary_dump() {
+        let mut account = mint_account(6, 100);
+        // Make the dump invalid UTF-8 so it can't be mistaken for text.
+        account.data[0] = 0xff;
+
+        let (pubkey, parsed) = parse_account(&account.data, None).unwrap();
+        assert_eq!(pubkey, None);
+        assert_eq!(parsed.data, account.data);
+    }
+
+    #[test]
+    fn guesses_owner_from_length() {
+        let owner_of = |account: Account, owner: Option<Pubkey>| {
+            parse_account(&account.data, owner).map(|(_, parsed)| parsed.owner)
+        };
+
+        assert_eq!(owner_of(mint_account(6, 100), None).unwrap(), spl_token::ID);
+        assert_eq!(
+            owner_of(token_2022_mint_account(6, 100), None).unwrap(),
+            spl_token_2022::ID
+        );
+        let program = Pubkey::new_unique();
+        assert_eq!(
+            owner_of(mint_account(6, 100), Some(program)).unwrap(),
+            program
+        );
+
+        let multisig = multisig_account(1, &[Pubkey::new_unique()]);
+        assert_eq!(
+            owner_of(multisig.clone(), None).unwrap_err().to_string(),
+            "355 bytes is the size of a multisig of either token program, pass --owner"
+        );
+        assert_eq!(
+            owner_of(multisig, Some(spl_token_2022::ID)).unwrap(),
+            spl_token_2022::ID
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! Parsing and instruction building for SPL Token and Token-2022 accounts,
+//! shared by the `surfer` CLI.
+//!
+//! The parsers work on raw [`solana_account::Account`]s, so they don't care
+//! where an account came from. Fetch helpers such as
+//! [`mint::TokenMetadata::fetch_and_parse`] take any [`source::AccountSource`],
+//! which [`RpcClient`](solana_client::nonblocking::rpc_client::RpcClient)
+//! implements.
+//!
+//! ```no_run
+//! use solana_client::nonblocking::rpc_client::RpcClient;
+//! use spl_token::solana_program::pubkey::Pubkey;
+//! use surfer::{mint::{MintWithExtensions, TokenMetadata}, source::AccountSource};
+//!
+//! # async fn run(mint_pubkey: Pubkey) -> anyhow::Result<()> {
+//! let rpc = RpcClient::new("https://api.mainnet-beta.solana.com".to_string());
+//! let account = AccountSource::get_account(&rpc, &mint_pubkey)
+//!     .await?
+//!     .ok_or_else(|| anyhow::anyhow!("Mint not found"))?;
+//! let metadata = TokenMetadata::fetch_and_parse(mint_pubkey, &rpc).await;
+//! let mint = MintWithExtensions::try_parse_mint_with_extensions(account, metadata)?;
+//! println!("decimals: {}", mint.base.get_decimals());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The instruction builders return plain [`Instruction`]s for callers that
+//! sign and send transactions themselves. They pick the token program from
+//! the parsed mint, or take it explicitly where no mint is needed:
+//!
+//! - mints: [`mint::build_initialize_mint2_ix`], [`mint::build_mint_to_ix`],
+//!   [`mint::build_mint_to_checked_ix`] and [`mint::build_set_authority_ix`];
+//! - token accounts: [`token_account::build_create_ix`],
+//!   [`token_account::build_transfer_ix`], [`token_account::build_burn_ix`],
+//!   [`token_account::build_burn_checked_ix`],
+//!   [`token_account::build_approve_ix`],
+//!   [`token_account::build_approve_checked_ix`],
+//!   [`token_account::build_revoke_ix`],
+//!   [`token_account::build_close_account_ix`],
+//!   [`token_account::build_freeze_ix`] and [`token_account::build_thaw_ix`].
+//!
+//! ```no_run
+//! use solana_account::Account;
+//! use spl_token::solana_program::pubkey::Pubkey;
+//! use surfer::{mint::MintWithExtensions, token_account::build_burn_checked_ix};
+//!
+//! # fn run(mint_pubkey: Pubkey, mint_account: Account, token_account: Pubkey, owner: Pubkey) -> anyhow::Result<()> {
+//! let mint = MintWithExtensions::try_parse_mint_with_extensions(mint_account, None)?;
+//! let ix = build_burn_checked_ix(&token_account, &mint_pubkey, &mint, &owner, &[], 1_000)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Instruction`]: spl_token::solana_program::instruction::Instruction
+
+#![warn(missing_docs)]
+
+/// Detecting the kind of an arbitrary account.
+pub mod account;
+/// Token amounts given as decimals or `ALL`.
+pub mod amount;
+/// Resolved cluster, commitment and signer configuration.
+pub mod config;
+/// Token-2022 extension decoding and mint extension initialization.
+pub mod extension;
+/// Reading accounts from files or stdin instead of RPC.
+pub mod input;
+/// Mint parsing, Metaplex metadata and mint instructions.
+pub mod mint;
+/// SPL multisig parsing and creation.
+pub mod multisig;
+/// Durable nonce accounts.
+pub mod nonce;
+/// JSON output helpers.
+pub mod output;
+/// Keypair and signer sources.
+pub mod signer;
+/// Where accounts are fetched from.
+pub mod source;
+#[cfg(test)]
+pub(crate) mod test_fixtures;
+/// Token account parsing and token account instructions.
+pub mod token_account;
+/// The legacy Token and Token-2022 programs.
+pub mod token_program;
+/// Signing, sending, simulating and serializing transactions.
+pub mod transaction;
+/// Table cells, spinners and formatting helpers.
+pub mod utils;
+/// Token balances of a wallet.
+pub mod wallet;
@@ -1,23 +1,39 @@
 use clap::Parser;
-use cli::Cli;
+use surfer::config::Config;
 
 mod cli;
-mod extension;
-mod mint;
-mod token_account;
-mod utils;
+
+use cli::{Cli, Commands};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
-    cli.validate_args()?;
+    let resolve = || Config::resolve(&cli.global, cli.command.send_args());
 
-    match cli.command {
-        cli::Commands::Mint(m_cmd) => {
-            m_cmd.process().await?;
+    match &cli.command {
+        Commands::Account(a_cmd) => {
+            a_cmd.process(&resolve()?).await?;
+        }
+        Commands::Mint(m_cmd) => {
+            m_cmd.process(&resolve()?).await?;
+        }
+        Commands::TokenAccount(ta_cmd) => {
+            ta_cmd.process(&resolve()?).await?;
+        }
+        Commands::Multisig(ms_cmd) => {
+            ms_cmd.process(&resolve()?).await?;
+        }
+        Commands::Nonce(n_cmd) => {
+            n_cmd.process(&resolve()?).await?;
+        }
+        Commands::Tx(tx_cmd) => {
+            tx_cmd.process(&resolve()?).await?;
+        }
+        Commands::Wallet(w_cmd) => {
+            w_cmd.process(&resolve()?).await?;
         }
-        cli::Commands::TokenAccount(ta_cmd) => {
-            ta_cmd.process().await?;
+        Commands::Config(c_cmd) => {
+            c_cmd.process(&cli.global)?;
         }
     }
 
@@ -1,40 +1,76 @@
-use super::metadata::TokenMetadata;
+use std::path::PathBuf;
+
+use super::{metadata::TokenMetadata, pretty_mint};
 use clap::Args;
-use colored::*;
-use prettytable::{color, Attr, Cell, Row, Table};
+use mpl_token_metadata::accounts::{MasterEdition, Metadata};
+use prettytable::{Row, Table};
+use serde_json::json;
 use solana_account::Account;
-use solana_client::nonblocking::rpc_client::RpcClient;
 use spl_token::solana_program::{program_pack::Pack, pubkey::Pubkey};
-use spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use spl_token_2022::extension::{
+    interest_bearing_mint::InterestBearingConfig, BaseStateWithExtensions, StateWithExtensions,
+};
 
 use crate::{
-    cli::{self, SolanaRpcArgs, TokenProgram},
-    extension::{mint_account_extensions_data_bytes, ExtensionData},
+    config::Config,
+    extension::{mint_account_extensions_data_bytes, print_extensions, ExtensionData},
+    input::{self, AccountInputArgs},
+    multisig,
+    output::UiAmount,
+    source::{AccountSource, MemorySource},
+    token_program::TokenProgram,
+    utils,
 };
 
+/// A parsed mint with every field ready for display or JSON output.
 #[derive(Debug, Clone)]
 pub struct PrettyMint {
+    /// Address of the mint.
     pub mint_pubkey: String,
+    /// Who can mint new tokens, `None` for a fixed supply.
     pub mint_authority: Option<String>,
+    /// Total supply in base units.
     pub supply: u64,
+    /// Number of base 10 digits to the right of the decimal place.
     pub decimals: u8,
+    /// Whether the mint was initialized.
     pub is_initialized: bool,
+    /// Who can freeze token accounts of the mint, if anyone.
     pub freeze_authority: Option<String>,
+    /// Token-2022 extensions, `None` for the legacy Token program.
     pub extensions: Option<Vec<ExtensionData>>,
+    /// Metaplex metadata, if fetched and present.
     pub token_metadata: Option<TokenMetadata>,
+    /// Cluster time used to compute interest for `InterestBearingConfig` mints.
+    pub unix_timestamp: Option<i64>,
 }
 
+/// A parsed mint and its address, converted into a [`PrettyMint`].
 pub struct MintWithPubkey {
+    /// The parsed mint.
     pub mint_data: MintWithExtensions,
+    /// Address of the mint.
     pub pubkey: String,
 }
 
+/// The base mint state of either token program.
 pub enum MintType {
+    /// A mint of the legacy Token program.
     LegacyMint(spl_token::state::Mint),
+    /// A mint of the Token-2022 program.
     Mint2022(spl_token_2022::state::Mint),
 }
 
 impl MintType {
+    /// The token program owning the mint.
+    pub fn token_program(&self) -> TokenProgram {
+        match self {
+            MintType::LegacyMint(_) => TokenProgram::LegacyToken,
+            MintType::Mint2022(_) => TokenProgram::Token2022,
+        }
+    }
+
+    /// Number of base 10 digits to the right of the decimal place.
     pub fn get_decimals(&self) -> u8 {
         match self {
             MintType::LegacyMint(mint) => mint.decimals,
@@ -43,17 +79,29 @@ impl MintType {
     }
 }
 
+/// A mint with its decoded Token-2022 extensions and, when fetched, its
+/// Metaplex metadata.
 pub struct MintWithExtensions {
+    /// The base mint state.
     pub base: MintType,
+    /// Metaplex metadata, if fetched and present.
     pub token_metadata: Option<TokenMetadata>,
+    /// Token-2022 extensions, `None` for the legacy Token program.
     pub extensions: Option<Vec<ExtensionData>>,
 }
 
 impl MintWithExtensions {
+    /// Parses a mint owned by either token program. `extensions` is `None`
+    /// for the legacy Token program.
     pub fn try_parse_mint_with_extensions(
         data: Account,
         token_metadata: Option<TokenMetadata>,
     ) -> anyhow::Result<Self> {
+        if multisig::is_multisig(&data) {
+            return Err(anyhow::anyhow!(
+                "Account is an SPL multisig, not a mint, use `surfer multisig fetch`"
+            ));
+        }
         let token_program = TokenProgram::try_from(data.owner)?;
         let data_bytes = data.data.as_slice();
         match token_program {
@@ -87,39 +135,48 @@ impl MintWithExtensions {
             }
         }
     }
-}
 
-impl PrettyMint {
-    pub fn to_header_cell(header: &str) -> Cell {
-        Cell::new(header)
-            .with_style(Attr::Bold)
-            .with_style(Attr::ForegroundColor(color::WHITE))
-    }
-    pub fn to_key_cell(key: &str) -> Cell {
-        Cell::new(key)
-            .with_style(Attr::Bold)
-            .with_style(Attr::ForegroundColor(color::GREEN))
+    /// The token's name and symbol, from its Metaplex metadata or else its
+    /// Token-2022 `TokenMetadata` extension.
+    pub fn name_and_symbol(&self) -> Option<(String, String)> {
+        name_and_symbol(self.token_metadata.as_ref(), self.extensions.as_deref())
     }
+}
 
-    pub fn to_value_cell(value: &str) -> Cell {
-        Cell::new(value)
-            .with_style(Attr::Bold)
-            .with_style(Attr::ForegroundColor(color::BRIGHT_CYAN))
+fn name_and_symbol(
+    token_metadata: Option<&TokenMetadata>,
+    extensions: Option<&[ExtensionData]>,
+) -> Option<(String, String)> {
+    if let Some(metadata) = token_metadata.and_then(|m| m.metadata.as_ref()) {
+        return Some((metadata.name.clone(), metadata.symbol.clone()));
     }
 
+    extensions
+        .into_iter()
+        .flatten()
+        .find_map(|extension| match extension {
+            ExtensionData::TokenMetadata(metadata) => {
+                Some((metadata.name.clone(), metadata.symbol.clone()))
+            }
+            _ => None,
+        })
+}
+
+impl PrettyMint {
+    /// Prints the mint as a table, followed by its metadata.
     pub fn print(&self) {
         let mut table = Table::new();
 
-        table.add_row(Row::new(vec![Self::to_header_cell("Mint Data")]));
+        table.add_row(Row::new(vec![utils::to_header_cell("Mint Data")]));
 
         table.add_row(Row::new(vec![
-            Self::to_key_cell("Mint Pubkey"),
-            Self::to_value_cell(&self.mint_pubkey),
+            utils::to_key_cell("Mint Pubkey"),
+            utils::to_value_cell(&self.mint_pubkey),
         ]));
 
         table.add_row(Row::new(vec![
-            Self::to_key_cell("Mint Authority"),
-            Self::to_value_cell(
+            utils::to_key_cell("Mint Authority"),
+            utils::to_value_cell(
                 &self
                     .mint_authority
                     .clone()
@@ -128,23 +185,30 @@ impl PrettyMint {
         ]));
 
         table.add_row(Row::new(vec![
-            Self::to_key_cell("Supply"),
-            Self::to_value_cell(&self.supply.to_string()),
+            utils::to_key_cell("Supply"),
+            utils::to_value_cell(&UiAmount::new(self.supply, self.decimals).to_string()),
         ]));
 
+        if let Some(supply) = self.interest_accrued_ui_amount(self.supply) {
+            table.add_row(Row::new(vec![
+                utils::to_key_cell("Supply With Interest"),
+                utils::to_value_cell(&supply),
+            ]));
+        }
+
         table.add_row(Row::new(vec![
-            Self::to_key_cell("Decimals"),
-            Self::to_value_cell(&self.decimals.to_string()),
+            utils::to_key_cell("Decimals"),
+            utils::to_value_cell(&self.decimals.to_string()),
         ]));
 
         table.add_row(Row::new(vec![
-            Self::to_key_cell("Is Initialized"),
-            Self::to_value_cell(&self.is_initialized.to_string()),
+            utils::to_key_cell("Is Initialized"),
+            utils::to_value_cell(&self.is_initialized.to_string()),
         ]));
 
         table.add_row(Row::new(vec![
-            Self::to_key_cell("Freeze Authority"),
-            Self::to_value_cell(
+            utils::to_key_cell("Freeze Authority"),
+            utils::to_value_cell(
                 &self
                     .freeze_authority
                     .clone()
@@ -156,54 +220,70 @@ impl PrettyMint {
         table.set_format(*prettytable::format::consts::FORMAT_CLEAN);
         table.printstd();
 
-        match &self.extensions {
-            Some(extensions) => {
-                let data = format!("{:#?}", extensions).cyan();
-                if !extensions.is_empty() {
-                    println!();
-                    println!(" {} : {}", "Mint Extensions".purple().bold(), data.bold());
-                }
+        if let Some(extensions) = &self.extensions {
+            if !extensions.is_empty() {
+                println!();
+                print_extensions("Mint Extensions", extensions, self.decimals);
             }
-            None => {}
         }
 
-        match &self.token_metadata {
-            Some(metadata) => {
-                println!();
-                let meta = metadata
-                    .metadata
-                    .as_ref()
-                    .map_or("None".to_string(), |m| format!("{:#?}", m));
-
-                let master_edition = metadata
-                    .master_edition
-                    .as_ref()
-                    .map_or("None".to_string(), |me| format!("{:#?}", me));
-
-                if meta != "None" {
-                    println!(
-                        " {} : {}",
-                        "Token Metadata".purple().bold(),
-                        meta.cyan().bold()
-                    );
-                }
-
-                println!();
-
-                if master_edition != "None" {
-                    println!(
-                        " {} : {}",
-                        "Master Edition".purple().bold(),
-                        master_edition.cyan().bold()
-                    );
-                }
-            }
-            None => {}
+        if let Some(metadata) = &self.token_metadata {
+            metadata.print();
         }
         println!();
     }
 }
 
+impl PrettyMint {
+    /// The `InterestBearingConfig` extension, if the mint has one.
+    pub fn interest_bearing_config(&self) -> Option<&InterestBearingConfig> {
+        self.extensions.as_ref()?.iter().find_map(|e| match e {
+            ExtensionData::InterestBearingConfig(config) => Some(config),
+            _ => None,
+        })
+    }
+
+    /// The token's name and symbol, as with
+    /// [`MintWithExtensions::name_and_symbol`].
+    pub fn name_and_symbol(&self) -> Option<(String, String)> {
+        name_and_symbol(self.token_metadata.as_ref(), self.extensions.as_deref())
+    }
+
+    /// UI amount of `amount` including the interest accrued up to
+    /// `unix_timestamp`, for mints with an `InterestBearingConfig`.
+    pub fn interest_accrued_ui_amount(&self, amount: u64) -> Option<String> {
+        self.interest_bearing_config()?.amount_to_ui_amount(
+            amount,
+            self.decimals,
+            self.unix_timestamp?,
+        )
+    }
+
+    /// The mint as the JSON document printed by `surfer mint fetch`.
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "pubkey": self.mint_pubkey,
+            "mintAuthority": self.mint_authority,
+            "supply": UiAmount::new(self.supply, self.decimals),
+            "interestAccruedSupply": self.interest_accrued_ui_amount(self.supply),
+            "decimals": self.decimals,
+            "isInitialized": self.is_initialized,
+            "freezeAuthority": self.freeze_authority,
+            "extensions": self.extensions.as_ref().map(|extensions| {
+                extensions
+                    .iter()
+                    .map(|e| e.to_json(self.decimals))
+                    .collect::<Vec<_>>()
+            }),
+            "metaplexMetadata": self.token_metadata.as_ref().and_then(|m| m.metadata_to_json()),
+            "masterEdition": self
+                .token_metadata
+                .as_ref()
+                .and_then(|m| m.master_edition_to_json()),
+        })
+    }
+}
+
 impl From<MintWithPubkey> for PrettyMint {
     fn from(mint_with_pubkey: MintWithPubkey) -> Self {
         match mint_with_pubkey.mint_data.base {
@@ -216,6 +296,7 @@ impl From<MintWithPubkey> for PrettyMint {
                 freeze_authority: mint.freeze_authority.map(|pk| pk.to_string()).into(),
                 extensions: None,
                 token_metadata: mint_with_pubkey.mint_data.token_metadata,
+                unix_timestamp: None,
             },
             MintType::Mint2022(mint) => Self {
                 mint_pubkey: mint_with_pubkey.pubkey.to_string(),
@@ -226,28 +307,314 @@ impl From<MintWithPubkey> for PrettyMint {
                 freeze_authority: mint.freeze_authority.map(|pk| pk.to_string()).into(),
                 extensions: mint_with_pubkey.mint_data.extensions,
                 token_metadata: mint_with_pubkey.mint_data.token_metadata,
+                unix_timestamp: None,
             },
         }
     }
 }
 
+/// Arguments of `surfer mint fetch`.
 #[derive(Debug, Args)]
 pub struct FetchMint {
+    #[clap(value_parser = utils::parse_pubkey,
+    required_unless_present_any = ["from_file", "from_stdin", "pubkeys_file"],
+    help = "The mint addresses to fetch. Must be valid base58 encoded pubkeys.")]
+    pub(crate) mint_pubkeys: Vec<Pubkey>,
+    #[clap(
+        long,
+        conflicts_with_all = ["from_file", "from_stdin"],
+        help = "Also fetch the mints listed in this file, one address per line."
+    )]
+    pub(crate) pubkeys_file: Option<PathBuf>,
+    #[clap(
+        long,
+        conflicts_with_all = ["from_file", "from_stdin"],
+        help = "Print a table row or JSON array element per mint even for a single address. Implied by several addresses or --pubkeys-file."
+    )]
+    pub(crate) batch: bool,
     #[clap(flatten)]
-    pub solana: SolanaRpcArgs,
-    #[clap(value_parser = cli::Cli::parse_pubkey,
-    help = "The mint address to fetch. Must be a valid base58 encoded pubkey.")]
-    pub mint_pubkey: Pubkey,
+    pub(crate) input: AccountInputArgs,
 }
 
 impl FetchMint {
-    pub async fn process_fetch_and_parse(&self) -> anyhow::Result<MintWithExtensions> {
-        let rpc_client = RpcClient::new(self.solana.solana_rpc_url.clone());
-        let acc = rpc_client.get_account(&self.mint_pubkey).await?;
-        let token_metadata = TokenMetadata::fetch_and_parse(self.mint_pubkey, &rpc_client).await;
-        let mint_with_extensions =
-            MintWithExtensions::try_parse_mint_with_extensions(acc, token_metadata)?;
-
-        Ok(mint_with_extensions)
+    /// Whether the mints are printed together, as one table or a JSON array.
+    /// That is the case whenever several may have been asked for, so the
+    /// output shape never depends on how many addresses a --pubkeys-file
+    /// holds. Input read from a file or stdin is always a single account.
+    pub fn is_batch(&self) -> bool {
+        !self.input.is_set()
+            && (self.batch || self.mint_pubkeys.len() > 1 || self.pubkeys_file.is_some())
+    }
+
+    /// Fetches the mint with its Metaplex metadata, or reads it from
+    /// --from-file or --from-stdin without touching RPC.
+    pub async fn process_fetch_and_parse(&self, config: &Config) -> anyhow::Result<PrettyMint> {
+        if let Some((pubkey, acc)) = self.input.read()? {
+            let mint_with_extensions =
+                MintWithExtensions::try_parse_mint_with_extensions(acc, None)?;
+            return pretty_mint(
+                &MemorySource::new(),
+                input::single_pubkey(&self.mint_pubkeys)?
+                    .or(pubkey)
+                    .as_ref(),
+                mint_with_extensions,
+            )
+            .await;
+        }
+
+        self.fetch_and_parse(&config.rpc_client()?).await
+    }
+
+    /// Fetches the mint and its Metaplex metadata from `source`.
+    pub async fn fetch_and_parse<S: AccountSource + ?Sized>(
+        &self,
+        source: &S,
+    ) -> anyhow::Result<PrettyMint> {
+        let mint_pubkey = input::required_pubkey(input::single_pubkey(&self.mint_pubkeys)?)?;
+
+        fetch_mints(source, &[mint_pubkey]).await?.remove(0).1
+    }
+
+    /// Fetches every mint given on the command line or in --pubkeys-file.
+    pub async fn fetch_batch<S: AccountSource + ?Sized>(
+        &self,
+        source: &S,
+    ) -> anyhow::Result<Vec<(Pubkey, anyhow::Result<PrettyMint>)>> {
+        let mint_pubkeys =
+            input::collect_pubkeys(&self.mint_pubkeys, self.pubkeys_file.as_deref())?;
+
+        fetch_mints(source, &mint_pubkeys).await
+    }
+}
+
+/// Fetches mints together with their Metaplex metadata and master editions
+/// in a single [`AccountSource::get_multiple_accounts`] call, which RPC
+/// splits into requests of [`crate::source::MAX_MULTIPLE_ACCOUNTS`]. Mints that are
+/// missing or fail to parse get their own error instead of failing the
+/// whole batch.
+pub async fn fetch_mints<S: AccountSource + ?Sized>(
+    source: &S,
+    mint_pubkeys: &[Pubkey],
+) -> anyhow::Result<Vec<(Pubkey, anyhow::Result<PrettyMint>)>> {
+    let metadata_pdas = mint_pubkeys.iter().map(|mint| Metadata::find_pda(mint).0);
+    let master_edition_pdas = mint_pubkeys
+        .iter()
+        .map(|mint| MasterEdition::find_pda(mint).0);
+    let pubkeys: Vec<Pubkey> = mint_pubkeys
+        .iter()
+        .copied()
+        .chain(metadata_pdas)
+        .chain(master_edition_pdas)
+        .collect();
+    let accounts = source.get_multiple_accounts(&pubkeys).await?;
+    let (mint_accounts, metaplex_accounts) = accounts.split_at(mint_pubkeys.len());
+    let (metadata_accounts, master_edition_accounts) =
+        metaplex_accounts.split_at(mint_pubkeys.len());
+
+    let mut mints: Vec<_> = mint_pubkeys
+        .iter()
+        .enumerate()
+        .map(|(i, mint_pubkey)| {
+            let mint = mint_accounts[i]
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("Account {} does not exist", mint_pubkey))
+                .and_then(|acc| {
+                    let token_metadata = TokenMetadata::from_accounts(
+                        metadata_accounts[i].as_ref(),
+                        master_edition_accounts[i].as_ref(),
+                    );
+                    MintWithExtensions::try_parse_mint_with_extensions(acc, token_metadata)
+                })
+                .map(|mint_data| {
+                    PrettyMint::from(MintWithPubkey {
+                        mint_data,
+                        pubkey: mint_pubkey.to_string(),
+                    })
+                });
+            (*mint_pubkey, mint)
+        })
+        .collect();
+
+    // Interest-bearing mints share a single read of the clock sysvar.
+    let is_interest_bearing = |mint: &anyhow::Result<PrettyMint>| {
+        mint.as_ref()
+            .is_ok_and(|m| m.interest_bearing_config().is_some())
+    };
+    if mints.iter().any(|(_, mint)| is_interest_bearing(mint)) {
+        let unix_timestamp = utils::fetch_unix_timestamp(source).await?;
+        for (_, mint) in mints
+            .iter_mut()
+            .filter(|(_, mint)| is_interest_bearing(mint))
+        {
+            if let Ok(mint) = mint {
+                mint.unix_timestamp = unix_timestamp;
+            }
+        }
+    }
+
+    Ok(mints)
+}
+
+/// Prints a batch of mints as one table with a row per mint.
+pub fn print_mints(mints: &[(Pubkey, anyhow::Result<PrettyMint>)]) {
+    let headers = [
+        "Mint",
+        "Symbol",
+        "Supply",
+        "Decimals",
+        "Mint Authority",
+        "Freeze Authority",
+        "Extensions",
+    ];
+    let mut table = Table::new();
+    table.add_row(Row::new(
+        headers.into_iter().map(utils::to_header_cell).collect(),
+    ));
+    for (pubkey, mint) in mints {
+        let mint = match mint {
+            Ok(mint) => mint,
+            Err(e) => {
+                table.add_row(Row::new(vec![
+                    utils::to_key_cell(&pubkey.to_string()),
+                    utils::to_error_cell(&e.to_string()).with_hspan(headers.len() - 1),
+                ]));
+                continue;
+            }
+        };
+        let extensions = mint
+            .extensions
+            .as_ref()
+            .map_or("-".to_string(), |extensions| {
+                extensions
+                    .iter()
+                    .map(|e| format!("{:?}", e.extension_type()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            });
+        table.add_row(Row::new(vec![
+            utils::to_key_cell(&mint.mint_pubkey),
+            utils::to_value_cell(
+                &mint
+                    .name_and_symbol()
+                    .map_or("-".to_string(), |(_, symbol)| symbol),
+            ),
+            utils::to_value_cell(&UiAmount::new(mint.supply, mint.decimals).ui_amount_string),
+            utils::to_value_cell(&mint.decimals.to_string()),
+            utils::to_value_cell(mint.mint_authority.as_deref().unwrap_or("None")),
+            utils::to_value_cell(mint.freeze_authority.as_deref().unwrap_or("None")),
+            utils::to_value_cell(&extensions),
+        ]));
+    }
+
+    println!();
+    table.printstd();
+}
+
+#[cfg(test)]
+mod tests {
+    use mpl_token_metadata::accounts::{MasterEdition, Metadata};
+
+    use super::*;
+    use crate::test_fixtures::{master_edition_account, metadata_account, mint_account};
+
+    fn fetch_mint(mint_pubkey: Pubkey) -> FetchMint {
+        FetchMint {
+            mint_pubkeys: vec![mint_pubkey],
+            pubkeys_file: None,
+            batch: false,
+            input: AccountInputArgs::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn fetch_mint_with_metaplex_accounts() {
+        let mint_pubkey = Pubkey::new_unique();
+        let source = MemorySource::new()
+            .with_account(mint_pubkey, mint_account(6, 5_000_000))
+            .with_account(
+                Metadata::find_pda(&mint_pubkey).0,
+                metadata_account(&mint_pubkey, "Surfer", "SURF"),
+            )
+            .with_account(
+                MasterEdition::find_pda(&mint_pubkey).0,
+                master_edition_account(0, Some(10)),
+            );
+
+        let mint = fetch_mint(mint_pubkey)
+            .fetch_and_parse(&source)
+            .await
+            .unwrap();
+        assert_eq!(mint.mint_pubkey, mint_pubkey.to_string());
+        assert_eq!((mint.decimals, mint.supply), (6, 5_000_000));
+
+        let token_metadata = mint.token_metadata.unwrap();
+        let metadata = token_metadata.metadata.unwrap();
+        assert_eq!(
+            (metadata.name.as_str(), metadata.symbol.as_str()),
+            ("Surfer", "SURF")
+        );
+        assert_eq!(token_metadata.master_edition.unwrap().max_supply, Some(10));
+    }
+
+    #[tokio::test]
+    async fn fetch_mint_without_metaplex_accounts() {
+        let mint_pubkey = Pubkey::new_unique();
+        let source = MemorySource::new().with_account(mint_pubkey, mint_account(9, 0));
+
+        let mint = fetch_mint(mint_pubkey)
+            .fetch_and_parse(&source)
+            .await
+            .unwrap();
+        assert_eq!(mint.decimals, 9);
+        assert!(mint.token_metadata.is_none());
+    }
+
+    #[tokio::test]
+    async fn fetch_missing_mint() {
+        let mint_pubkey = Pubkey::new_unique();
+
+        let err = fetch_mint(mint_pubkey)
+            .fetch_and_parse(&MemorySource::new())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("Account {} does not exist", mint_pubkey)
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_mints_reports_each_failure() {
+        let (with_metadata, missing, plain) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let source = MemorySource::new()
+            .with_account(with_metadata, mint_account(6, 0))
+            .with_account(
+                Metadata::find_pda(&with_metadata).0,
+                metadata_account(&with_metadata, "Surfer", "SURF"),
+            )
+            .with_account(plain, mint_account(9, 0));
+
+        let mints = fetch_mints(&source, &[with_metadata, missing, plain])
+            .await
+            .unwrap();
+        let pubkeys: Vec<_> = mints.iter().map(|(pubkey, _)| *pubkey).collect();
+        assert_eq!(pubkeys, vec![with_metadata, missing, plain]);
+
+        let mint = mints[0].1.as_ref().unwrap();
+        assert_eq!(
+            mint.name_and_symbol(),
+            Some(("Surfer".to_string(), "SURF".to_string()))
+        );
+        assert_eq!(
+            mints[1].1.as_ref().unwrap_err().to_string(),
+            format!("Account {} does not exist", missing)
+        );
+        let mint = mints[2].1.as_ref().unwrap();
+        assert_eq!(mint.decimals, 9);
+        assert!(mint.token_metadata.is_none());
     }
 }
@@ -0,0 +1,304 @@
+use std::{rc::Rc, str::FromStr};
+
+use clap::Args;
+use solana_sdk::{
+    instruction::Instruction,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    system_instruction,
+};
+use spl_token::solana_program::{program_pack::Pack, pubkey::Pubkey};
+use spl_token_2022::extension::ExtensionType;
+
+use crate::{
+    config::Config,
+    extension::{self, MintExtensionInit},
+    signer::{LocalWallet, SignerSource},
+    token_program::TokenProgram,
+    transaction::{self, SendArgs},
+    utils,
+};
+
+/// Arguments of `surfer mint create`.
+#[derive(Debug, Args)]
+pub struct CreateMint {
+    #[clap(
+        long,
+        value_enum,
+        default_value = "token2022",
+        help = "The token program that will own the new mint."
+    )]
+    pub(crate) program: TokenProgram,
+    #[clap(
+        long,
+        default_value_t = 9,
+        help = "Number of base 10 digits to the right of the decimal place."
+    )]
+    pub(crate) decimals: u8,
+    #[clap(
+        long,
+        value_parser = SignerSource::from_str,
+        help = "Keypair of the new mint account, in the same formats as --keypair. A new keypair is generated if not provided."
+    )]
+    pub(crate) mint_keypair: Option<SignerSource>,
+    #[clap(
+        long,
+        value_parser = utils::parse_pubkey,
+        help = "The mint authority. Defaults to the local wallet."
+    )]
+    pub(crate) mint_authority: Option<Pubkey>,
+    #[clap(
+        long,
+        value_parser = utils::parse_pubkey,
+        help = "The freeze authority. The mint has no freeze authority if not provided."
+    )]
+    pub(crate) freeze_authority: Option<Pubkey>,
+    #[clap(
+        long = "extension",
+        help = "A Token-2022 extension to initialize, as `<ExtensionType>[:key=value,...]`. \
+        Can be repeated, e.g. `--extension TransferFeeConfig:basis-points=50,maximum-fee=5000 \
+        --extension MetadataPointer`."
+    )]
+    pub(crate) extensions: Vec<MintExtensionInit>,
+    /// The signer and transaction flags.
+    #[clap(flatten)]
+    pub send: SendArgs,
+}
+
+impl CreateMint {
+    /// Creates the mint and returns its pubkey with the transaction
+    /// signature, or `None` if the transaction was not sent.
+    pub async fn process_create(
+        &self,
+        config: &Config,
+    ) -> anyhow::Result<Option<(Pubkey, Signature)>> {
+        let local_wallet = LocalWallet::fetch(config)?;
+        let mint_signer: Rc<dyn Signer> = match &self.mint_keypair {
+            Some(source) => source.signer(
+                "mint",
+                &config.signer.presigners,
+                config.transaction.allows_missing_signatures(),
+            )?,
+            None => Rc::new(Keypair::new()),
+        };
+        let mint_pubkey = mint_signer.pubkey();
+        let mint_authority = self.mint_authority.unwrap_or(local_wallet.pubkey());
+
+        let space = self.account_len()?;
+        let lamports = transaction::minimum_balance(config, space).await?;
+        let ixs = self.create_ixs(
+            &local_wallet.fee_payer.pubkey(),
+            &mint_pubkey,
+            &mint_authority,
+            space,
+            lamports,
+        )?;
+
+        let sig = local_wallet
+            .sign_and_send_ixs(ixs, config, &[mint_signer])
+            .await?;
+
+        Ok(sig.map(|sig| (mint_pubkey, sig)))
+    }
+
+    /// Size of the new mint account, including its Token-2022 extensions.
+    fn account_len(&self) -> anyhow::Result<usize> {
+        let extension_types =
+            extension::check_mint_extensions(&self.extensions, self.freeze_authority.as_ref())?;
+
+        match self.program {
+            TokenProgram::LegacyToken => {
+                if !extension_types.is_empty() {
+                    return Err(anyhow::anyhow!(
+                        "Extensions are only supported by the Token2022 program"
+                    ));
+                }
+                Ok(spl_token::state::Mint::LEN)
+            }
+            TokenProgram::Token2022 => Ok(ExtensionType::try_calculate_account_len::<
+                spl_token_2022::state::Mint,
+            >(&extension_types)?),
+        }
+    }
+
+    /// Allocates the mint account, then initializes its extensions and
+    /// finally the mint, which Token-2022 requires in that order.
+    fn create_ixs(
+        &self,
+        payer: &Pubkey,
+        mint: &Pubkey,
+        mint_authority: &Pubkey,
+        space: usize,
+        lamports: u64,
+    ) -> anyhow::Result<Vec<Instruction>> {
+        let program_id: Pubkey = self.program.clone().into();
+
+        let mut ixs = vec![system_instruction::create_account(
+            payer,
+            mint,
+            lamports,
+            space as u64,
+            &program_id,
+        )];
+        for extension in &self.extensions {
+            ixs.push(extension.init_instruction(&program_id, mint, mint_authority)?);
+        }
+        ixs.push(spl_token_2022::instruction::initialize_mint2(
+            &program_id,
+            mint,
+            mint_authority,
+            self.freeze_authority.as_ref(),
+            self.decimals,
+        )?);
+
+        Ok(ixs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program_test::ProgramTest;
+    use spl_token_2022::{
+        extension::transfer_fee::TransferFeeConfig, instruction::TokenInstruction,
+    };
+
+    use super::*;
+    use crate::{
+        extension::ExtensionData,
+        mint::MintWithExtensions,
+        test_fixtures::{get_account, process},
+        transaction::SendArgs,
+    };
+
+    fn create_mint(program: TokenProgram, extensions: Vec<MintExtensionInit>) -> CreateMint {
+        CreateMint {
+            program,
+            decimals: 6,
+            mint_keypair: None,
+            mint_authority: None,
+            freeze_authority: None,
+            extensions,
+            send: SendArgs::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn creates_a_token_2022_mint_with_extensions() {
+        let mut ctx = ProgramTest::default().start_with_context().await;
+        let payer = ctx.payer.pubkey();
+        let mint = Keypair::new();
+        let delegate = Pubkey::new_unique();
+        let create = create_mint(
+            TokenProgram::Token2022,
+            vec![
+                MintExtensionInit::TransferFeeConfig {
+                    authority: None,
+                    withdraw_withheld_authority: None,
+                    basis_points: 50,
+                    maximum_fee: 5_000,
+                },
+                MintExtensionInit::InterestBearingConfig {
+                    authority: None,
+                    rate: 250,
+                },
+                MintExtensionInit::MetadataPointer {
+                    authority: None,
+                    address: None,
+                },
+                MintExtensionInit::PermanentDelegate {
+                    delegate: Some(delegate),
+                },
+            ],
+        );
+
+        let space = create.account_len().unwrap();
+        assert_eq!(
+            space,
+            ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+                ExtensionType::TransferFeeConfig,
+                ExtensionType::InterestBearingConfig,
+                ExtensionType::MetadataPointer,
+                ExtensionType::PermanentDelegate,
+            ])
+            .unwrap()
+        );
+
+        let rent = ctx.banks_client.get_rent().await.unwrap();
+        let ixs = create
+            .create_ixs(
+                &payer,
+                &mint.pubkey(),
+                &payer,
+                space,
+                rent.minimum_balance(space),
+            )
+            .unwrap();
+        let token_ixs: Vec<TokenInstruction> = ixs[1..]
+            .iter()
+            .map(|ix| {
+                assert_eq!(ix.program_id, spl_token_2022::ID);
+                TokenInstruction::unpack(&ix.data).unwrap()
+            })
+            .collect();
+        assert_eq!(token_ixs.len(), 5);
+        let (initialize_mint, extension_inits) = token_ixs.split_last().unwrap();
+        assert!(matches!(
+            initialize_mint,
+            TokenInstruction::InitializeMint2 { decimals: 6, .. }
+        ));
+        assert!(!extension_inits
+            .iter()
+            .any(|ix| matches!(ix, TokenInstruction::InitializeMint2 { .. })));
+
+        process(&mut ctx, &ixs, &[&mint]).await;
+
+        let mint_acc = get_account(&mut ctx, &mint.pubkey()).await;
+        assert_eq!(mint_acc.data.len(), space);
+        let mint_data = MintWithExtensions::try_parse_mint_with_extensions(mint_acc, None).unwrap();
+        assert_eq!(mint_data.base.get_decimals(), 6);
+        let extensions = mint_data.extensions.unwrap();
+        assert_eq!(
+            extensions
+                .iter()
+                .map(|e| e.extension_type())
+                .collect::<Vec<_>>(),
+            vec![
+                ExtensionType::TransferFeeConfig,
+                ExtensionType::InterestBearingConfig,
+                ExtensionType::MetadataPointer,
+                ExtensionType::PermanentDelegate,
+            ]
+        );
+        let Some(ExtensionData::TransferFeeConfig(TransferFeeConfig {
+            newer_transfer_fee, ..
+        })) = extensions.first()
+        else {
+            panic!("expected TransferFeeConfig");
+        };
+        assert_eq!(u16::from(newer_transfer_fee.transfer_fee_basis_points), 50);
+        assert_eq!(u64::from(newer_transfer_fee.maximum_fee), 5_000);
+        let Some(ExtensionData::PermanentDelegate(permanent_delegate)) = extensions.last() else {
+            panic!("expected PermanentDelegate");
+        };
+        assert_eq!(
+            Option::<Pubkey>::from(permanent_delegate.delegate),
+            Some(delegate)
+        );
+    }
+
+    #[test]
+    fn legacy_token_rejects_extensions() {
+        let create = create_mint(
+            TokenProgram::LegacyToken,
+            vec![MintExtensionInit::NonTransferable],
+        );
+        let err = create.account_len().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Extensions are only supported by the Token2022 program"
+        );
+
+        let create = create_mint(TokenProgram::LegacyToken, vec![]);
+        assert_eq!(create.account_len().unwrap(), spl_token::state::Mint::LEN);
+    }
+}
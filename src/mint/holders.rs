@@ -0,0 +1,446 @@
+use std::collections::HashMap;
+
+use clap::Args;
+use colored::*;
+use prettytable::{Row, Table};
+use serde::Serialize;
+use solana_account::Account;
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::{
+    nonblocking::rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use spl_token::solana_program::{program_pack::Pack, pubkey::Pubkey};
+use spl_token_2022::extension::AccountType;
+
+use super::{pretty_mint, MintWithExtensions, PrettyMint};
+use crate::{
+    config::Config,
+    output::UiAmount,
+    source::{self, AccountSource},
+    token_account::TokenAccountWithExtensions,
+    token_program::TokenProgram,
+    utils,
+};
+
+/// The most accounts `getTokenLargestAccounts` returns.
+pub const LARGEST_ACCOUNTS_LIMIT: usize = 20;
+
+/// Arguments of `surfer mint holders`.
+#[derive(Debug, Args)]
+pub struct MintHoldersArgs {
+    #[clap(
+        value_parser = utils::parse_pubkey,
+        help = "The mint whose holders to report. Must be a valid base58 encoded pubkey."
+    )]
+    pub(crate) mint: Pubkey,
+    #[clap(
+        long,
+        help = "Scan every token account of the mint with getProgramAccounts instead of only the 20 largest. Slow on popular mints and disabled by some RPC providers."
+    )]
+    pub(crate) full_scan: bool,
+    #[clap(
+        long,
+        default_value_t = 10,
+        help = "Number of largest holders to list and to compute the top-N share over."
+    )]
+    pub(crate) top: usize,
+}
+
+/// A wallet holding a mint, summed over all its token accounts.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Holder {
+    /// The wallet owning the token accounts.
+    pub owner: String,
+    /// Balance summed over its token accounts.
+    pub balance: UiAmount,
+    /// Number of its token accounts counted.
+    pub token_accounts: usize,
+    /// Percentage of the mint's supply.
+    pub share: Option<f64>,
+}
+
+/// How a mint's supply is spread over its holders.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HoldersReport {
+    /// Address of the mint.
+    pub mint: String,
+    /// Total supply of the mint.
+    pub supply: UiAmount,
+    /// Whether every token account was scanned, rather than only the
+    /// largest ones.
+    pub full_scan: bool,
+    /// Number of token accounts counted.
+    pub token_accounts: usize,
+    /// Owners with a non-zero balance.
+    pub holders: usize,
+    /// The largest holders, largest first.
+    pub top: Vec<Holder>,
+    /// Percentage of the supply held by the `top` holders.
+    pub top_share: Option<f64>,
+    /// Gini coefficient of the holder balances, from 0 when everyone holds
+    /// the same to 1 when one holder has everything. Only computed with a
+    /// full scan, since the largest accounts alone say nothing about the
+    /// rest.
+    pub gini: Option<f64>,
+    /// Sum of the balances of the counted token accounts.
+    pub counted: UiAmount,
+    /// Supply not found in the counted token accounts.
+    pub unaccounted: UiAmount,
+}
+
+impl MintHoldersArgs {
+    /// Fetches the token accounts of the mint and builds the report.
+    pub async fn process_holders(&self, config: &Config) -> anyhow::Result<HoldersReport> {
+        let rpc_client = config.rpc_client()?;
+        let spinner = utils::get_spinner("Fetching mint...")?;
+        let mint_account = source::require_account(&rpc_client, &self.mint).await?;
+        let program_id: Pubkey = TokenProgram::try_from(mint_account.owner)?.into();
+        let mint = pretty_mint(
+            &rpc_client,
+            Some(&self.mint),
+            MintWithExtensions::try_parse_mint_with_extensions(mint_account, None)?,
+        )
+        .await?;
+
+        let token_accounts = match self.full_scan {
+            true => {
+                spinner.set_message("Scanning token accounts...");
+                scan_token_accounts(&rpc_client, &self.mint, &program_id).await?
+            }
+            false => {
+                spinner.set_message("Fetching largest token accounts...");
+                fetch_largest_accounts(&rpc_client, &self.mint).await?
+            }
+        };
+        spinner.finish_and_clear();
+
+        holders_report(&mint, token_accounts, self.top, self.full_scan)
+    }
+}
+
+/// Fetches the largest token accounts of `mint`.
+pub async fn fetch_largest_accounts(
+    rpc_client: &RpcClient,
+    mint: &Pubkey,
+) -> anyhow::Result<Vec<(Pubkey, Account)>> {
+    let addresses = rpc_client
+        .get_token_largest_accounts(mint)
+        .await?
+        .into_iter()
+        .map(|balance| {
+            balance
+                .address
+                .parse::<Pubkey>()
+                .map_err(|e| anyhow::anyhow!("Invalid pubkey `{}`: {}", balance.address, e))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let accounts = AccountSource::get_multiple_accounts(rpc_client, &addresses).await?;
+
+    // Accounts closed since the first request are skipped.
+    Ok(addresses
+        .into_iter()
+        .zip(accounts)
+        .filter_map(|(address, account)| Some((address, account?)))
+        .collect())
+}
+
+/// Fetches every token account of `mint` with `getProgramAccounts`.
+///
+/// Token-2022 accounts with extensions are longer than the base 165 bytes,
+/// so besides the exact size they are matched on the `AccountType` byte
+/// that follows the base account.
+pub async fn scan_token_accounts(
+    rpc_client: &RpcClient,
+    mint: &Pubkey,
+    program_id: &Pubkey,
+) -> anyhow::Result<Vec<(Pubkey, Account)>> {
+    let by_mint = RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, mint.as_ref()));
+    let base_size = RpcFilterType::DataSize(spl_token::state::Account::LEN as u64);
+    let mut filter_sets = vec![vec![by_mint.clone(), base_size]];
+    if *program_id == spl_token_2022::ID {
+        filter_sets.push(vec![
+            by_mint,
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                spl_token::state::Account::LEN,
+                vec![AccountType::Account as u8],
+            )),
+        ]);
+    }
+
+    let mut accounts = Vec::new();
+    for filters in filter_sets {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(rpc_client.commitment()),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        accounts.extend(
+            rpc_client
+                .get_program_accounts_with_config(program_id, config)
+                .await?,
+        );
+    }
+
+    Ok(accounts)
+}
+
+/// Sums token account balances per owner, largest first. Ties are ordered
+/// by owner so the result is stable.
+pub fn aggregate_by_owner(
+    token_accounts: &[TokenAccountWithExtensions],
+) -> Vec<(Pubkey, u64, usize)> {
+    let mut by_owner: HashMap<Pubkey, (u64, usize)> = HashMap::new();
+    for token_account in token_accounts {
+        let entry = by_owner.entry(token_account.base.get_owner()).or_default();
+        entry.0 = entry.0.saturating_add(token_account.base.get_amount());
+        entry.1 += 1;
+    }
+
+    let mut owners: Vec<_> = by_owner
+        .into_iter()
+        .map(|(owner, (amount, accounts))| (owner, amount, accounts))
+        .collect();
+    owners.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    owners
+}
+
+/// Gini coefficient of `amounts`, or `None` if they are empty or all zero.
+pub fn gini(amounts: &[u64]) -> Option<f64> {
+    let mut amounts: Vec<f64> = amounts.iter().map(|amount| *amount as f64).collect();
+    amounts.sort_by(f64::total_cmp);
+    let n = amounts.len() as f64;
+    let total: f64 = amounts.iter().sum();
+    if total == 0.0 {
+        return None;
+    }
+
+    // G = 2 * sum(i * x_i) / (n * sum(x)) - (n + 1) / n, with x ascending and i from 1
+    let weighted: f64 = amounts
+        .iter()
+        .enumerate()
+        .map(|(i, amount)| (i + 1) as f64 * amount)
+        .sum();
+    Some(2.0 * weighted / (n * total) - (n + 1.0) / n)
+}
+
+fn share(amount: u64, supply: u64) -> Option<f64> {
+    (supply > 0).then(|| amount as f64 / supply as f64 * 100.0)
+}
+
+/// Builds the holder report of `mint` from its token accounts. Accounts of
+/// other mints are rejected.
+pub fn holders_report(
+    mint: &PrettyMint,
+    token_accounts: Vec<(Pubkey, Account)>,
+    top: usize,
+    full_scan: bool,
+) -> anyhow::Result<HoldersReport> {
+    let parsed = token_accounts
+        .into_iter()
+        .map(|(pubkey, account)| {
+            let token_account =
+                TokenAccountWithExtensions::try_parse_token_account_with_extensions(account)
+                    .map_err(|e| {
+                        anyhow::anyhow!("Error parsing token account {}: {}", pubkey, e)
+                    })?;
+            if token_account.base.get_mint().to_string() != mint.mint_pubkey {
+                return Err(anyhow::anyhow!(
+                    "Token account {} is not an account of mint {}",
+                    pubkey,
+                    mint.mint_pubkey
+                ));
+            }
+            Ok(token_account)
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let owners: Vec<_> = aggregate_by_owner(&parsed)
+        .into_iter()
+        .filter(|(_, amount, _)| *amount > 0)
+        .collect();
+    let counted = parsed.iter().fold(0u64, |sum, account| {
+        sum.saturating_add(account.base.get_amount())
+    });
+    let top_amount = owners
+        .iter()
+        .take(top)
+        .fold(0u64, |sum, (_, amount, _)| sum.saturating_add(*amount));
+    let amounts: Vec<u64> = owners.iter().map(|(_, amount, _)| *amount).collect();
+
+    Ok(HoldersReport {
+        mint: mint.mint_pubkey.clone(),
+        supply: UiAmount::new(mint.supply, mint.decimals),
+        full_scan,
+        token_accounts: parsed.len(),
+        holders: owners.len(),
+        top: owners
+            .iter()
+            .take(top)
+            .map(|(owner, amount, accounts)| Holder {
+                owner: owner.to_string(),
+                balance: UiAmount::new(*amount, mint.decimals),
+                token_accounts: *accounts,
+                share: share(*amount, mint.supply),
+            })
+            .collect(),
+        top_share: share(top_amount, mint.supply),
+        gini: full_scan.then(|| gini(&amounts)).flatten(),
+        counted: UiAmount::new(counted, mint.decimals),
+        unaccounted: UiAmount::new(mint.supply.saturating_sub(counted), mint.decimals),
+    })
+}
+
+fn format_share(share: Option<f64>) -> String {
+    share.map_or("-".to_string(), |share| format!("{:.2}%", share))
+}
+
+impl HoldersReport {
+    /// Prints the report as a table.
+    pub fn print(&self) {
+        let mut table = Table::new();
+        table.add_row(Row::new(vec![utils::to_header_cell("Mint Holders")]));
+        for (key, value) in [
+            ("Mint", self.mint.clone()),
+            ("Supply", self.supply.to_string()),
+            (
+                "Scanned",
+                match self.full_scan {
+                    true => "All token accounts".to_string(),
+                    false => format!("Largest {} token accounts", LARGEST_ACCOUNTS_LIMIT),
+                },
+            ),
+            ("Token Accounts", self.token_accounts.to_string()),
+            ("Holders", self.holders.to_string()),
+            (
+                &format!("Top {} Share", self.top.len()),
+                format_share(self.top_share),
+            ),
+            (
+                "Gini",
+                match (self.gini, self.full_scan) {
+                    (Some(gini), _) => format!("{:.4}", gini),
+                    (None, true) => "-".to_string(),
+                    (None, false) => "Needs --full-scan".to_string(),
+                },
+            ),
+            ("Counted", self.counted.to_string()),
+            ("Unaccounted", self.unaccounted.to_string()),
+        ] {
+            table.add_row(Row::new(vec![
+                utils::to_key_cell(key),
+                utils::to_value_cell(&value),
+            ]));
+        }
+        table.printstd();
+
+        if self.top.is_empty() {
+            return;
+        }
+        let mut holders = Table::new();
+        holders.add_row(Row::new(
+            ["#", "Owner", "Balance", "Share", "Accounts"]
+                .into_iter()
+                .map(utils::to_header_cell)
+                .collect(),
+        ));
+        for (rank, holder) in self.top.iter().enumerate() {
+            holders.add_row(Row::new(vec![
+                utils::to_key_cell(&(rank + 1).to_string()),
+                utils::to_value_cell(&holder.owner),
+                utils::to_value_cell(&holder.balance.ui_amount_string),
+                utils::to_value_cell(&format_share(holder.share)),
+                utils::to_value_cell(&holder.token_accounts.to_string()),
+            ]));
+        }
+        holders.printstd();
+
+        if !self.full_scan && self.unaccounted.amount != "0" {
+            println!(
+                " {} the rest of the supply sits outside the largest accounts, use --full-scan to count it",
+                "Note:".yellow().bold()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::token_account;
+
+    fn holder_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> (Pubkey, Account) {
+        (Pubkey::new_unique(), token_account(mint, owner, amount))
+    }
+
+    fn pretty_mint(mint: &Pubkey, supply: u64) -> PrettyMint {
+        PrettyMint {
+            mint_pubkey: mint.to_string(),
+            mint_authority: None,
+            supply,
+            decimals: 2,
+            is_initialized: true,
+            freeze_authority: None,
+            extensions: None,
+            token_metadata: None,
+            unix_timestamp: None,
+        }
+    }
+
+    #[test]
+    fn gini_of_even_and_concentrated_holdings() {
+        assert_eq!(gini(&[]), None);
+        assert_eq!(gini(&[0, 0]), None);
+        assert_eq!(gini(&[5, 5, 5, 5]), Some(0.0));
+        // One of four holders has everything: (n - 1) / n.
+        assert_eq!(gini(&[0, 0, 0, 100]), Some(0.75));
+        assert!((gini(&[1, 2, 3, 4]).unwrap() - 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn report_aggregates_owners_and_reconciles_supply() {
+        let (mint, whale, minnow, empty) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let token_accounts = vec![
+            holder_account(&mint, &whale, 600),
+            holder_account(&mint, &minnow, 100),
+            holder_account(&mint, &whale, 200),
+            holder_account(&mint, &empty, 0),
+        ];
+
+        let report = holders_report(&pretty_mint(&mint, 1_000), token_accounts, 1, true).unwrap();
+        assert_eq!((report.token_accounts, report.holders), (4, 2));
+        assert_eq!(report.top.len(), 1);
+        assert_eq!(report.top[0].owner, whale.to_string());
+        assert_eq!(report.top[0].token_accounts, 2);
+        assert_eq!(report.top[0].balance.ui_amount_string, "8");
+        assert_eq!(report.top_share, Some(80.0));
+        assert!((report.gini.unwrap() - 0.3888888888888889).abs() < 1e-12);
+        assert_eq!(report.counted.amount, "900");
+        assert_eq!(report.unaccounted.amount, "100");
+    }
+
+    #[test]
+    fn report_rejects_accounts_of_other_mints() {
+        let mint = Pubkey::new_unique();
+        let token_accounts = vec![holder_account(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            1,
+        )];
+
+        let err = holders_report(&pretty_mint(&mint, 1), token_accounts, 10, false).unwrap_err();
+        assert!(err.to_string().contains("is not an account of mint"));
+    }
+}
@@ -1,34 +1,541 @@
-use clap::Subcommand;
+use std::{str::FromStr, time::Duration};
 
-#[derive(Debug, Subcommand)]
+use clap::{Args, Subcommand};
+use spl_token::solana_program::{instruction::Instruction, pubkey::Pubkey};
+use spl_token_2022::instruction::AuthorityType;
+
+use crate::{
+    amount::Amount,
+    config::Config,
+    mint::MintWithExtensions,
+    output,
+    signer::{LocalWallet, SignerSource},
+    source::{self, AccountSource},
+    token_program::TokenProgram,
+    transaction::{self, SendArgs},
+    utils::{self, optional_pubkey_or_prompt, pubkey_or_prompt, value_or_prompt},
+};
+
+/// Arguments of `surfer mint ix`. Prompts for the instruction if none is
+/// given.
+#[derive(Debug, Args)]
+pub struct MintIxArgs {
+    /// The signer and transaction flags.
+    #[clap(flatten)]
+    pub send: SendArgs,
+    #[clap(subcommand)]
+    pub(crate) ix: Option<MintInstructions>,
+}
+
+/// The mint instructions `surfer mint ix` can send.
+#[derive(Debug, Clone, Subcommand)]
 pub enum MintInstructions {
-    InitializeMint,
-    SetAuthority,
-    MintTo,
-    MintToChecked,
-    InitilaizeMint2,
+    /// Initialize an allocated mint account
+    InitializeMint(InitializeMintArgs),
+    /// Change or remove one of the mint authorities
+    SetAuthority(SetAuthorityArgs),
+    /// Mint new tokens to a token account
+    MintTo(MintToArgs),
+    /// Mint new tokens to a token account, asserting the mint decimals
+    MintToChecked(MintToArgs),
+    /// Initialize an allocated mint account without requiring the rent sysvar
+    InitializeMint2(InitializeMintArgs),
+}
+
+/// Arguments of `InitializeMint` and `InitializeMint2`.
+#[derive(Debug, Clone, Default, Args)]
+pub struct InitializeMintArgs {
+    #[clap(long, value_parser = utils::parse_pubkey, help = "The mint account to initialize.")]
+    pub(crate) mint: Option<Pubkey>,
+    #[clap(
+        long,
+        help = "Number of base 10 digits to the right of the decimal place."
+    )]
+    pub(crate) decimals: Option<u8>,
+    #[clap(
+        long,
+        value_parser = utils::parse_pubkey,
+        help = "The mint authority. Defaults to the local wallet."
+    )]
+    pub(crate) mint_authority: Option<Pubkey>,
+    #[clap(
+        long,
+        value_parser = utils::parse_pubkey,
+        help = "The freeze authority. The mint has no freeze authority if not provided."
+    )]
+    pub(crate) freeze_authority: Option<Pubkey>,
+}
+
+/// Arguments of `SetAuthority`.
+#[derive(Debug, Clone, Default, Args)]
+pub struct SetAuthorityArgs {
+    #[clap(long, value_parser = utils::parse_pubkey, help = "The mint whose authority changes.")]
+    pub(crate) mint: Option<Pubkey>,
+    #[clap(
+        long,
+        value_parser = authority_type_from_select_str,
+        help = "The authority to change, e.g. MintTokens or FreezeAccount."
+    )]
+    pub(crate) authority_type: Option<AuthorityType>,
+    #[clap(
+        long,
+        value_parser = utils::parse_pubkey,
+        conflicts_with = "disable",
+        help = "The new authority."
+    )]
+    pub(crate) new_authority: Option<Pubkey>,
+    #[clap(long, help = "Remove the authority instead of replacing it.")]
+    pub(crate) disable: bool,
+    #[clap(
+        long,
+        value_parser = SignerSource::from_str,
+        help = "Keypair of the current authority, in the same formats as --keypair. Defaults to the wallet."
+    )]
+    pub(crate) authority: Option<SignerSource>,
+}
+
+/// Arguments of `MintTo` and `MintToChecked`.
+#[derive(Debug, Clone, Default, Args)]
+pub struct MintToArgs {
+    #[clap(long, value_parser = utils::parse_pubkey, help = "The mint to mint from.")]
+    pub(crate) mint: Option<Pubkey>,
+    #[clap(long, value_parser = utils::parse_pubkey, help = "The destination token account.")]
+    pub(crate) to: Option<Pubkey>,
+    #[clap(long, help = "The amount to mint in UI units, e.g. 1.5.")]
+    pub(crate) amount: Option<Amount>,
+    #[clap(
+        long,
+        help = "Interpret --amount as raw base units instead of UI units."
+    )]
+    pub(crate) raw: bool,
+    #[clap(
+        long,
+        value_parser = SignerSource::from_str,
+        help = "Keypair of the mint authority, in the same formats as --keypair. Defaults to the wallet."
+    )]
+    pub(crate) authority: Option<SignerSource>,
 }
 
 impl MintInstructions {
+    /// Names of the instructions, for the interactive prompt.
     pub fn to_select_vec() -> Vec<&'static str> {
         vec![
             "InitializeMint",
             "SetAuthority",
             "MintTo",
             "MintToChecked",
-            "InitilaizeMint2",
+            "InitializeMint2",
         ]
     }
 
+    /// The instruction picked at the prompt, with its arguments left to be
+    /// prompted for.
     pub fn from_select_str(select_str: &str) -> anyhow::Result<Self> {
         match select_str {
-            "InitializeMint" => Ok(Self::InitializeMint),
-            "SetAuthority" => Ok(Self::SetAuthority),
-            "MintTo" => Ok(Self::MintTo),
-            "MintToChecked" => Ok(Self::MintToChecked),
-            "InitilaizeMint2" => Ok(Self::InitilaizeMint2),
+            "InitializeMint" => Ok(Self::InitializeMint(InitializeMintArgs::default())),
+            "SetAuthority" => Ok(Self::SetAuthority(SetAuthorityArgs::default())),
+            "MintTo" => Ok(Self::MintTo(MintToArgs::default())),
+            "MintToChecked" => Ok(Self::MintToChecked(MintToArgs::default())),
+            "InitializeMint2" => Ok(Self::InitializeMint2(InitializeMintArgs::default())),
 
             _ => Err(anyhow::anyhow!("Invalid mint instruction: {}", select_str)),
         }
     }
+
+    /// Builds the instruction, prompting for missing arguments, then signs
+    /// and sends it.
+    pub async fn process_ix(&self, config: &Config) -> anyhow::Result<()> {
+        let local_wallet = LocalWallet::fetch(config)?;
+        let rpc_client = config.rpc_client()?;
+
+        let (ix, signers) = match self {
+            MintInstructions::InitializeMint(args) | MintInstructions::InitializeMint2(args) => {
+                output::print_heading("Initialize mint", config.output);
+
+                let mint_pubkey = pubkey_or_prompt(args.mint, "--mint", "Mint account pubkey")?;
+                let program_id = fetch_token_program(&rpc_client, &mint_pubkey).await?;
+
+                let decimals = value_or_prompt(args.decimals, "--decimals", "Decimals", Some("9"))?;
+                let mint_authority = optional_pubkey_or_prompt(
+                    args.mint_authority,
+                    "Mint authority pubkey (leave empty to use local wallet)",
+                )?
+                .unwrap_or(local_wallet.pubkey());
+                let freeze_authority = optional_pubkey_or_prompt(
+                    args.freeze_authority,
+                    "Freeze authority pubkey (leave empty for none)",
+                )?;
+
+                let ix = if let MintInstructions::InitializeMint(_) = self {
+                    build_initialize_mint_ix(
+                        &program_id,
+                        &mint_pubkey,
+                        &mint_authority,
+                        freeze_authority.as_ref(),
+                        decimals,
+                    )?
+                } else {
+                    build_initialize_mint2_ix(
+                        &program_id,
+                        &mint_pubkey,
+                        &mint_authority,
+                        freeze_authority.as_ref(),
+                        decimals,
+                    )?
+                };
+
+                (ix, vec![])
+            }
+            MintInstructions::SetAuthority(args) => {
+                output::print_heading("Set mint authority", config.output);
+
+                let mint_pubkey = pubkey_or_prompt(args.mint, "--mint", "Mint account pubkey")?;
+                let program_id = fetch_token_program(&rpc_client, &mint_pubkey).await?;
+
+                let authority_type = utils::select_or_prompt(
+                    args.authority_type.clone(),
+                    "--authority-type",
+                    "Select the authority to change",
+                    mint_authority_types(&TokenProgram::try_from(program_id)?),
+                    authority_type_from_select_str,
+                )?;
+                let new_authority = match (args.new_authority, args.disable) {
+                    (_, true) => None,
+                    (Some(new_authority), false) => Some(new_authority),
+                    (None, false) if utils::can_prompt() => utils::get_optional_pubkey_from_prompt(
+                        "New authority pubkey (leave empty for none)",
+                    )?,
+                    (None, false) => {
+                        return Err(anyhow::anyhow!(
+                            "Missing `--new-authority`, pass `--disable` to remove the authority"
+                        ))
+                    }
+                };
+
+                let authority = local_wallet.authority(args.authority.as_ref(), "authority")?;
+
+                let ix = build_set_authority_ix(
+                    &program_id,
+                    &mint_pubkey,
+                    new_authority.as_ref(),
+                    authority_type,
+                    &authority.pubkey,
+                    &authority.multisig_signers(),
+                )?;
+
+                (ix, authority.signers)
+            }
+            MintInstructions::MintTo(args) | MintInstructions::MintToChecked(args) => {
+                output::print_heading("Mint tokens", config.output);
+
+                let mint_pubkey = pubkey_or_prompt(args.mint, "--mint", "Mint account pubkey")?;
+                let dest_pubkey =
+                    pubkey_or_prompt(args.to, "--to", "Destination token account pubkey")?;
+
+                let amount = value_or_prompt(
+                    args.amount.clone(),
+                    "--amount",
+                    "Amount to mint (e.g. 1.5)",
+                    None,
+                )?;
+
+                let spinner = utils::get_spinner("Fetching mint data...")?;
+                spinner.enable_steady_tick(Duration::from_millis(100));
+                let mint_acc = source::require_account(&rpc_client, &mint_pubkey).await?;
+                spinner.finish_and_clear();
+
+                let mint_data = MintWithExtensions::try_parse_mint_with_extensions(mint_acc, None)?;
+
+                let amount = amount.to_raw_amount(mint_data.base.get_decimals(), None, args.raw)?;
+                let authority =
+                    local_wallet.authority(args.authority.as_ref(), "mint authority")?;
+
+                let ix = if let MintInstructions::MintTo(_) = self {
+                    build_mint_to_ix(
+                        &mint_pubkey,
+                        &mint_data,
+                        &dest_pubkey,
+                        &authority.pubkey,
+                        &authority.multisig_signers(),
+                        amount,
+                    )?
+                } else {
+                    build_mint_to_checked_ix(
+                        &mint_pubkey,
+                        &mint_data,
+                        &dest_pubkey,
+                        &authority.pubkey,
+                        &authority.multisig_signers(),
+                        amount,
+                    )?
+                };
+
+                (ix, authority.signers)
+            }
+        };
+
+        if let Some(sig) = local_wallet
+            .sign_and_send_ixs(vec![ix], config, &signers)
+            .await?
+        {
+            transaction::print_signature(&sig, config.output)?;
+        }
+        Ok(())
+    }
+}
+
+/// Builds an `InitializeMint` for the mint account `mint`, owned by
+/// `program_id`. The mint has no freeze authority if `freeze_authority` is
+/// `None`.
+pub fn build_initialize_mint_ix(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    mint_authority: &Pubkey,
+    freeze_authority: Option<&Pubkey>,
+    decimals: u8,
+) -> anyhow::Result<Instruction> {
+    Ok(spl_token_2022::instruction::initialize_mint(
+        program_id,
+        mint,
+        mint_authority,
+        freeze_authority,
+        decimals,
+    )?)
+}
+
+/// Builds an `InitializeMint2`, which unlike `InitializeMint` doesn't take
+/// the rent sysvar.
+pub fn build_initialize_mint2_ix(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    mint_authority: &Pubkey,
+    freeze_authority: Option<&Pubkey>,
+    decimals: u8,
+) -> anyhow::Result<Instruction> {
+    Ok(spl_token_2022::instruction::initialize_mint2(
+        program_id,
+        mint,
+        mint_authority,
+        freeze_authority,
+        decimals,
+    )?)
+}
+
+/// Builds a `SetAuthority` changing the `authority_type` authority of `mint`
+/// to `new_authority`, or removing it if `None`. `multisig_signers` is empty
+/// unless `authority` is a multisig.
+pub fn build_set_authority_ix(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    new_authority: Option<&Pubkey>,
+    authority_type: AuthorityType,
+    authority: &Pubkey,
+    multisig_signers: &[&Pubkey],
+) -> anyhow::Result<Instruction> {
+    Ok(spl_token_2022::instruction::set_authority(
+        program_id,
+        mint,
+        new_authority,
+        authority_type,
+        authority,
+        multisig_signers,
+    )?)
+}
+
+/// Builds a `MintTo` of `amount` base units against the token program owning
+/// the parsed `mint_data`. `multisig_signers` is empty unless
+/// `mint_authority` is a multisig.
+pub fn build_mint_to_ix(
+    mint: &Pubkey,
+    mint_data: &MintWithExtensions,
+    destination: &Pubkey,
+    mint_authority: &Pubkey,
+    multisig_signers: &[&Pubkey],
+    amount: u64,
+) -> anyhow::Result<Instruction> {
+    Ok(spl_token_2022::instruction::mint_to(
+        &mint_data.base.token_program().into(),
+        mint,
+        destination,
+        mint_authority,
+        multisig_signers,
+        amount,
+    )?)
+}
+
+/// Same as [`build_mint_to_ix`], but the instruction also asserts the
+/// decimals of `mint_data`.
+pub fn build_mint_to_checked_ix(
+    mint: &Pubkey,
+    mint_data: &MintWithExtensions,
+    destination: &Pubkey,
+    mint_authority: &Pubkey,
+    multisig_signers: &[&Pubkey],
+    amount: u64,
+) -> anyhow::Result<Instruction> {
+    Ok(spl_token_2022::instruction::mint_to_checked(
+        &mint_data.base.token_program().into(),
+        mint,
+        destination,
+        mint_authority,
+        multisig_signers,
+        amount,
+        mint_data.base.get_decimals(),
+    )?)
+}
+
+/// Fetches `pubkey` and returns the token program that owns it.
+async fn fetch_token_program<S: AccountSource + ?Sized>(
+    source: &S,
+    pubkey: &Pubkey,
+) -> anyhow::Result<Pubkey> {
+    let acc = source::require_account(source, pubkey).await?;
+    TokenProgram::try_from(acc.owner)?;
+
+    Ok(acc.owner)
+}
+
+fn mint_authority_types(token_program: &TokenProgram) -> Vec<&'static str> {
+    match token_program {
+        TokenProgram::LegacyToken => vec!["MintTokens", "FreezeAccount"],
+        TokenProgram::Token2022 => vec![
+            "MintTokens",
+            "FreezeAccount",
+            "CloseMint",
+            "TransferFeeConfig",
+            "WithheldWithdraw",
+            "InterestRate",
+            "PermanentDelegate",
+            "ConfidentialTransferMint",
+            "TransferHookProgramId",
+            "ConfidentialTransferFeeConfig",
+            "MetadataPointer",
+            "GroupPointer",
+            "GroupMemberPointer",
+        ],
+    }
+}
+
+fn authority_type_from_select_str(select_str: &str) -> anyhow::Result<AuthorityType> {
+    match select_str {
+        "MintTokens" => Ok(AuthorityType::MintTokens),
+        "FreezeAccount" => Ok(AuthorityType::FreezeAccount),
+        "CloseMint" => Ok(AuthorityType::CloseMint),
+        "TransferFeeConfig" => Ok(AuthorityType::TransferFeeConfig),
+        "WithheldWithdraw" => Ok(AuthorityType::WithheldWithdraw),
+        "InterestRate" => Ok(AuthorityType::InterestRate),
+        "PermanentDelegate" => Ok(AuthorityType::PermanentDelegate),
+        "ConfidentialTransferMint" => Ok(AuthorityType::ConfidentialTransferMint),
+        "TransferHookProgramId" => Ok(AuthorityType::TransferHookProgramId),
+        "ConfidentialTransferFeeConfig" => Ok(AuthorityType::ConfidentialTransferFeeConfig),
+        "MetadataPointer" => Ok(AuthorityType::MetadataPointer),
+        "GroupPointer" => Ok(AuthorityType::GroupPointer),
+        "GroupMemberPointer" => Ok(AuthorityType::GroupMemberPointer),
+
+        _ => Err(anyhow::anyhow!("Invalid authority type: {}", select_str)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program_test::{ProgramTest, ProgramTestContext};
+    use solana_sdk::{
+        signature::{Keypair, Signer},
+        system_instruction,
+    };
+    use spl_associated_token_account::get_associated_token_address_with_program_id;
+    use spl_token_2022::{solana_program::program_pack::Pack, state::Mint};
+
+    use super::*;
+    use crate::{
+        mint::MintType,
+        test_fixtures::{get_account, process},
+        token_account::ixs::build_create_ix,
+    };
+
+    async fn get_mint(ctx: &mut ProgramTestContext, mint: &Pubkey) -> MintWithExtensions {
+        let acc = get_account(ctx, mint).await;
+        MintWithExtensions::try_parse_mint_with_extensions(acc, None).unwrap()
+    }
+
+    fn supply_and_mint_authority(mint_data: &MintWithExtensions) -> (u64, Option<Pubkey>) {
+        match &mint_data.base {
+            MintType::LegacyMint(mint) => (mint.supply, mint.mint_authority.into()),
+            MintType::Mint2022(mint) => (mint.supply, mint.mint_authority.into()),
+        }
+    }
+
+    async fn initialize_mint_and_disable(program: TokenProgram) {
+        let program_id: Pubkey = program.into();
+        let mut ctx = ProgramTest::default().start_with_context().await;
+        let payer = ctx.payer.pubkey();
+        let mint = Keypair::new();
+
+        let rent = ctx.banks_client.get_rent().await.unwrap();
+        process(
+            &mut ctx,
+            &[
+                system_instruction::create_account(
+                    &payer,
+                    &mint.pubkey(),
+                    rent.minimum_balance(Mint::LEN),
+                    Mint::LEN as u64,
+                    &program_id,
+                ),
+                build_initialize_mint2_ix(&program_id, &mint.pubkey(), &payer, Some(&payer), 6)
+                    .unwrap(),
+            ],
+            &[&mint],
+        )
+        .await;
+
+        let mint_data = get_mint(&mut ctx, &mint.pubkey()).await;
+        assert_eq!(Pubkey::from(mint_data.base.token_program()), program_id);
+        assert_eq!(mint_data.base.get_decimals(), 6);
+
+        let mint_acc = get_account(&mut ctx, &mint.pubkey()).await;
+        let destination =
+            get_associated_token_address_with_program_id(&payer, &mint.pubkey(), &program_id);
+        process(
+            &mut ctx,
+            &[
+                build_create_ix(&payer, &payer, &mint.pubkey(), &mint_acc).unwrap(),
+                build_mint_to_ix(&mint.pubkey(), &mint_data, &destination, &payer, &[], 1_000)
+                    .unwrap(),
+                build_mint_to_checked_ix(
+                    &mint.pubkey(),
+                    &mint_data,
+                    &destination,
+                    &payer,
+                    &[],
+                    500,
+                )
+                .unwrap(),
+                build_set_authority_ix(
+                    &program_id,
+                    &mint.pubkey(),
+                    None,
+                    AuthorityType::MintTokens,
+                    &payer,
+                    &[],
+                )
+                .unwrap(),
+            ],
+            &[],
+        )
+        .await;
+
+        let mint_data = get_mint(&mut ctx, &mint.pubkey()).await;
+        assert_eq!(supply_and_mint_authority(&mint_data), (1_500, None));
+    }
+
+    #[tokio::test]
+    async fn legacy_token_initialize_mint_and_disable() {
+        initialize_mint_and_disable(TokenProgram::LegacyToken).await;
+    }
+
+    #[tokio::test]
+    async fn token_2022_initialize_mint_and_disable() {
+        initialize_mint_and_disable(TokenProgram::Token2022).await;
+    }
 }
@@ -1,51 +1,160 @@
+use colored::*;
 use mpl_token_metadata::accounts::{MasterEdition, Metadata};
-use solana_account::ReadableAccount;
-use solana_client::nonblocking::rpc_client::RpcClient;
+use serde_json::json;
+use solana_account::{Account, ReadableAccount};
 use spl_pod::solana_pubkey::Pubkey;
 
+use crate::source::AccountSource;
+
+/// The Metaplex accounts derived from a mint, either of which may be missing.
 #[derive(Debug, Clone)]
 pub struct TokenMetadata {
+    /// The metadata account.
     pub metadata: Option<Metadata>,
+    /// The master edition account, present for NFTs.
     pub master_edition: Option<MasterEdition>,
 }
 
 impl TokenMetadata {
-    pub async fn fetch_and_parse(mint_pubkey: Pubkey, rpc: &RpcClient) -> Option<TokenMetadata> {
-        let (mut metadata, master_edition) = tokio::join!(
-            fetch_and_parse_metadata(mint_pubkey, rpc),
-            fetch_and_parse_master_edition(mint_pubkey, rpc)
-        );
-
-        metadata = metadata.map(|mut m| {
-            m.uri = m.uri.trim_end_matches('\0').to_string();
-            m.name = m.name.trim_end_matches('\0').to_string();
-            m.symbol = m.symbol.trim_end_matches('\0').to_string();
-            m
-        });
+    /// Fetches the metadata and master edition PDAs of `mint_pubkey`. Accounts
+    /// that are missing or fail to parse are left out, and `None` is returned
+    /// if neither is found.
+    pub async fn fetch_and_parse<S: AccountSource + ?Sized>(
+        mint_pubkey: Pubkey,
+        source: &S,
+    ) -> Option<TokenMetadata> {
+        let pdas = [
+            Metadata::find_pda(&mint_pubkey).0,
+            MasterEdition::find_pda(&mint_pubkey).0,
+        ];
+        let accounts = source.get_multiple_accounts(&pdas).await.ok()?;
+
+        Self::from_accounts(accounts[0].as_ref(), accounts[1].as_ref())
+    }
+
+    /// Parses already fetched metadata and master edition accounts, as with
+    /// [`TokenMetadata::fetch_and_parse`].
+    pub fn from_accounts(
+        metadata: Option<&Account>,
+        master_edition: Option<&Account>,
+    ) -> Option<TokenMetadata> {
+        let metadata = metadata.and_then(|acc| parse_metadata(acc.data()));
+        let master_edition =
+            master_edition.and_then(|acc| MasterEdition::from_bytes(acc.data()).ok());
+        if metadata.is_none() && master_edition.is_none() {
+            return None;
+        }
 
         Some(Self {
             metadata,
             master_edition,
         })
     }
+
+    /// Prints the metadata and master edition, whichever are present.
+    pub fn print(&self) {
+        println!();
+        if let Some(metadata) = &self.metadata {
+            println!(
+                " {} : {}",
+                "Token Metadata".purple().bold(),
+                format!("{:#?}", metadata).cyan().bold()
+            );
+        }
+
+        println!();
+
+        if let Some(master_edition) = &self.master_edition {
+            println!(
+                " {} : {}",
+                "Master Edition".purple().bold(),
+                format!("{:#?}", master_edition).cyan().bold()
+            );
+        }
+    }
+}
+
+/// Parses a Metaplex metadata account, trimming the NUL padding of its
+/// fixed-size strings.
+pub fn parse_metadata(data: &[u8]) -> Option<Metadata> {
+    Metadata::from_bytes(data).ok().map(|mut m| {
+        m.uri = m.uri.trim_end_matches('\0').to_string();
+        m.name = m.name.trim_end_matches('\0').to_string();
+        m.symbol = m.symbol.trim_end_matches('\0').to_string();
+        m
+    })
 }
 
-pub async fn fetch_and_parse_metadata(mint_pubkey: Pubkey, rpc: &RpcClient) -> Option<Metadata> {
+/// Fetches and parses the Metaplex metadata PDA of `mint_pubkey`.
+pub async fn fetch_and_parse_metadata<S: AccountSource + ?Sized>(
+    mint_pubkey: Pubkey,
+    source: &S,
+) -> Option<Metadata> {
     let metadata_pubkey = Metadata::find_pda(&mint_pubkey).0;
-    rpc.get_account(&metadata_pubkey)
+    source
+        .get_account(&metadata_pubkey)
         .await
-        .and_then(|acc| Metadata::from_bytes(acc.data()).map_err(|e| e.into()))
         .ok()
+        .flatten()
+        .and_then(|acc| parse_metadata(acc.data()))
 }
 
-pub async fn fetch_and_parse_master_edition(
+/// Fetches and parses the Metaplex master edition PDA of `mint_pubkey`.
+pub async fn fetch_and_parse_master_edition<S: AccountSource + ?Sized>(
     mint_pubkey: Pubkey,
-    rpc: &RpcClient,
+    source: &S,
 ) -> Option<MasterEdition> {
     let master_edition_pubkey = MasterEdition::find_pda(&mint_pubkey).0;
 
-    rpc.get_account(&master_edition_pubkey)
+    source
+        .get_account(&master_edition_pubkey)
         .await
-        .and_then(|acc| MasterEdition::from_bytes(acc.data()).map_err(|e| e.into()))
         .ok()
+        .flatten()
+        .and_then(|acc| MasterEdition::from_bytes(acc.data()).ok())
+}
+
+impl TokenMetadata {
+    /// The metadata account as JSON, if present.
+    pub fn metadata_to_json(&self) -> Option<serde_json::Value> {
+        self.metadata.as_ref().map(|m| {
+            json!({
+                "address": Metadata::find_pda(&m.mint).0.to_string(),
+                "updateAuthority": m.update_authority.to_string(),
+                "mint": m.mint.to_string(),
+                "name": m.name,
+                "symbol": m.symbol,
+                "uri": m.uri,
+                "sellerFeeBasisPoints": m.seller_fee_basis_points,
+                "creators": m.creators.as_ref().map(|creators| {
+                    creators
+                        .iter()
+                        .map(|c| json!({
+                            "address": c.address.to_string(),
+                            "verified": c.verified,
+                            "share": c.share,
+                        }))
+                        .collect::<Vec<_>>()
+                }),
+                "primarySaleHappened": m.primary_sale_happened,
+                "isMutable": m.is_mutable,
+                "editionNonce": m.edition_nonce,
+                "tokenStandard": m.token_standard.as_ref().map(|ts| format!("{:?}", ts)),
+                "collection": m.collection.as_ref().map(|c| json!({
+                    "key": c.key.to_string(),
+                    "verified": c.verified,
+                })),
+            })
+        })
+    }
+
+    /// The master edition account as JSON, if present.
+    pub fn master_edition_to_json(&self) -> Option<serde_json::Value> {
+        self.master_edition.as_ref().map(|me| {
+            json!({
+                "supply": me.supply.to_string(),
+                "maxSupply": me.max_supply.map(|max| max.to_string()),
+            })
+        })
+    }
 }
@@ -1,46 +1,137 @@
+/// Mint parsing and printing.
 pub mod account;
+/// `surfer mint create`.
+pub mod create;
+/// Holder concentration reports.
+pub mod holders;
+/// Mint instructions.
 pub mod ixs;
+/// Metaplex metadata and master editions.
 pub mod metadata;
 
 use std::time::Duration;
 
 pub use account::*;
 use clap::Subcommand;
-use inquire::Select;
+use colored::*;
+pub use create::*;
+pub use holders::*;
 pub use ixs::*;
 pub use metadata::*;
+use serde_json::json;
+use spl_token::solana_program::pubkey::Pubkey;
 
-use crate::utils;
+use crate::{
+    config::Config,
+    input,
+    output::{self, OutputFormat},
+    source::AccountSource,
+    transaction, utils,
+};
 
+/// Fetch, create and send instructions to mints
 #[derive(Debug, Subcommand)]
 pub enum MintCommands {
+    /// Fetch one or more mints with their Metaplex metadata
     Fetch(FetchMint),
-    Ix,
+    /// Create and initialize a new mint
+    Create(CreateMint),
+    /// Send a single mint instruction
+    Ix(MintIxArgs),
+    /// Report the largest holders of a mint and how concentrated its supply is
+    Holders(MintHoldersArgs),
+}
+
+/// Builds the printable mint, fetching the cluster time from `source` for
+/// interest-bearing mints. Sources without the clock sysvar, as for accounts
+/// read from files, leave accrued interest out.
+pub async fn pretty_mint<S: AccountSource + ?Sized>(
+    source: &S,
+    mint_pubkey: Option<&Pubkey>,
+    mint_data: MintWithExtensions,
+) -> anyhow::Result<PrettyMint> {
+    let mut mint: PrettyMint = MintWithPubkey {
+        mint_data,
+        pubkey: input::display_pubkey(mint_pubkey),
+    }
+    .into();
+    if mint.interest_bearing_config().is_some() {
+        mint.unix_timestamp = utils::fetch_unix_timestamp(source).await?;
+    }
+
+    Ok(mint)
 }
 
 impl MintCommands {
-    pub async fn process(&self) -> anyhow::Result<()> {
+    /// Runs the subcommand and prints its result.
+    pub async fn process(&self, config: &Config) -> anyhow::Result<()> {
+        let output = config.output;
         match self {
+            MintCommands::Fetch(f) if f.is_batch() => {
+                let spinner = utils::get_spinner("Fetching mints...")?;
+                let mints = f.fetch_batch(&config.rpc_client()?).await?;
+                spinner.finish_and_clear();
+
+                match output {
+                    OutputFormat::Table => print_mints(&mints),
+                    _ => output::print_json(
+                        &output::batch_to_json(&mints, PrettyMint::to_json),
+                        output,
+                    )?,
+                }
+            }
             MintCommands::Fetch(f) => {
                 let spinner = utils::get_spinner("Fetching mint data...")?;
                 spinner.enable_steady_tick(Duration::from_millis(100));
-                let mint: PrettyMint = MintWithPubkey {
-                    mint_data: f.process_fetch_and_parse().await?,
-                    pubkey: f.mint_pubkey.to_string(),
-                }
-                .into();
+                let mint = f.process_fetch_and_parse(config).await?;
                 spinner.finish_and_clear();
 
-                mint.print();
+                match output {
+                    OutputFormat::Table => mint.print(),
+                    _ => output::print_json(&mint.to_json(), output)?,
+                }
+            }
+            MintCommands::Create(c) => {
+                let Some((mint_pubkey, sig)) = c.process_create(config).await? else {
+                    return Ok(());
+                };
+
+                match output {
+                    OutputFormat::Table => {
+                        transaction::print_signature(&sig, output)?;
+                        println!(
+                            "{} : {}",
+                            "Created mint".purple().bold(),
+                            mint_pubkey.to_string().cyan().bold()
+                        )
+                    }
+                    _ => output::print_json(
+                        &json!({
+                            "signature": sig.to_string(),
+                            "mint": mint_pubkey.to_string(),
+                        }),
+                        output,
+                    )?,
+                }
             }
-            MintCommands::Ix => {
-                let _ix = MintInstructions::from_select_str(
-                    Select::new(
-                        "Select an instruction to execute",
-                        MintInstructions::to_select_vec(),
-                    )
-                    .prompt()?,
+            MintCommands::Holders(args) => {
+                let report = args.process_holders(config).await?;
+
+                match output {
+                    OutputFormat::Table => report.print(),
+                    _ => output::print_json(&report, output)?,
+                }
+            }
+            MintCommands::Ix(args) => {
+                let ix = utils::select_or_prompt(
+                    args.ix.clone(),
+                    "<instruction>",
+                    "Select an instruction to execute",
+                    MintInstructions::to_select_vec(),
+                    MintInstructions::from_select_str,
                 )?;
+
+                ix.process_ix(config).await?;
             }
         }
 
@@ -0,0 +1,201 @@
+use clap::Args;
+use prettytable::{Row, Table};
+use serde_json::json;
+use solana_account::Account;
+use spl_token::solana_program::{program_pack::Pack, pubkey::Pubkey};
+
+use crate::{
+    config::Config,
+    input::{self, AccountInputArgs},
+    source::{self, AccountSource},
+    token_program::TokenProgram,
+    utils,
+};
+
+/// Size of an SPL multisig account, shared by both token programs. Token-2022
+/// never gives mints or token accounts with extensions this exact size.
+pub const MULTISIG_LEN: usize = spl_token::state::Multisig::LEN;
+
+/// Whether `account` is an SPL multisig of either token program.
+pub fn is_multisig(account: &Account) -> bool {
+    TokenProgram::try_from(account.owner).is_ok() && account.data.len() == MULTISIG_LEN
+}
+
+/// A parsed multisig of either token program.
+#[derive(Debug)]
+pub struct PrettyMultisig {
+    /// Address of the multisig.
+    pub pubkey: String,
+    /// The token program owning the multisig.
+    pub program: String,
+    /// Number of signatures required.
+    pub m: u8,
+    /// Number of signers.
+    pub n: u8,
+    /// The signers allowed to sign for the multisig.
+    pub signers: Vec<String>,
+}
+
+impl PrettyMultisig {
+    /// Parses a multisig owned by either token program.
+    pub fn try_parse(pubkey: Option<&Pubkey>, account: &Account) -> anyhow::Result<Self> {
+        let token_program = TokenProgram::try_from(account.owner)?;
+        let (m, n, signers) = match token_program {
+            TokenProgram::LegacyToken => {
+                let multisig = spl_token::state::Multisig::unpack(&account.data)
+                    .map_err(|e| anyhow::anyhow!("Error unpacking multisig: {}", e))?;
+                (multisig.m, multisig.n, multisig.signers)
+            }
+            TokenProgram::Token2022 => {
+                let multisig = spl_token_2022::state::Multisig::unpack(&account.data)
+                    .map_err(|e| anyhow::anyhow!("Error unpacking multisig: {}", e))?;
+                (multisig.m, multisig.n, multisig.signers)
+            }
+        };
+        // The programs only check the initialized flag when unpacking.
+        if n as usize > signers.len() {
+            return Err(anyhow::anyhow!(
+                "Invalid multisig with {} signers, at most {} are allowed",
+                n,
+                signers.len()
+            ));
+        }
+        if m > n {
+            return Err(anyhow::anyhow!(
+                "Invalid multisig requiring {} of {} signers",
+                m,
+                n
+            ));
+        }
+
+        Ok(Self {
+            pubkey: input::display_pubkey(pubkey),
+            program: account.owner.to_string(),
+            m,
+            n,
+            signers: signers[..n as usize]
+                .iter()
+                .map(|signer| signer.to_string())
+                .collect(),
+        })
+    }
+
+    /// Prints the multisig as a table.
+    pub fn print(&self) {
+        let mut table = Table::new();
+        table.add_row(Row::new(vec![utils::to_header_cell("Multisig Data")]));
+        table.add_row(Row::new(vec![
+            utils::to_key_cell("Multisig Pubkey"),
+            utils::to_value_cell(&self.pubkey),
+        ]));
+        table.add_row(Row::new(vec![
+            utils::to_key_cell("Program"),
+            utils::to_value_cell(&self.program),
+        ]));
+        table.add_row(Row::new(vec![
+            utils::to_key_cell("Required Signatures"),
+            utils::to_value_cell(&format!("{} of {}", self.m, self.n)),
+        ]));
+        table.add_row(Row::new(vec![
+            utils::to_key_cell("Signers"),
+            utils::to_value_cell(&self.signers.join("\n")),
+        ]));
+
+        println!();
+        table.set_format(*prettytable::format::consts::FORMAT_CLEAN);
+        table.printstd();
+        println!();
+    }
+
+    /// The multisig as the JSON document printed by `surfer multisig fetch`.
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "pubkey": self.pubkey,
+            "program": self.program,
+            "m": self.m,
+            "n": self.n,
+            "signers": self.signers,
+        })
+    }
+}
+
+/// Arguments of `surfer multisig fetch`.
+#[derive(Debug, Args)]
+pub struct FetchMultisig {
+    #[clap(
+        value_parser = utils::parse_pubkey,
+        required_unless_present_any = ["from_file", "from_stdin"],
+        help = "The multisig address to fetch. Must be a valid base58 encoded pubkey."
+    )]
+    pub(crate) multisig_pubkey: Option<Pubkey>,
+    #[clap(flatten)]
+    pub(crate) input: AccountInputArgs,
+}
+
+impl FetchMultisig {
+    /// Reads the multisig from --from-file or --from-stdin, or fetches it
+    /// over RPC.
+    pub async fn process_fetch_and_parse(&self, config: &Config) -> anyhow::Result<PrettyMultisig> {
+        if let Some((pubkey, acc)) = self.input.read()? {
+            return PrettyMultisig::try_parse(self.multisig_pubkey.or(pubkey).as_ref(), &acc);
+        }
+
+        self.fetch_and_parse(&config.rpc_client()?).await
+    }
+
+    /// Fetches the multisig from `source`.
+    pub async fn fetch_and_parse<S: AccountSource + ?Sized>(
+        &self,
+        source: &S,
+    ) -> anyhow::Result<PrettyMultisig> {
+        let multisig_pubkey = input::required_pubkey(self.multisig_pubkey)?;
+        let acc = source::require_account(source, &multisig_pubkey).await?;
+
+        PrettyMultisig::try_parse(Some(&multisig_pubkey), &acc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::multisig_account;
+
+    #[test]
+    fn parses_the_used_signers() {
+        let signers = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let multisig = PrettyMultisig::try_parse(None, &multisig_account(2, &signers)).unwrap();
+
+        assert_eq!((multisig.m, multisig.n), (2, 3));
+        assert_eq!(
+            multisig.signers,
+            signers.iter().map(|s| s.to_string()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn rejects_out_of_range_signer_counts() {
+        let signers = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+
+        // Byte 1 holds n, byte 0 holds m.
+        let mut too_many = multisig_account(2, &signers);
+        too_many.data[1] = 12;
+        let err = PrettyMultisig::try_parse(None, &too_many).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid multisig with 12 signers, at most 11 are allowed"
+        );
+
+        let mut unreachable = multisig_account(2, &signers);
+        unreachable.data[0] = 4;
+        let err = PrettyMultisig::try_parse(None, &unreachable).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid multisig requiring 4 of 3 signers");
+    }
+}
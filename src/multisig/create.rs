@@ -0,0 +1,101 @@
+use std::{rc::Rc, str::FromStr};
+
+use clap::Args;
+use solana_sdk::{
+    signature::{Keypair, Signature},
+    signer::Signer,
+    system_instruction,
+};
+use spl_token::{instruction::MAX_SIGNERS, solana_program::pubkey::Pubkey};
+
+use crate::{
+    config::Config,
+    multisig::MULTISIG_LEN,
+    signer::{LocalWallet, SignerSource},
+    token_program::TokenProgram,
+    transaction::{self, SendArgs},
+    utils,
+};
+
+/// Arguments of `surfer multisig create`.
+#[derive(Debug, Args)]
+pub struct CreateMultisig {
+    #[clap(help = "Number of signatures required, at most the number of signers.")]
+    pub(crate) m: u8,
+    #[clap(
+        required = true,
+        num_args = 1..=MAX_SIGNERS,
+        value_parser = utils::parse_pubkey,
+        help = "The pubkeys allowed to sign, up to 11."
+    )]
+    pub(crate) signers: Vec<Pubkey>,
+    #[clap(
+        long,
+        value_enum,
+        default_value = "token2022",
+        help = "The token program that will own the new multisig."
+    )]
+    pub(crate) program: TokenProgram,
+    #[clap(
+        long,
+        value_parser = SignerSource::from_str,
+        help = "Keypair of the new multisig account. Defaults to a new random keypair."
+    )]
+    pub(crate) multisig_keypair: Option<SignerSource>,
+    /// The signer and transaction flags.
+    #[clap(flatten)]
+    pub send: SendArgs,
+}
+
+impl CreateMultisig {
+    /// Creates the multisig and returns its pubkey with the transaction
+    /// signature, or `None` if the transaction was not sent.
+    pub async fn process_create(
+        &self,
+        config: &Config,
+    ) -> anyhow::Result<Option<(Pubkey, Signature)>> {
+        let n = self.signers.len();
+        if self.m == 0 || self.m as usize > n {
+            return Err(anyhow::anyhow!(
+                "Required signatures must be between 1 and the {} signers, got {}",
+                n,
+                self.m
+            ));
+        }
+
+        let local_wallet = LocalWallet::fetch(config)?;
+        let multisig_signer: Rc<dyn Signer> = match &self.multisig_keypair {
+            Some(source) => source.signer(
+                "multisig",
+                &config.signer.presigners,
+                config.transaction.allows_missing_signatures(),
+            )?,
+            None => Rc::new(Keypair::new()),
+        };
+        let multisig_pubkey = multisig_signer.pubkey();
+        let program_id: Pubkey = self.program.clone().into();
+
+        let lamports = transaction::minimum_balance(config, MULTISIG_LEN).await?;
+        let ixs = vec![
+            system_instruction::create_account(
+                &local_wallet.fee_payer.pubkey(),
+                &multisig_pubkey,
+                lamports,
+                MULTISIG_LEN as u64,
+                &program_id,
+            ),
+            spl_token_2022::instruction::initialize_multisig2(
+                &program_id,
+                &multisig_pubkey,
+                &self.signers.iter().collect::<Vec<_>>(),
+                self.m,
+            )?,
+        ];
+
+        let sig = local_wallet
+            .sign_and_send_ixs(ixs, config, &[multisig_signer])
+            .await?;
+
+        Ok(sig.map(|sig| (multisig_pubkey, sig)))
+    }
+}
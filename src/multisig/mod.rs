@@ -0,0 +1,69 @@
+/// Multisig parsing and printing.
+pub mod account;
+/// `surfer multisig create`.
+pub mod create;
+
+pub use account::*;
+use clap::Subcommand;
+use colored::*;
+pub use create::*;
+use serde_json::json;
+
+use crate::{
+    config::Config,
+    output::{self, OutputFormat},
+    transaction, utils,
+};
+
+/// Fetch and create SPL multisigs
+#[derive(Debug, Subcommand)]
+pub enum MultisigCommands {
+    /// Fetch a multisig and its signers
+    Fetch(FetchMultisig),
+    /// Create and initialize an M-of-N SPL multisig
+    Create(Box<CreateMultisig>),
+}
+
+impl MultisigCommands {
+    /// Runs the subcommand and prints its result.
+    pub async fn process(&self, config: &Config) -> anyhow::Result<()> {
+        let output = config.output;
+        match self {
+            MultisigCommands::Fetch(f) => {
+                let spinner = utils::get_spinner("Fetching multisig data...")?;
+                let multisig = f.process_fetch_and_parse(config).await?;
+                spinner.finish_and_clear();
+
+                match output {
+                    OutputFormat::Table => multisig.print(),
+                    _ => output::print_json(&multisig.to_json(), output)?,
+                }
+            }
+            MultisigCommands::Create(c) => {
+                let Some((multisig_pubkey, sig)) = c.process_create(config).await? else {
+                    return Ok(());
+                };
+
+                match output {
+                    OutputFormat::Table => {
+                        transaction::print_signature(&sig, output)?;
+                        println!(
+                            "{} : {}",
+                            "Created multisig".purple().bold(),
+                            multisig_pubkey.to_string().cyan().bold()
+                        )
+                    }
+                    _ => output::print_json(
+                        &json!({
+                            "signature": sig.to_string(),
+                            "multisig": multisig_pubkey.to_string(),
+                        }),
+                        output,
+                    )?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
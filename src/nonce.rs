@@ -0,0 +1,397 @@
+use std::{rc::Rc, str::FromStr};
+
+use clap::{Args, Subcommand};
+use colored::*;
+use prettytable::{Row, Table};
+use serde::Serialize;
+use solana_account::Account;
+use solana_sdk::{
+    nonce::{
+        state::{Data, Versions},
+        State,
+    },
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_instruction, system_program,
+};
+
+use crate::{
+    amount::Amount,
+    config::Config,
+    output::{self, OutputFormat, UiAmount},
+    signer::{LocalWallet, SignerSource},
+    source::{self, AccountSource},
+    transaction::{self, SendArgs},
+    utils,
+};
+
+const SOL_DECIMALS: u8 = 9;
+
+/// Create and use durable nonce accounts
+#[derive(Debug, Subcommand)]
+pub enum NonceCommands {
+    /// Create and initialize a durable nonce account
+    Create(CreateNonce),
+    /// Show the authority and stored blockhash of a nonce account
+    Show(NonceAccountArgs),
+    /// Advance the stored blockhash, invalidating transactions signed over it
+    Advance(AdvanceNonce),
+    /// Withdraw lamports from a nonce account
+    Withdraw(WithdrawNonce),
+}
+
+/// Arguments of `surfer nonce create`.
+#[derive(Debug, Args)]
+pub struct CreateNonce {
+    #[clap(
+        long,
+        value_parser = SignerSource::from_str,
+        help = "Keypair of the new nonce account. Defaults to a new random keypair."
+    )]
+    pub(crate) nonce_keypair: Option<SignerSource>,
+    #[clap(
+        long,
+        value_parser = utils::parse_pubkey,
+        help = "Authority of the nonce account. Defaults to the wallet."
+    )]
+    pub(crate) authority: Option<Pubkey>,
+    #[clap(
+        long,
+        value_parser = Amount::from_str,
+        help = "SOL to fund the account with. Defaults to the rent-exempt minimum."
+    )]
+    pub(crate) amount: Option<Amount>,
+    /// The signer and transaction flags.
+    #[clap(flatten)]
+    pub send: SendArgs,
+}
+
+/// Arguments of `surfer nonce show`.
+#[derive(Debug, Args)]
+pub struct NonceAccountArgs {
+    #[clap(value_parser = utils::parse_pubkey, help = "The nonce account.")]
+    pub(crate) nonce: Pubkey,
+}
+
+/// Arguments of `surfer nonce advance`.
+#[derive(Debug, Args)]
+pub struct AdvanceNonce {
+    #[clap(
+        id = "nonce_account",
+        value_name = "NONCE",
+        value_parser = utils::parse_pubkey,
+        help = "The nonce account."
+    )]
+    pub(crate) nonce: Pubkey,
+    /// The signer and transaction flags.
+    #[clap(flatten)]
+    pub send: SendArgs,
+}
+
+/// Arguments of `surfer nonce withdraw`.
+#[derive(Debug, Args)]
+pub struct WithdrawNonce {
+    #[clap(
+        id = "nonce_account",
+        value_name = "NONCE",
+        value_parser = utils::parse_pubkey,
+        help = "The nonce account."
+    )]
+    pub(crate) nonce: Pubkey,
+    #[clap(
+        long,
+        value_parser = utils::parse_pubkey,
+        help = "Recipient of the lamports. Defaults to the wallet."
+    )]
+    pub(crate) to: Option<Pubkey>,
+    #[clap(
+        long,
+        value_parser = Amount::from_str,
+        help = "SOL to withdraw, or ALL to close the account."
+    )]
+    pub(crate) amount: Amount,
+    /// The signer and transaction flags.
+    #[clap(flatten)]
+    pub send: SendArgs,
+}
+
+/// An initialized nonce account.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NonceAccount {
+    /// Address of the nonce account.
+    pub address: String,
+    /// The authority allowed to advance the nonce and withdraw from it.
+    pub authority: String,
+    /// The stored blockhash transactions are signed over.
+    pub blockhash: String,
+    /// Fee per signature when the nonce was last advanced.
+    pub lamports_per_signature: u64,
+    /// Balance of the account in SOL.
+    pub balance: UiAmount,
+}
+
+impl NonceAccount {
+    /// Builds the printable nonce account from its parsed state.
+    pub fn new(address: String, account: &Account, data: &Data) -> Self {
+        NonceAccount {
+            address,
+            authority: data.authority.to_string(),
+            blockhash: data.blockhash().to_string(),
+            lamports_per_signature: data.get_lamports_per_signature(),
+            balance: UiAmount::new(account.lamports, SOL_DECIMALS),
+        }
+    }
+
+    /// Prints the nonce account as a table.
+    pub fn print(&self) {
+        let mut table = Table::new();
+        table.add_row(Row::new(vec![utils::to_header_cell("Nonce Account")]));
+        for (key, value) in [
+            ("Address", self.address.clone()),
+            ("Authority", self.authority.clone()),
+            ("Blockhash", self.blockhash.clone()),
+            (
+                "Fee (lamports/signature)",
+                self.lamports_per_signature.to_string(),
+            ),
+            ("Balance (SOL)", self.balance.to_string()),
+        ] {
+            table.add_row(Row::new(vec![
+                utils::to_key_cell(key),
+                utils::to_value_cell(&value),
+            ]));
+        }
+        table.printstd();
+    }
+}
+
+/// Fetches a nonce account and returns it along with its initialized state.
+pub async fn fetch_nonce<S: AccountSource + ?Sized>(
+    source: &S,
+    nonce: &Pubkey,
+) -> anyhow::Result<(Account, Data)> {
+    let account = source::require_account(source, nonce).await?;
+    let data = parse_nonce(&nonce.to_string(), &account)?;
+
+    Ok((account, data))
+}
+
+/// Parses the initialized state of a nonce account.
+pub fn parse_nonce(nonce: &str, account: &Account) -> anyhow::Result<Data> {
+    if account.owner != system_program::id() {
+        return Err(anyhow::anyhow!(
+            "{} is not a nonce account, it is owned by {}",
+            nonce,
+            account.owner
+        ));
+    }
+
+    let versions: Versions = bincode::deserialize(&account.data)
+        .map_err(|_| anyhow::anyhow!("{} is not a nonce account", nonce))?;
+    match versions.state() {
+        State::Initialized(data) => Ok(data.clone()),
+        State::Uninitialized => Err(anyhow::anyhow!(
+            "Nonce account {} is not initialized",
+            nonce
+        )),
+    }
+}
+
+impl NonceCommands {
+    /// Runs the subcommand and prints its result.
+    pub async fn process(&self, config: &Config) -> anyhow::Result<()> {
+        let output = config.output;
+        match self {
+            NonceCommands::Create(args) => {
+                let local_wallet = LocalWallet::fetch(config)?;
+                let nonce_signer: Rc<dyn Signer> = match &args.nonce_keypair {
+                    Some(source) => source.signer(
+                        "nonce",
+                        &config.signer.presigners,
+                        config.transaction.allows_missing_signatures(),
+                    )?,
+                    None => Rc::new(Keypair::new()),
+                };
+                let nonce = nonce_signer.pubkey();
+                let authority = args.authority.unwrap_or(local_wallet.pubkey());
+
+                let lamports = match &args.amount {
+                    Some(amount) => amount.to_raw_amount(SOL_DECIMALS, None, false)?,
+                    None => transaction::minimum_balance(config, State::size()).await?,
+                };
+
+                let ixs = system_instruction::create_nonce_account(
+                    &local_wallet.fee_payer.pubkey(),
+                    &nonce,
+                    &authority,
+                    lamports,
+                );
+                let Some(sig) = local_wallet
+                    .sign_and_send_ixs(ixs, config, &[nonce_signer])
+                    .await?
+                else {
+                    return Ok(());
+                };
+
+                match output {
+                    OutputFormat::Table => {
+                        transaction::print_signature(&sig, output)?;
+                        println!(
+                            "{} : {}",
+                            "Created nonce account".purple().bold(),
+                            nonce.to_string().cyan().bold()
+                        )
+                    }
+                    _ => output::print_json(
+                        &serde_json::json!({
+                            "signature": sig.to_string(),
+                            "nonce": nonce.to_string(),
+                        }),
+                        output,
+                    )?,
+                }
+            }
+            NonceCommands::Show(args) => {
+                let spinner = utils::get_spinner("Fetching nonce account...")?;
+                let (account, data) = fetch_nonce(&config.rpc_client()?, &args.nonce).await?;
+                spinner.finish_and_clear();
+
+                let nonce_account = NonceAccount::new(args.nonce.to_string(), &account, &data);
+                match output {
+                    OutputFormat::Table => nonce_account.print(),
+                    _ => output::print_json(&nonce_account, output)?,
+                }
+            }
+            NonceCommands::Advance(args) => {
+                let local_wallet = LocalWallet::fetch(config)?;
+                let authority = local_wallet
+                    .role_signer(config.signer.nonce_authority.as_ref(), "nonce authority")?;
+
+                let ix =
+                    system_instruction::advance_nonce_account(&args.nonce, &authority.pubkey());
+                let Some(sig) = local_wallet
+                    .sign_and_send_ixs(vec![ix], config, &[authority])
+                    .await?
+                else {
+                    return Ok(());
+                };
+
+                let (_, data) = fetch_nonce(&config.rpc_client()?, &args.nonce).await?;
+                match output {
+                    OutputFormat::Table => {
+                        transaction::print_signature(&sig, output)?;
+                        println!(
+                            "{} : {}",
+                            "New nonce blockhash".purple().bold(),
+                            data.blockhash().to_string().cyan().bold()
+                        )
+                    }
+                    _ => output::print_json(
+                        &serde_json::json!({
+                            "signature": sig.to_string(),
+                            "blockhash": data.blockhash().to_string(),
+                        }),
+                        output,
+                    )?,
+                }
+            }
+            NonceCommands::Withdraw(args) => {
+                let local_wallet = LocalWallet::fetch(config)?;
+                let authority = local_wallet
+                    .role_signer(config.signer.nonce_authority.as_ref(), "nonce authority")?;
+                let to = args.to.unwrap_or(local_wallet.pubkey());
+
+                let balance = match args.amount {
+                    Amount::All => Some(
+                        fetch_nonce(&config.rpc_client()?, &args.nonce)
+                            .await?
+                            .0
+                            .lamports,
+                    ),
+                    Amount::Decimal(_) => None,
+                };
+                let lamports = args.amount.to_raw_amount(SOL_DECIMALS, balance, false)?;
+
+                let ix = system_instruction::withdraw_nonce_account(
+                    &args.nonce,
+                    &authority.pubkey(),
+                    &to,
+                    lamports,
+                );
+                if let Some(sig) = local_wallet
+                    .sign_and_send_ixs(vec![ix], config, &[authority])
+                    .await?
+                {
+                    transaction::print_signature(&sig, output)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::{
+        source::MemorySource,
+        test_fixtures::{mint_account, nonce_account},
+    };
+
+    #[tokio::test]
+    async fn fetch_nonce_from_any_source() {
+        let (nonce, authority, mint) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let source = MemorySource::new()
+            .with_account(nonce, nonce_account(&authority))
+            .with_account(mint, mint_account(6, 0));
+
+        let (_, data) = fetch_nonce(&source, &nonce).await.unwrap();
+        assert_eq!(data.authority, authority);
+
+        let err = fetch_nonce(&source, &mint).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "{} is not a nonce account, it is owned by {}",
+                mint,
+                spl_token::ID
+            )
+        );
+
+        let missing = Pubkey::new_unique();
+        let err = fetch_nonce(&source, &missing).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("Account {} does not exist", missing)
+        );
+    }
+
+    #[derive(Parser)]
+    struct Advance {
+        #[clap(flatten)]
+        args: AdvanceNonce,
+    }
+
+    #[test]
+    fn nonce_account_is_not_the_nonce_flag() {
+        let (nonce, durable_nonce) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let advance = Advance::try_parse_from([
+            "advance",
+            &nonce.to_string(),
+            "--nonce",
+            &durable_nonce.to_string(),
+        ])
+        .unwrap();
+        assert_eq!(advance.args.nonce, nonce);
+        assert_eq!(advance.args.send.transaction.nonce, Some(durable_nonce));
+    }
+}
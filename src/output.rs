@@ -0,0 +1,89 @@
+use std::fmt;
+
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::json;
+use spl_token::solana_program::pubkey::Pubkey;
+
+/// How commands print their results.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable tables
+    Table,
+    /// Pretty-printed JSON
+    Json,
+    /// JSON on a single line
+    JsonCompact,
+}
+
+/// A token amount in base units together with its decimal-adjusted value.
+///
+/// Both values are strings so that u64 amounts survive JSON parsers that
+/// store numbers as doubles.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiAmount {
+    /// The amount in base units.
+    pub amount: String,
+    /// Decimals of the mint.
+    pub decimals: u8,
+    /// The decimal-adjusted amount, without trailing zeros.
+    pub ui_amount_string: String,
+}
+
+impl UiAmount {
+    /// Formats `amount` base units of a mint with `decimals`.
+    pub fn new(amount: u64, decimals: u8) -> Self {
+        Self {
+            amount: amount.to_string(),
+            decimals,
+            ui_amount_string: spl_token_2022::amount_to_ui_amount_string_trimmed(amount, decimals),
+        }
+    }
+}
+
+/// Prints `value` as JSON. Must not be called with [`OutputFormat::Table`].
+pub fn print_json<T: Serialize>(value: &T, format: OutputFormat) -> anyhow::Result<()> {
+    let json = match format {
+        OutputFormat::Json => serde_json::to_string_pretty(value)?,
+        OutputFormat::JsonCompact => serde_json::to_string(value)?,
+        OutputFormat::Table => {
+            return Err(anyhow::anyhow!("Table output cannot be printed as JSON"))
+        }
+    };
+    println!("{}", json);
+
+    Ok(())
+}
+
+/// Prints the heading of a command. Only table output gets one, so JSON
+/// output stays parseable.
+pub fn print_heading(heading: &str, format: OutputFormat) {
+    if format == OutputFormat::Table {
+        println!("{}", heading);
+    }
+}
+
+/// Serializes the results of a batch fetch as a JSON array, with
+/// `{ "pubkey", "error" }` in place of the items that failed.
+pub fn batch_to_json<T>(
+    results: &[(Pubkey, anyhow::Result<T>)],
+    to_json: impl Fn(&T) -> serde_json::Value,
+) -> serde_json::Value {
+    results
+        .iter()
+        .map(|(pubkey, result)| match result {
+            Ok(item) => to_json(item),
+            Err(e) => json!({
+                "pubkey": pubkey.to_string(),
+                "error": e.to_string(),
+            }),
+        })
+        .collect()
+}
+
+impl fmt::Display for UiAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (raw: {})", self.ui_amount_string, self.amount)
+    }
+}
@@ -0,0 +1,587 @@
+use std::{
+    fmt, io,
+    path::{Path, PathBuf},
+    rc::Rc,
+    str::FromStr,
+};
+
+use clap::Args;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{
+        keypair_from_seed_phrase_and_passphrase, read_keypair, read_keypair_file, Keypair,
+        Signature,
+    },
+    signer::{null_signer::NullSigner, presigner::Presigner, Signer},
+    system_instruction,
+};
+
+use crate::{
+    config::Config,
+    nonce,
+    output::{self, OutputFormat},
+    transaction, utils,
+};
+
+/// The Solana CLI default keypair, relative to the home directory.
+pub const DEFAULT_KEYPAIR_PATH: &str = ".config/solana/id.json";
+
+const PROMPT_URI: &str = "prompt://";
+const ENV_URI_PREFIX: &str = "env://";
+const FILE_URI_PREFIX: &str = "file://";
+
+/// Flags naming who signs a transaction.
+#[derive(Debug, Clone, Default, Args)]
+pub struct SignerArgs {
+    /// Wallet keypair: a file path, `stdin`, `prompt://`, `env://<VAR>` or a pubkey for offline signing. Defaults to ~/.config/solana/id.json
+    #[clap(short, long, global = true, value_parser = SignerSource::from_str)]
+    pub keypair: Option<SignerSource>,
+
+    /// Fee payer keypair, in the same formats as --keypair. Defaults to the wallet keypair
+    #[clap(long, global = true, value_parser = SignerSource::from_str)]
+    pub fee_payer: Option<SignerSource>,
+
+    /// Authority of the --nonce account, in the same formats as --keypair. Defaults to the wallet keypair
+    #[clap(long, global = true, value_parser = SignerSource::from_str)]
+    pub nonce_authority: Option<SignerSource>,
+
+    /// Signer of the SPL multisig given as the instruction authority or owner, in the same formats as --keypair. Can be repeated
+    #[clap(long = "multisig-signer", global = true, value_parser = SignerSource::from_str)]
+    pub multisig_signers: Vec<SignerSource>,
+
+    /// A signature produced offline with --sign-only, as `<PUBKEY>=<SIGNATURE>`. Can be repeated
+    #[clap(long = "signer", global = true, value_parser = parse_presigner)]
+    pub presigners: Vec<Presigner>,
+}
+
+fn parse_presigner(s: &str) -> anyhow::Result<Presigner> {
+    let (pubkey, signature) = s
+        .split_once('=')
+        .ok_or_else(|| anyhow::anyhow!("Invalid signer `{}`, expected <PUBKEY>=<SIGNATURE>", s))?;
+    let pubkey = Pubkey::from_str(pubkey)
+        .map_err(|e| anyhow::anyhow!("Invalid signer pubkey `{}`: {}", pubkey, e))?;
+    let signature = Signature::from_str(signature)
+        .map_err(|e| anyhow::anyhow!("Invalid signature `{}`: {}", signature, e))?;
+
+    Ok(Presigner::new(&pubkey, &signature))
+}
+
+/// Where a signing keypair is loaded from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SignerSource {
+    /// A JSON keypair file, given as a plain path or a `file://` URI.
+    File(PathBuf),
+    /// A JSON keypair piped through stdin.
+    Stdin,
+    /// A seed phrase and optional passphrase entered at a prompt.
+    Prompt,
+    /// A base58 encoded secret key held in an environment variable.
+    Env(String),
+    /// A bare pubkey whose signature is supplied with `--signer`, or left
+    /// absent with `--sign-only`.
+    Pubkey(Pubkey),
+}
+
+impl FromStr for SignerSource {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        // Relative paths resolve against the working directory.
+        parse_signer_source(s, Path::new(""))
+    }
+}
+
+/// Parses a signer source, checking for a file named like a pubkey under
+/// `dir`.
+fn parse_signer_source(s: &str, dir: &Path) -> anyhow::Result<SignerSource> {
+    if s == "stdin" || s == "-" {
+        return Ok(SignerSource::Stdin);
+    }
+    if s == PROMPT_URI {
+        return Ok(SignerSource::Prompt);
+    }
+    if let Some(var) = s.strip_prefix(ENV_URI_PREFIX) {
+        if var.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Missing environment variable name in `{}`",
+                s
+            ));
+        }
+        return Ok(SignerSource::Env(var.to_string()));
+    }
+
+    // A pubkey is only taken as such if no file of that name exists.
+    if let Ok(pubkey) = Pubkey::from_str(s) {
+        if !dir.join(s).exists() {
+            return Ok(SignerSource::Pubkey(pubkey));
+        }
+    }
+
+    let path = s.strip_prefix(FILE_URI_PREFIX).unwrap_or(s);
+    if path.is_empty() {
+        return Err(anyhow::anyhow!("Empty keypair path"));
+    }
+    Ok(SignerSource::File(PathBuf::from(path)))
+}
+
+impl fmt::Display for SignerSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignerSource::File(path) => write!(f, "{}", path.display()),
+            SignerSource::Stdin => write!(f, "stdin"),
+            SignerSource::Prompt => write!(f, "{}", PROMPT_URI),
+            SignerSource::Env(var) => write!(f, "{}{}", ENV_URI_PREFIX, var),
+            SignerSource::Pubkey(pubkey) => write!(f, "{}", pubkey),
+        }
+    }
+}
+
+impl SignerSource {
+    /// Resolves the signer for `role`. Pubkey sources resolve to the matching
+    /// `--signer` signature, or to an absent signature when `allow_missing`,
+    /// i.e. when only signing offline or simulating.
+    pub fn signer(
+        &self,
+        role: &str,
+        presigners: &[Presigner],
+        allow_missing: bool,
+    ) -> anyhow::Result<Rc<dyn Signer>> {
+        let SignerSource::Pubkey(pubkey) = self else {
+            return Ok(Rc::new(self.load(role)?));
+        };
+
+        match presigners.iter().find(|p| p.pubkey() == *pubkey) {
+            Some(presigner) => Ok(Rc::new(presigner.clone())),
+            None if allow_missing => Ok(Rc::new(NullSigner::new(pubkey))),
+            None => Err(anyhow::anyhow!(
+                "No signature for {} {}, pass --signer {}=<SIGNATURE> or a keypair",
+                role,
+                pubkey,
+                pubkey
+            )),
+        }
+    }
+
+    /// Loads the keypair. `role` names the signer in prompts and errors,
+    /// e.g. `fee payer`.
+    pub fn load(&self, role: &str) -> anyhow::Result<Keypair> {
+        let keypair = match self {
+            SignerSource::File(path) => read_keypair_file(path).map_err(|e| e.to_string()),
+            SignerSource::Stdin => read_keypair(&mut io::stdin()).map_err(|e| e.to_string()),
+            SignerSource::Prompt => {
+                if !utils::can_prompt() {
+                    return Err(anyhow::anyhow!(
+                        "Cannot prompt for the {} seed phrase because stdin is not a terminal",
+                        role
+                    ));
+                }
+                let seed_phrase = inquire::Password::new(&format!("Seed phrase for {}", role))
+                    .without_confirmation()
+                    .prompt()?;
+                let passphrase = inquire::Password::new("Passphrase (leave empty for none)")
+                    .without_confirmation()
+                    .prompt()?;
+                keypair_from_seed_phrase_and_passphrase(seed_phrase.trim(), &passphrase)
+                    .map_err(|e| e.to_string())
+            }
+            SignerSource::Pubkey(pubkey) => Err(format!(
+                "{} is a pubkey, a keypair is required to sign here",
+                pubkey
+            )),
+            SignerSource::Env(var) => std::env::var(var)
+                .map_err(|e| format!("{}: {}", var, e))
+                .and_then(|secret| {
+                    let bytes = solana_sdk::bs58::decode(secret.trim())
+                        .into_vec()
+                        .map_err(|e| e.to_string())?;
+                    Keypair::from_bytes(&bytes).map_err(|e| e.to_string())
+                }),
+        };
+
+        keypair.map_err(|e| anyhow::anyhow!("Failed to load {} keypair from {}: {}", role, self, e))
+    }
+}
+
+/// The wallet and fee payer a command signs with.
+pub struct LocalWallet {
+    /// The wallet, also the default authority of instructions.
+    pub signer: Rc<dyn Signer>,
+    /// Pays for the transaction, the wallet unless `--fee-payer` is given.
+    pub fee_payer: Rc<dyn Signer>,
+    signer_source: SignerSource,
+    fee_payer_source: SignerSource,
+    multisig_signer_sources: Vec<SignerSource>,
+    presigners: Vec<Presigner>,
+    allow_missing_signatures: bool,
+}
+
+/// The authority of a token instruction: a single signer, or an SPL
+/// multisig account signed for by the `--multisig-signer`s.
+pub struct Authority {
+    /// The authority the instruction names.
+    pub pubkey: Pubkey,
+    /// Who signs for it: the authority itself or the multisig signers.
+    pub signers: Vec<Rc<dyn Signer>>,
+    multisig_signers: Vec<Pubkey>,
+}
+
+impl Authority {
+    /// The signer pubkeys the token instruction builders expect, empty
+    /// unless the authority is a multisig.
+    pub fn multisig_signers(&self) -> Vec<&Pubkey> {
+        self.multisig_signers.iter().collect()
+    }
+
+    /// Whether the authority is an SPL multisig.
+    pub fn is_multisig(&self) -> bool {
+        !self.multisig_signers.is_empty()
+    }
+}
+
+impl LocalWallet {
+    /// Loads the wallet and fee payer from the resolved signer flags.
+    pub fn fetch(config: &Config) -> anyhow::Result<LocalWallet> {
+        let args = &config.signer;
+        let allow_missing_signatures = config.transaction.allows_missing_signatures();
+        let signer_source = default_signer_source(args)?;
+        let signer = signer_source.signer("wallet", &args.presigners, allow_missing_signatures)?;
+
+        let fee_payer_source = args.fee_payer.clone().unwrap_or(signer_source.clone());
+        let fee_payer = if fee_payer_source == signer_source {
+            signer.clone()
+        } else {
+            fee_payer_source.signer("fee payer", &args.presigners, allow_missing_signatures)?
+        };
+
+        Ok(LocalWallet {
+            signer,
+            fee_payer,
+            signer_source,
+            fee_payer_source,
+            multisig_signer_sources: args.multisig_signers.clone(),
+            presigners: args.presigners.clone(),
+            allow_missing_signatures,
+        })
+    }
+
+    /// Resolves the signer for an instruction role such as `--owner`,
+    /// falling back to the wallet. Sources already loaded for the wallet or
+    /// fee payer are reused so stdin and prompts are only read once.
+    pub fn role_signer(
+        &self,
+        source: Option<&SignerSource>,
+        role: &str,
+    ) -> anyhow::Result<Rc<dyn Signer>> {
+        match source {
+            None => Ok(self.signer.clone()),
+            Some(source) if *source == self.signer_source => Ok(self.signer.clone()),
+            Some(source) if *source == self.fee_payer_source => Ok(self.fee_payer.clone()),
+            Some(source) => source.signer(role, &self.presigners, self.allow_missing_signatures),
+        }
+    }
+
+    /// Resolves the authority for a token instruction role. With
+    /// `--multisig-signer` the role must be given as the multisig pubkey and
+    /// the multisig signers sign in its place.
+    pub fn authority(
+        &self,
+        source: Option<&SignerSource>,
+        role: &str,
+    ) -> anyhow::Result<Authority> {
+        if self.multisig_signer_sources.is_empty() {
+            let signer = self.role_signer(source, role)?;
+            return Ok(Authority {
+                pubkey: signer.pubkey(),
+                signers: vec![signer],
+                multisig_signers: vec![],
+            });
+        }
+
+        let Some(SignerSource::Pubkey(multisig)) = source else {
+            return Err(anyhow::anyhow!(
+                "With --multisig-signer the {} must be the pubkey of the multisig account",
+                role
+            ));
+        };
+        let signers = self
+            .multisig_signer_sources
+            .iter()
+            .map(|source| self.role_signer(Some(source), "multisig signer"))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Authority {
+            pubkey: *multisig,
+            multisig_signers: signers.iter().map(|signer| signer.pubkey()).collect(),
+            signers,
+        })
+    }
+
+    /// The wallet pubkey.
+    pub fn pubkey(&self) -> Pubkey {
+        self.signer.pubkey()
+    }
+
+    /// Signs and sends `ixs` and returns the signature, leaving it to the
+    /// caller to print. With `--dry-run` the transaction is only simulated
+    /// and with `--sign-only` only signed, and `None` is returned.
+    /// With `--nonce` an advance instruction is prepended and the nonce's
+    /// stored blockhash is used.
+    pub async fn sign_and_send_ixs(
+        &self,
+        ixs: Vec<solana_sdk::instruction::Instruction>,
+        config: &Config,
+        signers: &[Rc<dyn Signer>],
+    ) -> anyhow::Result<Option<Signature>> {
+        let tx_args = &config.transaction;
+        if tx_args.tx_file.is_some() && !tx_args.sign_only {
+            return Err(anyhow::anyhow!(
+                "--tx-file can only be used with --sign-only"
+            ));
+        }
+        if !self.presigners.is_empty() && tx_args.blockhash.is_none() && tx_args.nonce.is_none() {
+            return Err(anyhow::anyhow!(
+                "--signer requires --blockhash set to the blockhash the signatures were made over, or --nonce"
+            ));
+        }
+
+        let spinner = utils::get_spinner(match (tx_args.dry_run, tx_args.sign_only) {
+            (true, _) => "Simulating tx...",
+            (_, true) => "Signing tx...",
+            _ => "Sending tx...",
+        })?;
+        let mut ixs = ixs;
+        let mut signers = signers.to_vec();
+        let nonce_authority = match tx_args.nonce {
+            Some(nonce) => {
+                let authority =
+                    self.role_signer(config.signer.nonce_authority.as_ref(), "nonce authority")?;
+                ixs.insert(
+                    0,
+                    system_instruction::advance_nonce_account(&nonce, &authority.pubkey()),
+                );
+                signers.push(authority.clone());
+                Some(authority.pubkey())
+            }
+            None => None,
+        };
+
+        let ixs = transaction::with_compute_budget(config, ixs, &self.fee_payer.pubkey()).await?;
+        let mut tx = solana_sdk::transaction::Transaction::new_with_payer(
+            &ixs,
+            Some(&self.fee_payer.pubkey()),
+        );
+        let recent_blockhash = match (tx_args.blockhash, tx_args.nonce, nonce_authority) {
+            (Some(blockhash), _, _) => blockhash,
+            (None, Some(nonce), Some(authority)) => {
+                let (_, data) = nonce::fetch_nonce(&config.rpc_client()?, &nonce).await?;
+                if data.authority != authority {
+                    return Err(anyhow::anyhow!(
+                        "Nonce account {} has authority {}, pass it with --nonce-authority",
+                        nonce,
+                        data.authority
+                    ));
+                }
+                data.blockhash()
+            }
+            _ => config.rpc_client()?.get_latest_blockhash().await?,
+        };
+        let signers = self.distinct_signers(&signers);
+
+        if tx_args.sign_only {
+            tx.try_partial_sign(&signers, recent_blockhash)
+                .map_err(transaction::signing_error)?;
+            spinner.finish_and_clear();
+
+            transaction::print_signed_transaction(&tx, config.output)?;
+            if let Some(path) = &tx_args.tx_file {
+                transaction::write_transaction_file(path, &tx)?;
+            }
+            return Ok(None);
+        }
+
+        let rpc_client = config.rpc_client()?;
+        if tx_args.dry_run {
+            // Simulation skips signature verification, so signers that are
+            // not available locally do not prevent a preview.
+            tx.try_partial_sign(&signers, recent_blockhash)
+                .map_err(transaction::signing_error)?;
+            let report = transaction::simulate(&rpc_client, &tx, &ixs, config.commitment).await?;
+            spinner.finish_and_clear();
+
+            match config.output {
+                OutputFormat::Table => report.print(),
+                _ => output::print_json(&report.to_json(), config.output)?,
+            }
+            return Ok(None);
+        }
+
+        tx.try_sign(&signers, recent_blockhash)
+            .map_err(transaction::signing_error)?;
+        let sig = rpc_client.send_and_confirm_transaction(&tx).await?;
+        spinner.finish_and_clear();
+
+        Ok(Some(sig))
+    }
+
+    /// The fee payer followed by `signers`, each pubkey only once.
+    fn distinct_signers<'a>(&'a self, signers: &'a [Rc<dyn Signer>]) -> Vec<&'a dyn Signer> {
+        let mut distinct: Vec<&dyn Signer> = vec![self.fee_payer.as_ref()];
+        for signer in signers {
+            if !distinct.iter().any(|s| s.pubkey() == signer.pubkey()) {
+                distinct.push(signer.as_ref());
+            }
+        }
+
+        distinct
+    }
+}
+
+/// The `--keypair` source, defaulting to the Solana CLI default keypair.
+pub fn default_signer_source(args: &SignerArgs) -> anyhow::Result<SignerSource> {
+    match &args.keypair {
+        Some(source) => Ok(source.clone()),
+        None => {
+            let home_dir =
+                dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Failed to get home dir"))?;
+            Ok(SignerSource::File(home_dir.join(DEFAULT_KEYPAIR_PATH)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use solana_client::nonblocking::rpc_client::RpcClient;
+    use solana_sdk::{commitment_config::CommitmentConfig, hash::Hash};
+
+    use super::*;
+    use crate::transaction::TransactionArgs;
+
+    fn parse(s: &str) -> anyhow::Result<SignerSource> {
+        SignerSource::from_str(s)
+    }
+
+    #[test]
+    fn parses_files_stdin_and_prompt() {
+        assert_eq!(
+            parse("~/keys/id.json").unwrap(),
+            SignerSource::File(PathBuf::from("~/keys/id.json"))
+        );
+        assert_eq!(
+            parse("file:///tmp/id.json").unwrap(),
+            SignerSource::File(PathBuf::from("/tmp/id.json"))
+        );
+        assert_eq!(parse("stdin").unwrap(), SignerSource::Stdin);
+        assert_eq!(parse("-").unwrap(), SignerSource::Stdin);
+        assert_eq!(parse("prompt://").unwrap(), SignerSource::Prompt);
+        assert_eq!(
+            parse("file://").unwrap_err().to_string(),
+            "Empty keypair path"
+        );
+    }
+
+    #[test]
+    fn parses_env_vars() {
+        assert_eq!(
+            parse("env://SURFER_KEY").unwrap(),
+            SignerSource::Env("SURFER_KEY".to_string())
+        );
+        assert_eq!(
+            parse("env://").unwrap_err().to_string(),
+            "Missing environment variable name in `env://`"
+        );
+    }
+
+    #[test]
+    fn parses_pubkey_unless_a_file_has_its_name() {
+        let pubkey = Pubkey::new_unique();
+        assert_eq!(
+            parse(&pubkey.to_string()).unwrap(),
+            SignerSource::Pubkey(pubkey)
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let pubkey = Pubkey::new_unique();
+        fs::write(dir.path().join(pubkey.to_string()), "[]").unwrap();
+        assert_eq!(
+            parse_signer_source(&pubkey.to_string(), dir.path()).unwrap(),
+            SignerSource::File(PathBuf::from(pubkey.to_string()))
+        );
+    }
+
+    #[test]
+    fn loads_env_keypair() {
+        let keypair = Keypair::new();
+        let var = format!("SURFER_TEST_KEY_{}", keypair.pubkey());
+        std::env::set_var(&var, keypair.to_base58_string());
+
+        let loaded = SignerSource::Env(var.clone()).load("wallet").unwrap();
+        std::env::remove_var(&var);
+        assert_eq!(loaded.pubkey(), keypair.pubkey());
+
+        let err = SignerSource::Env(var).load("wallet").unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Failed to load wallet keypair from env://"));
+    }
+
+    fn config(keypair: Pubkey, dry_run: bool) -> Config {
+        Config {
+            rpc_url: None,
+            commitment: CommitmentConfig::confirmed(),
+            signer: SignerArgs {
+                keypair: Some(SignerSource::Pubkey(keypair)),
+                ..SignerArgs::default()
+            },
+            transaction: TransactionArgs {
+                dry_run,
+                ..TransactionArgs::default()
+            },
+            output: OutputFormat::Json,
+            profile: None,
+        }
+    }
+
+    #[test]
+    fn pubkey_wallet_needs_a_signature_to_send() {
+        let err = LocalWallet::fetch(&config(Pubkey::new_unique(), false))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("No signature for wallet"));
+    }
+
+    #[tokio::test]
+    async fn dry_run_simulates_with_a_pubkey_only_authority() {
+        let owner = Pubkey::new_unique();
+        let config = config(owner, true);
+        let local_wallet = LocalWallet::fetch(&config).unwrap();
+        let authority = local_wallet
+            .authority(Some(&SignerSource::Pubkey(owner)), "owner")
+            .unwrap();
+        assert_eq!(authority.pubkey, owner);
+
+        let ixs = vec![spl_token::instruction::revoke(
+            &spl_token::ID,
+            &Pubkey::new_unique(),
+            &authority.pubkey,
+            &authority.multisig_signers(),
+        )
+        .unwrap()];
+        let mut tx = solana_sdk::transaction::Transaction::new_with_payer(
+            &ixs,
+            Some(&local_wallet.fee_payer.pubkey()),
+        );
+        tx.try_partial_sign(
+            &local_wallet.distinct_signers(&authority.signers),
+            Hash::new_unique(),
+        )
+        .unwrap();
+
+        let rpc_client = RpcClient::new_mock("succeeds".to_string());
+        let report = transaction::simulate(&rpc_client, &tx, &ixs, config.commitment)
+            .await
+            .unwrap();
+        assert!(report.to_json()["err"].is_null());
+    }
+}
@@ -0,0 +1,247 @@
+use std::{collections::HashMap, fs, future::Future, path::PathBuf};
+
+use solana_account::Account;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use spl_token::solana_program::pubkey::Pubkey;
+
+use crate::input;
+
+/// The most accounts `getMultipleAccounts` returns per request.
+pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// Somewhere accounts can be fetched from.
+///
+/// The parsers only need raw [`Account`]s, so the fetch helpers take any
+/// source rather than a concrete [`RpcClient`]. Services with their own
+/// cache or indexer can implement this to reuse them, and tests can use
+/// [`MemorySource`] or [`FixtureDirSource`] instead of a cluster.
+pub trait AccountSource {
+    /// Fetches the account at `pubkey`, or `None` if it does not exist.
+    fn get_account(
+        &self,
+        pubkey: &Pubkey,
+    ) -> impl Future<Output = anyhow::Result<Option<Account>>> + Send;
+
+    /// Fetches several accounts, returned in the order of `pubkeys`.
+    fn get_multiple_accounts(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> impl Future<Output = anyhow::Result<Vec<Option<Account>>>> + Send;
+}
+
+/// Fetches the account at `pubkey`, failing if it does not exist.
+pub async fn require_account<S: AccountSource + ?Sized>(
+    source: &S,
+    pubkey: &Pubkey,
+) -> anyhow::Result<Account> {
+    source
+        .get_account(pubkey)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Account {} does not exist", pubkey))
+}
+
+impl AccountSource for RpcClient {
+    async fn get_account(&self, pubkey: &Pubkey) -> anyhow::Result<Option<Account>> {
+        Ok(self
+            .get_account_with_commitment(pubkey, self.commitment())
+            .await?
+            .value)
+    }
+
+    async fn get_multiple_accounts(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> anyhow::Result<Vec<Option<Account>>> {
+        let mut accounts = Vec::with_capacity(pubkeys.len());
+        for chunk in pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            accounts.extend(
+                self.get_multiple_accounts_with_commitment(chunk, self.commitment())
+                    .await?
+                    .value,
+            );
+        }
+
+        Ok(accounts)
+    }
+}
+
+/// `None` is a source without any accounts, for code paths that only have
+/// RPC some of the time.
+impl<S: AccountSource + Sync> AccountSource for Option<S> {
+    async fn get_account(&self, pubkey: &Pubkey) -> anyhow::Result<Option<Account>> {
+        match self {
+            Some(source) => source.get_account(pubkey).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn get_multiple_accounts(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> anyhow::Result<Vec<Option<Account>>> {
+        match self {
+            Some(source) => source.get_multiple_accounts(pubkeys).await,
+            None => Ok(vec![None; pubkeys.len()]),
+        }
+    }
+}
+
+/// Accounts held in memory, for tests and for accounts read ahead of time.
+#[derive(Debug, Clone, Default)]
+pub struct MemorySource {
+    accounts: HashMap<Pubkey, Account>,
+}
+
+impl MemorySource {
+    /// An empty source.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `account` at `pubkey`, builder style.
+    pub fn with_account(mut self, pubkey: Pubkey, account: Account) -> Self {
+        self.insert(pubkey, account);
+        self
+    }
+
+    /// Adds `account` at `pubkey`, replacing any account already there.
+    pub fn insert(&mut self, pubkey: Pubkey, account: Account) {
+        self.accounts.insert(pubkey, account);
+    }
+}
+
+impl AccountSource for MemorySource {
+    async fn get_account(&self, pubkey: &Pubkey) -> anyhow::Result<Option<Account>> {
+        Ok(self.accounts.get(pubkey).cloned())
+    }
+
+    async fn get_multiple_accounts(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> anyhow::Result<Vec<Option<Account>>> {
+        Ok(pubkeys
+            .iter()
+            .map(|pubkey| self.accounts.get(pubkey).cloned())
+            .collect())
+    }
+}
+
+/// Accounts stored as files named after their pubkey, e.g. saved with
+/// `solana account <PUBKEY> --output json > <DIR>/<PUBKEY>.json`.
+///
+/// Files are looked up as `<PUBKEY>.json`, `<PUBKEY>.base64` and
+/// `<PUBKEY>.bin`, and parsed like `--from-file`. Accounts without a file
+/// don't exist.
+#[derive(Debug, Clone)]
+pub struct FixtureDirSource {
+    dir: PathBuf,
+}
+
+impl FixtureDirSource {
+    const EXTENSIONS: [&'static str; 3] = ["json", "base64", "bin"];
+
+    /// A source reading account files from `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn read(&self, pubkey: &Pubkey) -> anyhow::Result<Option<Account>> {
+        for extension in Self::EXTENSIONS {
+            let path = self.dir.join(format!("{}.{}", pubkey, extension));
+            if fs::metadata(&path).is_ok() {
+                return Ok(Some(input::read_account_file(&path, None)?.1));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+impl AccountSource for FixtureDirSource {
+    async fn get_account(&self, pubkey: &Pubkey) -> anyhow::Result<Option<Account>> {
+        self.read(pubkey)
+    }
+
+    async fn get_multiple_accounts(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> anyhow::Result<Vec<Option<Account>>> {
+        pubkeys.iter().map(|pubkey| self.read(pubkey)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{prelude::BASE64_STANDARD, Engine};
+
+    use super::*;
+    use crate::test_fixtures::mint_account;
+
+    #[tokio::test]
+    async fn memory_source_keeps_order_and_gaps() {
+        let (first, missing, second) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let source = MemorySource::new()
+            .with_account(first, mint_account(6, 0))
+            .with_account(second, mint_account(9, 0));
+
+        let accounts = source
+            .get_multiple_accounts(&[second, missing, first])
+            .await
+            .unwrap();
+        assert_eq!(
+            accounts,
+            vec![Some(mint_account(9, 0)), None, Some(mint_account(6, 0))]
+        );
+
+        let err = require_account(&source, &missing).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("Account {} does not exist", missing)
+        );
+    }
+
+    #[tokio::test]
+    async fn fixture_dir_source_reads_json_and_base64() {
+        let dir = std::env::temp_dir().join(format!("surfer-fixtures-{}", Pubkey::new_unique()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let (json_mint, base64_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let account = mint_account(6, 0);
+        let data = BASE64_STANDARD.encode(&account.data);
+        fs::write(
+            dir.join(format!("{}.json", json_mint)),
+            serde_json::json!({
+                "pubkey": json_mint.to_string(),
+                "account": {
+                    "lamports": account.lamports,
+                    "data": [data, "base64"],
+                    "owner": spl_token::ID.to_string(),
+                    "executable": false,
+                    "rentEpoch": 0,
+                    "space": account.data.len(),
+                },
+            })
+            .to_string(),
+        )
+        .unwrap();
+        fs::write(dir.join(format!("{}.base64", base64_mint)), &data).unwrap();
+
+        let source = FixtureDirSource::new(&dir);
+        let accounts = source
+            .get_multiple_accounts(&[json_mint, base64_mint, Pubkey::new_unique()])
+            .await
+            .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(accounts[0], Some(account.clone()));
+        // Raw data doesn't record lamports, only the data and a guessed owner.
+        let base64_account = accounts[1].as_ref().unwrap();
+        assert_eq!(base64_account.data, account.data);
+        assert_eq!(base64_account.owner, spl_token::ID);
+        assert_eq!(accounts[2], None);
+    }
+}
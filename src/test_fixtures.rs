@@ -0,0 +1,308 @@
+//! Canned accounts shared by the unit tests.
+
+use mpl_token_metadata::types::Key;
+use solana_account::Account;
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    hash::Hash,
+    instruction::Instruction,
+    nonce::state::{Data, DurableNonce, State, Versions},
+    signature::{Keypair, Signer},
+    system_instruction, system_program,
+    transaction::Transaction,
+};
+use spl_token::{
+    solana_program::{program_option::COption, program_pack::Pack, pubkey::Pubkey},
+    state::AccountState,
+};
+use spl_token_2022::extension::{
+    immutable_owner::ImmutableOwner, mint_close_authority::MintCloseAuthority,
+    BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut,
+};
+
+/// An initialized legacy Token mint.
+pub(crate) fn mint_account(decimals: u8, supply: u64) -> Account {
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        supply,
+        decimals,
+        is_initialized: true,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut data);
+
+    Account {
+        data,
+        owner: spl_token::ID,
+        ..Account::default()
+    }
+}
+
+/// An initialized legacy Token account.
+pub(crate) fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Account {
+    token_account_with_state(mint, owner, amount, AccountState::Initialized)
+}
+
+/// A legacy Token account wrapping `amount` lamports of SOL.
+pub(crate) fn token_account_native(owner: &Pubkey, amount: u64) -> Account {
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint: spl_token::native_mint::ID,
+        owner: *owner,
+        amount,
+        state: AccountState::Initialized,
+        is_native: COption::Some(2_039_280),
+        ..Default::default()
+    }
+    .pack_into_slice(&mut data);
+
+    Account {
+        lamports: 2_039_280 + amount,
+        data,
+        owner: spl_token::ID,
+        ..Account::default()
+    }
+}
+
+pub(crate) fn token_account_with_state(
+    mint: &Pubkey,
+    owner: &Pubkey,
+    amount: u64,
+    state: AccountState,
+) -> Account {
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        state,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut data);
+
+    Account {
+        data,
+        owner: spl_token::ID,
+        ..Account::default()
+    }
+}
+
+/// An initialized Token-2022 mint with a close authority, so its
+/// `AccountType` follows the base account padding.
+pub(crate) fn token_2022_mint_account(decimals: u8, supply: u64) -> Account {
+    let len = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+        ExtensionType::MintCloseAuthority,
+    ])
+    .unwrap();
+    let mut data = vec![0; len];
+    let mut state =
+        StateWithExtensionsMut::<spl_token_2022::state::Mint>::unpack_uninitialized(&mut data)
+            .unwrap();
+    state
+        .init_extension::<MintCloseAuthority>(true)
+        .unwrap()
+        .close_authority = Some(Pubkey::new_unique()).try_into().unwrap();
+    state.base = spl_token_2022::state::Mint {
+        supply,
+        decimals,
+        is_initialized: true,
+        ..Default::default()
+    };
+    state.pack_base();
+    state.init_account_type().unwrap();
+
+    Account {
+        data,
+        owner: spl_token_2022::ID,
+        ..Account::default()
+    }
+}
+
+/// An initialized Token-2022 account with an immutable owner.
+pub(crate) fn token_2022_token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Account {
+    let len = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Account>(&[
+        ExtensionType::ImmutableOwner,
+    ])
+    .unwrap();
+    let mut data = vec![0; len];
+    let mut state =
+        StateWithExtensionsMut::<spl_token_2022::state::Account>::unpack_uninitialized(&mut data)
+            .unwrap();
+    state.init_extension::<ImmutableOwner>(true).unwrap();
+    state.base = spl_token_2022::state::Account {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        state: spl_token_2022::state::AccountState::Initialized,
+        ..Default::default()
+    };
+    state.pack_base();
+    state.init_account_type().unwrap();
+
+    Account {
+        data,
+        owner: spl_token_2022::ID,
+        ..Account::default()
+    }
+}
+
+/// An initialized legacy Token multisig.
+pub(crate) fn multisig_account(m: u8, signers: &[Pubkey]) -> Account {
+    let mut multisig = spl_token::state::Multisig {
+        m,
+        n: signers.len() as u8,
+        is_initialized: true,
+        ..Default::default()
+    };
+    multisig.signers[..signers.len()].copy_from_slice(signers);
+    let mut data = vec![0; spl_token::state::Multisig::LEN];
+    multisig.pack_into_slice(&mut data);
+
+    Account {
+        data,
+        owner: spl_token::ID,
+        ..Account::default()
+    }
+}
+
+/// An initialized durable nonce account.
+pub(crate) fn nonce_account(authority: &Pubkey) -> Account {
+    let durable_nonce = DurableNonce::from_blockhash(&Hash::new_unique());
+    let state = State::Initialized(Data::new(*authority, durable_nonce, 5000));
+
+    Account {
+        data: bincode::serialize(&Versions::new(state)).unwrap(),
+        owner: system_program::id(),
+        ..Account::default()
+    }
+}
+
+/// A metadata account as the program writes it, with NUL padded strings
+/// and every optional field left empty.
+pub(crate) fn metadata_account(mint: &Pubkey, name: &str, symbol: &str) -> Account {
+    let mut data = vec![Key::MetadataV1 as u8];
+    data.extend_from_slice(Pubkey::new_unique().as_ref());
+    data.extend_from_slice(mint.as_ref());
+    for (value, len) in [(name, 32), (symbol, 10), ("", 200)] {
+        data.extend_from_slice(&(len as u32).to_le_bytes());
+        data.extend_from_slice(format!("{:\0<len$}", value, len = len).as_bytes());
+    }
+    data.resize(679, 0);
+
+    Account {
+        data,
+        owner: mpl_token_metadata::ID,
+        ..Account::default()
+    }
+}
+
+pub(crate) fn master_edition_account(supply: u64, max_supply: Option<u64>) -> Account {
+    let mut data = vec![Key::MasterEditionV2 as u8];
+    data.extend_from_slice(&supply.to_le_bytes());
+    match max_supply {
+        Some(max_supply) => {
+            data.push(1);
+            data.extend_from_slice(&max_supply.to_le_bytes());
+        }
+        None => data.push(0),
+    }
+
+    Account {
+        data,
+        owner: mpl_token_metadata::ID,
+        ..Account::default()
+    }
+}
+
+/// Signs `ixs` with the test payer and `signers` and processes them.
+pub(crate) async fn process(
+    ctx: &mut ProgramTestContext,
+    ixs: &[Instruction],
+    signers: &[&Keypair],
+) {
+    let blockhash = ctx.banks_client.get_latest_blockhash().await.unwrap();
+    let mut all_signers = vec![&ctx.payer];
+    all_signers.extend_from_slice(signers);
+    let tx =
+        Transaction::new_signed_with_payer(ixs, Some(&ctx.payer.pubkey()), &all_signers, blockhash);
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+}
+
+pub(crate) async fn get_account(ctx: &mut ProgramTestContext, pubkey: &Pubkey) -> Account {
+    ctx.banks_client
+        .get_account(*pubkey)
+        .await
+        .unwrap()
+        .expect("account should exist")
+}
+
+/// Creates a mint of `program_id` with the test payer as mint authority.
+pub(crate) async fn create_mint(
+    ctx: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    decimals: u8,
+    freeze_authority: Option<&Pubkey>,
+) -> Pubkey {
+    let payer = ctx.payer.pubkey();
+    let mint = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    process(
+        ctx,
+        &[
+            system_instruction::create_account(
+                &payer,
+                &mint.pubkey(),
+                rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                program_id,
+            ),
+            spl_token_2022::instruction::initialize_mint2(
+                program_id,
+                &mint.pubkey(),
+                &payer,
+                freeze_authority,
+                decimals,
+            )
+            .unwrap(),
+        ],
+        &[&mint],
+    )
+    .await;
+
+    mint.pubkey()
+}
+
+/// Creates an `m`-of-n multisig of `program_id` over `signers`.
+pub(crate) async fn create_multisig(
+    ctx: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    m: u8,
+    signers: &[Pubkey],
+) -> Pubkey {
+    let payer = ctx.payer.pubkey();
+    let multisig = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    process(
+        ctx,
+        &[
+            system_instruction::create_account(
+                &payer,
+                &multisig.pubkey(),
+                rent.minimum_balance(spl_token::state::Multisig::LEN),
+                spl_token::state::Multisig::LEN as u64,
+                program_id,
+            ),
+            spl_token_2022::instruction::initialize_multisig2(
+                program_id,
+                &multisig.pubkey(),
+                &signers.iter().collect::<Vec<_>>(),
+                m,
+            )
+            .unwrap(),
+        ],
+        &[&multisig],
+    )
+    .await;
+
+    multisig.pubkey()
+}
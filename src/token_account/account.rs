@@ -1,45 +1,134 @@
+use std::{collections::HashMap, path::PathBuf};
+
 use crate::{
-    cli::{self, SolanaRpcArgs, TokenProgram},
-    extension::{token_account_extensions_data_bytes, ExtensionData},
+    config::Config,
+    extension::{print_extensions, token_account_extensions_data_bytes, ExtensionData},
+    input::{self, AccountInputArgs},
+    mint::{fetch_mints, PrettyMint},
+    multisig,
+    output::UiAmount,
+    source::{self, AccountSource, MemorySource},
+    token_program::TokenProgram,
+    utils,
 };
+
+use super::{pretty_token_account, pretty_token_account_with_mint};
 use clap::Args;
-use colored::*;
-use prettytable::{color, Attr, Cell, Row};
+use prettytable::{Row, Table};
+use serde_json::json;
 use solana_account::Account;
-use solana_client::nonblocking::rpc_client::RpcClient;
 use spl_token::solana_program::{program_pack::Pack, pubkey::Pubkey};
-use spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use spl_token_2022::{
+    extension::{BaseStateWithExtensions, StateWithExtensions},
+    state::AccountState,
+};
 
+/// A parsed token account with every field ready for display or JSON output.
 #[derive(Debug)]
 pub struct PrettyTokenAccount {
+    /// Address of the token account.
     pub token_account_pubkey: String,
+    /// Mint of the token account.
     pub mint: String,
+    /// Owner of the token account.
     pub owner: String,
+    /// Balance in base units.
     pub amount: u64,
+    /// Delegate allowed to transfer or burn `delegated_amount`, if any.
     pub delegate: Option<String>,
+    /// The raw `AccountState`, see [`PrettyTokenAccount::state_name`].
     pub state: u8,
+    /// Rent-exempt reserve of wrapped SOL accounts.
     pub is_native: Option<u64>,
+    /// Amount the delegate may still transfer or burn.
     pub delegated_amount: u64,
+    /// Who can close the account instead of the owner, if anyone.
     pub close_authority: Option<String>,
+    /// Token-2022 extensions, `None` for the legacy Token program.
     pub extensions: Option<Vec<ExtensionData>>,
+    /// Decimals of the mint.
+    pub decimals: u8,
+    /// UI amount including interest, for mints with an `InterestBearingConfig`.
+    pub interest_accrued_amount: Option<String>,
 }
 
+/// A parsed token account with its address and the decimals of its mint,
+/// converted into a [`PrettyTokenAccount`].
 pub struct TokenAccountWithPubkey {
+    /// The parsed token account.
     pub token_account: TokenAccountWithExtensions,
+    /// Address of the token account.
     pub pubkey: String,
+    /// Decimals of the mint.
+    pub decimals: u8,
 }
 
+/// The base token account state of either token program.
 pub enum TokenAccountType {
+    /// A token account of the legacy Token program.
     LegacyToken(spl_token::state::Account),
+    /// A token account of the Token-2022 program.
     Token2022(spl_token_2022::state::Account),
 }
+
+impl TokenAccountType {
+    /// Mint of the token account.
+    pub fn get_mint(&self) -> Pubkey {
+        match self {
+            TokenAccountType::LegacyToken(token_account) => token_account.mint,
+            TokenAccountType::Token2022(token_account) => token_account.mint,
+        }
+    }
+
+    /// Balance in base units.
+    pub fn get_amount(&self) -> u64 {
+        match self {
+            TokenAccountType::LegacyToken(token_account) => token_account.amount,
+            TokenAccountType::Token2022(token_account) => token_account.amount,
+        }
+    }
+
+    /// Whether the account holds wrapped SOL.
+    pub fn is_native(&self) -> bool {
+        match self {
+            TokenAccountType::LegacyToken(token_account) => token_account.is_native(),
+            TokenAccountType::Token2022(token_account) => token_account.is_native(),
+        }
+    }
+
+    /// Owner of the token account.
+    pub fn get_owner(&self) -> Pubkey {
+        match self {
+            TokenAccountType::LegacyToken(token_account) => token_account.owner,
+            TokenAccountType::Token2022(token_account) => token_account.owner,
+        }
+    }
+
+    /// Whether the account is frozen.
+    pub fn is_frozen(&self) -> bool {
+        match self {
+            TokenAccountType::LegacyToken(token_account) => token_account.is_frozen(),
+            TokenAccountType::Token2022(token_account) => token_account.is_frozen(),
+        }
+    }
+}
+/// A token account with its decoded Token-2022 extensions.
 pub struct TokenAccountWithExtensions {
+    /// The base token account state.
     pub base: TokenAccountType,
+    /// Token-2022 extensions, `None` for the legacy Token program.
     pub extensions: Option<Vec<ExtensionData>>,
 }
 
 impl TokenAccountWithExtensions {
+    /// Parses a token account owned by either token program. `extensions` is
+    /// `None` for the legacy Token program.
     pub fn try_parse_token_account_with_extensions(data: Account) -> anyhow::Result<Self> {
+        if multisig::is_multisig(&data) {
+            return Err(anyhow::anyhow!(
+                "Account is an SPL multisig, not a token account, use `surfer multisig fetch`"
+            ));
+        }
         let token_program = TokenProgram::try_from(data.owner)?;
 
         let data_bytes = data.data.as_slice();
@@ -75,76 +164,62 @@ impl TokenAccountWithExtensions {
 }
 
 impl PrettyTokenAccount {
-    pub fn to_header_cell(header: &str) -> Cell {
-        Cell::new(header)
-            .with_style(Attr::Bold)
-            .with_style(Attr::ForegroundColor(color::WHITE))
-    }
-
-    pub fn to_key_cell(key: &str) -> Cell {
-        Cell::new(key)
-            .with_style(Attr::Bold)
-            .with_style(Attr::ForegroundColor(color::GREEN))
-    }
-
-    pub fn to_value_cell(value: &str) -> Cell {
-        Cell::new(value)
-            .with_style(Attr::Bold)
-            .with_style(Attr::ForegroundColor(color::BRIGHT_CYAN))
-    }
+    /// Prints the token account as a table.
     pub fn print(&self) {
         let mut table = prettytable::Table::new();
 
-        table.add_row(Row::new(vec![Self::to_header_cell("Token Account Data")]));
+        table.add_row(Row::new(vec![utils::to_header_cell("Token Account Data")]));
 
         table.add_row(Row::new(vec![
-            Self::to_key_cell("Token Account Pubkey"),
-            Self::to_value_cell(&self.token_account_pubkey),
+            utils::to_key_cell("Token Account Pubkey"),
+            utils::to_value_cell(&self.token_account_pubkey),
         ]));
 
         table.add_row(Row::new(vec![
-            Self::to_key_cell("Mint"),
-            Self::to_value_cell(&self.mint),
+            utils::to_key_cell("Mint"),
+            utils::to_value_cell(&self.mint),
         ]));
 
         table.add_row(Row::new(vec![
-            Self::to_key_cell("Owner"),
-            Self::to_value_cell(&self.owner),
+            utils::to_key_cell("Owner"),
+            utils::to_value_cell(&self.owner),
         ]));
 
         table.add_row(Row::new(vec![
-            Self::to_key_cell("Amount"),
-            Self::to_value_cell(&self.amount.to_string()),
+            utils::to_key_cell("Amount"),
+            utils::to_value_cell(&UiAmount::new(self.amount, self.decimals).to_string()),
         ]));
 
+        if let Some(amount) = &self.interest_accrued_amount {
+            table.add_row(Row::new(vec![
+                utils::to_key_cell("Amount With Interest"),
+                utils::to_value_cell(amount),
+            ]));
+        }
+
         table.add_row(Row::new(vec![
-            Self::to_key_cell("Delegate"),
-            Self::to_value_cell(&self.delegate.clone().map_or("None".to_string(), |pk| pk)),
+            utils::to_key_cell("Delegate"),
+            utils::to_value_cell(&self.delegate.clone().map_or("None".to_string(), |pk| pk)),
         ]));
 
         table.add_row(Row::new(vec![
-            Self::to_key_cell("State"),
-            Self::to_value_cell(match self.state {
-                0 => "Frozen",
-                1 => "Initialized",
-                2 => "Uninitialized",
-                _ => "Unknown",
-            }),
+            utils::to_key_cell("State"),
+            utils::to_value_cell(self.state_name()),
         ]));
 
         table.add_row(Row::new(vec![
-            Self::to_key_cell("Is Native"),
-            Self::to_value_cell(&self.is_native.map_or("None".to_string(), |v| v.to_string())),
+            utils::to_key_cell("Is Native"),
+            utils::to_value_cell(&self.is_native.map_or("None".to_string(), |v| v.to_string())),
         ]));
 
         table.add_row(Row::new(vec![
-            Self::to_key_cell("Delegated Amount"),
-            Self::to_value_cell(&self.delegated_amount.to_string()),
+            utils::to_key_cell("Delegated Amount"),
+            utils::to_value_cell(&UiAmount::new(self.delegated_amount, self.decimals).to_string()),
         ]));
 
         table.add_row(Row::new(vec![
-            Self::to_key_cell("Close Authority"),
-            Self::to_value_cell(
+            utils::to_key_cell("Close Authority"),
+            utils::to_value_cell(
                 &self
                     .close_authority
                     .clone()
@@ -156,21 +231,48 @@ impl PrettyTokenAccount {
         table.set_format(*prettytable::format::consts::FORMAT_CLEAN);
         table.printstd();
         println!();
-        match &self.extensions {
-            Some(extensions) => {
-                let data = format!("{:#?}", extensions).bright_yellow();
-                println!(
-                    " {} : {}",
-                    "Token Account Extensions".purple().bold(),
-                    data.cyan().bold()
-                );
-            }
-            None => {}
+        if let Some(extensions) = &self.extensions {
+            print_extensions("Token Account Extensions", extensions, self.decimals);
         }
         println!();
     }
 }
 
+impl PrettyTokenAccount {
+    /// Name of the account state, e.g. `Frozen`.
+    pub fn state_name(&self) -> &'static str {
+        match AccountState::try_from(self.state) {
+            Ok(AccountState::Uninitialized) => "Uninitialized",
+            Ok(AccountState::Initialized) => "Initialized",
+            Ok(AccountState::Frozen) => "Frozen",
+            Err(_) => "Unknown",
+        }
+    }
+
+    /// The token account as the JSON document printed by
+    /// `surfer token-account fetch`.
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "pubkey": self.token_account_pubkey,
+            "mint": self.mint,
+            "owner": self.owner,
+            "amount": UiAmount::new(self.amount, self.decimals),
+            "interestAccruedAmount": self.interest_accrued_amount,
+            "delegate": self.delegate,
+            "state": self.state_name(),
+            "isNative": self.is_native.map(|lamports| lamports.to_string()),
+            "delegatedAmount": UiAmount::new(self.delegated_amount, self.decimals),
+            "closeAuthority": self.close_authority,
+            "extensions": self.extensions.as_ref().map(|extensions| {
+                extensions
+                    .iter()
+                    .map(|e| e.to_json(self.decimals))
+                    .collect::<Vec<_>>()
+            }),
+        })
+    }
+}
+
 impl From<TokenAccountWithPubkey> for PrettyTokenAccount {
     fn from(ta_with_pubkey: TokenAccountWithPubkey) -> Self {
         match ta_with_pubkey.token_account.base {
@@ -181,13 +283,15 @@ impl From<TokenAccountWithPubkey> for PrettyTokenAccount {
                 amount: token_account.amount,
                 delegate: token_account.delegate.map(|pk| pk.to_string()).into(),
                 state: token_account.state as u8,
-                is_native: token_account.is_native.map(|v| v.into()).into(),
+                is_native: token_account.is_native.into(),
                 delegated_amount: token_account.delegated_amount,
                 close_authority: token_account
                     .close_authority
                     .map(|pk| pk.to_string())
                     .into(),
                 extensions: None,
+                decimals: ta_with_pubkey.decimals,
+                interest_accrued_amount: None,
             },
             TokenAccountType::Token2022(token_account) => Self {
                 token_account_pubkey: ta_with_pubkey.pubkey.to_string(),
@@ -196,84 +300,409 @@ impl From<TokenAccountWithPubkey> for PrettyTokenAccount {
                 amount: token_account.amount,
                 delegate: token_account.delegate.map(|pk| pk.to_string()).into(),
                 state: token_account.state as u8,
-                is_native: token_account.is_native.map(|v| v.into()).into(),
+                is_native: token_account.is_native.into(),
                 delegated_amount: token_account.delegated_amount,
                 close_authority: token_account
                     .close_authority
                     .map(|pk| pk.to_string())
                     .into(),
                 extensions: ta_with_pubkey.token_account.extensions,
+                decimals: ta_with_pubkey.decimals,
+                interest_accrued_amount: None,
             },
         }
     }
 }
 
+/// Arguments of `surfer token-account fetch`.
 #[derive(Debug, Args)]
 pub struct FetchTokenAccount {
-    #[clap(flatten)]
-    pub solana: SolanaRpcArgs,
     #[clap(
-        value_parser = cli::Cli::parse_pubkey,
-        help = "The account address to fetch. Must be a valid base58 encoded pubkey."
+        value_parser = utils::parse_pubkey,
+        required_unless_present_any = ["from_file", "from_stdin", "pubkeys_file"],
+        help = "The account addresses to fetch. Must be valid base58 encoded pubkeys."
+    )]
+    pub(crate) account_pubkeys: Vec<Pubkey>,
+    #[clap(
+        long = "mint",
+        value_parser = utils::parse_pubkey,
+        conflicts_with_all = ["pubkeys_file", "batch"],
+        help = "The mint of a single account, to fetch both at once. Must be a valid base58 encoded pubkey."
     )]
-    pub account_pubkey: Pubkey,
+    pub(crate) mint_pubkey: Option<Pubkey>,
+    #[clap(
+        long,
+        conflicts_with_all = ["from_file", "from_stdin"],
+        help = "Also fetch the token accounts listed in this file, one address per line."
+    )]
+    pub(crate) pubkeys_file: Option<PathBuf>,
+    #[clap(
+        long,
+        conflicts_with_all = ["from_file", "from_stdin"],
+        help = "Print a table row or JSON array element per account even for a single address. Implied by several addresses or --pubkeys-file."
+    )]
+    pub(crate) batch: bool,
+    #[clap(flatten)]
+    pub(crate) input: AccountInputArgs,
     #[clap(
-        value_parser = cli::Cli::parse_pubkey,
-        help = "The mint address to fetch. Must be a valid base58 encoded pubkey."
+        long,
+        help = "Read the mint from a file as well, in the same formats as --from-file. The mint is fetched over RPC otherwise."
     )]
-    pub mint_pubkey: Option<Pubkey>,
+    pub(crate) mint_file: Option<PathBuf>,
 }
 
 impl FetchTokenAccount {
-    pub async fn process_fetch(&self) -> anyhow::Result<(Account, Account)> {
-        if let Some(mint_pubkey) = self.mint_pubkey {
-            // Concurrently fetch the token account and mint
-            let rpc_client = RpcClient::new(self.solana.solana_rpc_url.clone());
-            let fetch_res = tokio::join!(
-                rpc_client.get_account(&self.account_pubkey),
-                rpc_client.get_account(&mint_pubkey)
-            );
+    /// Whether the token accounts are printed together, as one table or a
+    /// JSON array. That is the case whenever several may have been asked
+    /// for, so the output shape never depends on how many addresses a
+    /// --pubkeys-file holds. Input read from a file or stdin is always a
+    /// single account.
+    pub fn is_batch(&self) -> bool {
+        !self.input.is_set()
+            && (self.batch || self.account_pubkeys.len() > 1 || self.pubkeys_file.is_some())
+    }
 
-            let (token_acc, mint_acc) = (
-                fetch_res
-                    .0
-                    .map_err(|e| anyhow::anyhow!("Error fetching account: {}", e))?,
-                fetch_res
-                    .1
-                    .map_err(|e| anyhow::anyhow!("Error fetching mint: {}", e))?,
-            );
+    /// Fetches the token account and its mint, or reads them from
+    /// --from-file, --from-stdin and --mint-file.
+    pub async fn process_fetch(
+        &self,
+        config: &Config,
+    ) -> anyhow::Result<(PrettyMint, PrettyTokenAccount)> {
+        let Some((pubkey, token_acc)) = self.input.read()? else {
+            return self.fetch_and_parse(&config.rpc_client()?).await;
+        };
+
+        let account_pubkey = input::single_pubkey(&self.account_pubkeys)?.or(pubkey);
+        match &self.mint_file {
+            Some(path) => {
+                let (_, mint_acc) = input::read_account_file(path, None)?;
+                pretty_token_account(
+                    &MemorySource::new(),
+                    account_pubkey.as_ref(),
+                    token_acc,
+                    mint_acc,
+                )
+                .await
+            }
+            None => {
+                let rpc_client = config.rpc_client()?;
+                let mint_pubkey = match self.mint_pubkey {
+                    Some(mint_pubkey) => mint_pubkey,
+                    None => TokenAccountWithExtensions::try_parse_token_account_with_extensions(
+                        token_acc.clone(),
+                    )?
+                    .base
+                    .get_mint(),
+                };
+                let mint_acc = source::require_account(&rpc_client, &mint_pubkey).await?;
+                pretty_token_account(&rpc_client, account_pubkey.as_ref(), token_acc, mint_acc)
+                    .await
+            }
+        }
+    }
+
+    /// Fetches the token account, its mint and the mint's Metaplex metadata
+    /// from `source`.
+    pub async fn fetch_and_parse<S: AccountSource + ?Sized>(
+        &self,
+        source: &S,
+    ) -> anyhow::Result<(PrettyMint, PrettyTokenAccount)> {
+        let account_pubkey = input::required_pubkey(input::single_pubkey(&self.account_pubkeys)?)?;
+        let (token_acc, mint_acc) = self.fetch_accounts(source, &account_pubkey).await?;
 
-            return Ok((token_acc, mint_acc));
+        pretty_token_account(source, Some(&account_pubkey), token_acc, mint_acc).await
+    }
+
+    /// Fetches every token account given on the command line or in
+    /// --pubkeys-file, then their distinct mints in a second batch.
+    pub async fn fetch_batch<S: AccountSource + ?Sized>(
+        &self,
+        source: &S,
+    ) -> anyhow::Result<Vec<(Pubkey, anyhow::Result<(PrettyMint, PrettyTokenAccount)>)>> {
+        if self.mint_pubkey.is_some() {
+            return Err(anyhow::anyhow!(
+                "--mint can only be given with a single token account"
+            ));
+        }
+        let account_pubkeys =
+            input::collect_pubkeys(&self.account_pubkeys, self.pubkeys_file.as_deref())?;
+
+        fetch_token_accounts(source, &account_pubkeys).await
+    }
+
+    async fn fetch_accounts<S: AccountSource + ?Sized>(
+        &self,
+        source: &S,
+        account_pubkey: &Pubkey,
+    ) -> anyhow::Result<(Account, Account)> {
+        if let Some(mint_pubkey) = self.mint_pubkey {
+            // Fetch the token account and mint in a single request
+            let mut accounts = source
+                .get_multiple_accounts(&[*account_pubkey, mint_pubkey])
+                .await?
+                .into_iter();
+
+            let token_acc = accounts
+                .next()
+                .flatten()
+                .ok_or_else(|| anyhow::anyhow!("Account {} does not exist", account_pubkey))?;
+            let mint_acc = accounts
+                .next()
+                .flatten()
+                .ok_or_else(|| anyhow::anyhow!("Mint {} does not exist", mint_pubkey))?;
+
+            Ok((token_acc, mint_acc))
         } else {
             // Sequentially fetch the token account and mint
-            let rpc_client = RpcClient::new(self.solana.solana_rpc_url.clone());
-            let token_acc = rpc_client.get_account(&self.account_pubkey).await?;
+            let token_acc = source::require_account(source, account_pubkey).await?;
+            let mint = TokenAccountWithExtensions::try_parse_token_account_with_extensions(
+                token_acc.clone(),
+            )?
+            .base
+            .get_mint();
 
-            let token_program = TokenProgram::try_from(token_acc.owner)?;
+            let mint_acc = source::require_account(source, &mint).await?;
 
-            println!("Token program: {:?}", token_program);
+            Ok((token_acc, mint_acc))
+        }
+    }
+}
 
-            match token_program {
-                TokenProgram::LegacyToken => {
-                    let token_account =
-                        spl_token::state::Account::unpack(&token_acc.data.as_slice())
-                            .map_err(|e| anyhow::anyhow!("Error unpacking token account: {}", e))?;
+/// Fetches token accounts and then their distinct mints with Metaplex
+/// metadata, in batched [`AccountSource::get_multiple_accounts`] calls.
+/// Accounts that are missing or fail to parse, or whose mint does, get their
+/// own error instead of failing the whole batch.
+pub async fn fetch_token_accounts<S: AccountSource + ?Sized>(
+    source: &S,
+    account_pubkeys: &[Pubkey],
+) -> anyhow::Result<Vec<(Pubkey, anyhow::Result<(PrettyMint, PrettyTokenAccount)>)>> {
+    let token_accounts: Vec<_> = source
+        .get_multiple_accounts(account_pubkeys)
+        .await?
+        .into_iter()
+        .zip(account_pubkeys)
+        .map(|(account, pubkey)| {
+            let token_account = account
+                .ok_or_else(|| anyhow::anyhow!("Account {} does not exist", pubkey))
+                .and_then(TokenAccountWithExtensions::try_parse_token_account_with_extensions);
+            (*pubkey, token_account)
+        })
+        .collect();
+
+    let mut mint_pubkeys: Vec<Pubkey> = token_accounts
+        .iter()
+        .filter_map(|(_, token_account)| Some(token_account.as_ref().ok()?.base.get_mint()))
+        .collect();
+    mint_pubkeys.sort();
+    mint_pubkeys.dedup();
+    let mints: HashMap<Pubkey, anyhow::Result<PrettyMint>> = fetch_mints(source, &mint_pubkeys)
+        .await?
+        .into_iter()
+        .collect();
+
+    Ok(token_accounts
+        .into_iter()
+        .map(|(pubkey, token_account)| {
+            let result = token_account.and_then(|token_account| {
+                let mint_pubkey = token_account.base.get_mint();
+                let mint = mints[&mint_pubkey]
+                    .as_ref()
+                    .map_err(|e| anyhow::anyhow!("Error fetching mint {}: {}", mint_pubkey, e))?;
+                let token_account =
+                    pretty_token_account_with_mint(token_account, Some(&pubkey), mint);
+                Ok((mint.clone(), token_account))
+            });
+            (pubkey, result)
+        })
+        .collect())
+}
 
-                    let mint_acc = rpc_client.get_account(&token_account.mint).await?;
+/// Prints a batch of token accounts as one table with a row per account.
+pub fn print_token_accounts(
+    token_accounts: &[(Pubkey, anyhow::Result<(PrettyMint, PrettyTokenAccount)>)],
+) {
+    let headers = ["Account", "Mint", "Symbol", "Owner", "Balance", "State"];
+    let mut table = Table::new();
+    table.add_row(Row::new(
+        headers.into_iter().map(utils::to_header_cell).collect(),
+    ));
+    for (pubkey, result) in token_accounts {
+        let (mint, token_account) = match result {
+            Ok(result) => result,
+            Err(e) => {
+                table.add_row(Row::new(vec![
+                    utils::to_key_cell(&pubkey.to_string()),
+                    utils::to_error_cell(&e.to_string()).with_hspan(headers.len() - 1),
+                ]));
+                continue;
+            }
+        };
+        let balance = token_account.interest_accrued_amount.clone().unwrap_or(
+            UiAmount::new(token_account.amount, token_account.decimals).ui_amount_string,
+        );
+        table.add_row(Row::new(vec![
+            utils::to_key_cell(&token_account.token_account_pubkey),
+            utils::to_value_cell(&token_account.mint),
+            utils::to_value_cell(
+                &mint
+                    .name_and_symbol()
+                    .map_or("-".to_string(), |(_, symbol)| symbol),
+            ),
+            utils::to_value_cell(&token_account.owner),
+            utils::to_value_cell(&balance),
+            utils::to_value_cell(token_account.state_name()),
+        ]));
+    }
 
-                    Ok((token_acc, mint_acc))
-                }
-                TokenProgram::Token2022 => {
-                    let token_account =
-                        StateWithExtensions::<spl_token_2022::state::Account>::unpack(
-                            &token_acc.data.as_slice(),
-                        )?;
+    println!();
+    table.printstd();
+}
 
-                    let mint_acc = rpc_client.get_account(&token_account.base.mint).await?;
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::test_fixtures::{mint_account, token_account};
+
+    fn fetch_token_account(
+        account_pubkey: Pubkey,
+        mint_pubkey: Option<Pubkey>,
+    ) -> FetchTokenAccount {
+        FetchTokenAccount {
+            account_pubkeys: vec![account_pubkey],
+            mint_pubkey,
+            pubkeys_file: None,
+            batch: false,
+            input: AccountInputArgs::default(),
+            mint_file: None,
+        }
+    }
 
-                    Ok((token_acc, mint_acc))
-                }
-            }
+    #[tokio::test]
+    async fn fetch_token_account_and_its_mint() {
+        let (account_pubkey, mint_pubkey, owner) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let source = MemorySource::new()
+            .with_account(
+                account_pubkey,
+                token_account(&mint_pubkey, &owner, 1_500_000),
+            )
+            .with_account(mint_pubkey, mint_account(6, 0));
+
+        // The mint is read from the token account unless it is given.
+        for given_mint in [None, Some(mint_pubkey)] {
+            let (mint, token_account) = fetch_token_account(account_pubkey, given_mint)
+                .fetch_and_parse(&source)
+                .await
+                .unwrap();
+            assert_eq!(mint.mint_pubkey, mint_pubkey.to_string());
+            assert_eq!(
+                token_account.token_account_pubkey,
+                account_pubkey.to_string()
+            );
+            assert_eq!(token_account.owner, owner.to_string());
+            assert_eq!(
+                (token_account.amount, token_account.decimals),
+                (1_500_000, 6)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn fetch_token_account_with_missing_mint() {
+        let (account_pubkey, mint_pubkey) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let source = MemorySource::new().with_account(
+            account_pubkey,
+            token_account(&mint_pubkey, &Pubkey::new_unique(), 0),
+        );
+
+        let err = fetch_token_account(account_pubkey, Some(mint_pubkey))
+            .fetch_and_parse(&source)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("Mint {} does not exist", mint_pubkey)
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_token_accounts_shares_mints() {
+        let (mint_pubkey, missing_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (first, second, missing, orphan) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let source = MemorySource::new()
+            .with_account(mint_pubkey, mint_account(2, 0))
+            .with_account(
+                first,
+                token_account(&mint_pubkey, &Pubkey::new_unique(), 100),
+            )
+            .with_account(
+                second,
+                token_account(&mint_pubkey, &Pubkey::new_unique(), 250),
+            )
+            .with_account(
+                orphan,
+                token_account(&missing_mint, &Pubkey::new_unique(), 1),
+            );
+
+        let token_accounts = fetch_token_accounts(&source, &[first, missing, second, orphan])
+            .await
+            .unwrap();
+        let pubkeys: Vec<_> = token_accounts.iter().map(|(pubkey, _)| *pubkey).collect();
+        assert_eq!(pubkeys, vec![first, missing, second, orphan]);
+
+        for (index, amount) in [(0, 100), (2, 250)] {
+            let (mint, token_account) = token_accounts[index].1.as_ref().unwrap();
+            assert_eq!(mint.mint_pubkey, mint_pubkey.to_string());
+            assert_eq!((token_account.amount, token_account.decimals), (amount, 2));
+        }
+        assert_eq!(
+            token_accounts[1].1.as_ref().unwrap_err().to_string(),
+            format!("Account {} does not exist", missing)
+        );
+        assert_eq!(
+            token_accounts[3].1.as_ref().unwrap_err().to_string(),
+            format!(
+                "Error fetching mint {}: Account {} does not exist",
+                missing_mint, missing_mint
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn mint_only_applies_to_a_single_account() {
+        let mut args = fetch_token_account(Pubkey::new_unique(), Some(Pubkey::new_unique()));
+        args.account_pubkeys.push(Pubkey::new_unique());
+        assert!(args.is_batch());
+        let err = args.fetch_batch(&MemorySource::new()).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "--mint can only be given with a single token account"
+        );
+
+        #[derive(Parser)]
+        struct Fetch {
+            #[clap(flatten)]
+            args: FetchTokenAccount,
+        }
+        let (account, mint) = (
+            Pubkey::new_unique().to_string(),
+            Pubkey::new_unique().to_string(),
+        );
+        let fetch = ["fetch", &account, "--mint", &mint];
+        for conflicting in [&["--pubkeys-file", "accounts.txt"][..], &["--batch"]] {
+            let parsed = Fetch::try_parse_from(fetch.iter().chain(conflicting));
+            assert!(parsed.is_err(), "--mint with {}", conflicting[0]);
         }
+        assert!(Fetch::try_parse_from(fetch).is_ok());
     }
 }
@@ -1,60 +1,266 @@
-use std::time::Duration;
+use std::{str::FromStr, time::Duration};
 
-use clap::Subcommand;
+use clap::{Args, Subcommand};
+use solana_account::Account;
+use solana_sdk::signer::Signer;
+use spl_token::solana_program::{instruction::Instruction, pubkey::Pubkey};
 
 use crate::{
-    cli::{LocalWallet, SolanaRpcArgs, TokenProgram},
-    mint::FetchMint,
-    utils::{self, get_pubkey_from_prompt},
+    amount::Amount,
+    config::Config,
+    mint::MintWithExtensions,
+    output,
+    signer::{LocalWallet, SignerSource},
+    source::{self, AccountSource},
+    token_account::TokenAccountWithExtensions,
+    token_program::TokenProgram,
+    transaction::{self, SendArgs},
+    utils::{self, pubkey_or_prompt, value_or_prompt},
 };
 
+/// Arguments of `surfer token-account ix`. Prompts for the instruction if
+/// none is given.
+#[derive(Debug, Args)]
+pub struct TokenAccountIxArgs {
+    /// The signer and transaction flags.
+    #[clap(flatten)]
+    pub send: SendArgs,
+    #[clap(subcommand)]
+    pub(crate) ix: Option<TokenAccountInstructions>,
+}
+
+/// The token account instructions `surfer token-account ix` can send.
 #[derive(Debug, Clone, Subcommand)]
 pub enum TokenAccountInstructions {
-    Create,
-    Transfer,
+    /// Create the associated token account of a wallet for a mint
+    Create(CreateTokenAccountArgs),
+    /// Transfer tokens between token accounts
+    Transfer(TransferArgs),
+    /// Burn tokens from a token account
+    Burn(BurnArgs),
+    /// Burn tokens from a token account, asserting the mint decimals
+    BurnChecked(BurnArgs),
+    /// Allow a delegate to transfer or burn tokens from a token account
+    Approve(ApproveArgs),
+    /// Approve a delegate, asserting the mint decimals
+    ApproveChecked(ApproveArgs),
+    /// Remove the delegate of a token account
+    Revoke(RevokeArgs),
+    /// Close an empty token account and reclaim its rent
+    CloseAccount(CloseAccountArgs),
+    /// Freeze a token account using the mint freeze authority
+    FreezeAccount(FreezeAccountArgs),
+    /// Thaw a frozen token account using the mint freeze authority
+    ThawAccount(FreezeAccountArgs),
+}
+
+/// Arguments of `Create`.
+#[derive(Debug, Clone, Default, Args)]
+pub struct CreateTokenAccountArgs {
+    #[clap(long, value_parser = utils::parse_pubkey, help = "The mint of the new token account.")]
+    pub(crate) mint: Option<Pubkey>,
+    #[clap(
+        long,
+        value_parser = utils::parse_pubkey,
+        help = "The wallet owning the new token account. Defaults to the local wallet."
+    )]
+    pub(crate) wallet_address: Option<Pubkey>,
+}
+
+/// Arguments of `Transfer`.
+#[derive(Debug, Clone, Default, Args)]
+pub struct TransferArgs {
+    #[clap(long, value_parser = utils::parse_pubkey, help = "The mint of the tokens to transfer.")]
+    pub(crate) mint: Option<Pubkey>,
+    #[clap(long, value_parser = utils::parse_pubkey, help = "The source token account.")]
+    pub(crate) from: Option<Pubkey>,
+    #[clap(long, value_parser = utils::parse_pubkey, help = "The destination token account.")]
+    pub(crate) to: Option<Pubkey>,
+    #[clap(
+        long,
+        help = "The amount to transfer in UI units, e.g. 1.5, or ALL for the full source balance."
+    )]
+    pub(crate) amount: Option<Amount>,
+    #[clap(
+        long,
+        help = "Interpret --amount as raw base units instead of UI units."
+    )]
+    pub(crate) raw: bool,
+    #[clap(
+        long,
+        value_parser = SignerSource::from_str,
+        help = "Keypair of the source token account owner, in the same formats as --keypair. Defaults to the wallet."
+    )]
+    pub(crate) owner: Option<SignerSource>,
+}
+
+/// Arguments of `Revoke`.
+#[derive(Debug, Clone, Default, Args)]
+pub struct RevokeArgs {
+    #[clap(long, value_parser = utils::parse_pubkey, help = "The token account.")]
+    pub(crate) account: Option<Pubkey>,
+    #[clap(
+        long,
+        value_parser = SignerSource::from_str,
+        help = "Keypair of the token account owner, in the same formats as --keypair. Defaults to the wallet."
+    )]
+    pub(crate) owner: Option<SignerSource>,
+}
+
+/// Arguments of `FreezeAccount` and `ThawAccount`.
+#[derive(Debug, Clone, Default, Args)]
+pub struct FreezeAccountArgs {
+    #[clap(long, value_parser = utils::parse_pubkey, help = "The token account.")]
+    pub(crate) account: Option<Pubkey>,
+    #[clap(
+        long,
+        value_parser = SignerSource::from_str,
+        help = "Keypair of the mint freeze authority, in the same formats as --keypair. Defaults to the wallet."
+    )]
+    pub(crate) authority: Option<SignerSource>,
+}
+
+/// Arguments of `Burn` and `BurnChecked`.
+#[derive(Debug, Clone, Default, Args)]
+pub struct BurnArgs {
+    #[clap(long, value_parser = utils::parse_pubkey, help = "The token account to burn from.")]
+    pub(crate) account: Option<Pubkey>,
+    #[clap(
+        long,
+        help = "The amount to burn in UI units, e.g. 1.5, or ALL for the full account balance."
+    )]
+    pub(crate) amount: Option<Amount>,
+    #[clap(
+        long,
+        help = "Interpret --amount as raw base units instead of UI units."
+    )]
+    pub(crate) raw: bool,
+    #[clap(
+        long,
+        value_parser = SignerSource::from_str,
+        help = "Keypair of the token account owner, in the same formats as --keypair. Defaults to the wallet."
+    )]
+    pub(crate) owner: Option<SignerSource>,
+}
+
+/// Arguments of `Approve` and `ApproveChecked`.
+#[derive(Debug, Clone, Default, Args)]
+pub struct ApproveArgs {
+    #[clap(long, value_parser = utils::parse_pubkey, help = "The token account to delegate.")]
+    pub(crate) account: Option<Pubkey>,
+    #[clap(long, value_parser = utils::parse_pubkey, help = "The delegate.")]
+    pub(crate) delegate: Option<Pubkey>,
+    #[clap(
+        long,
+        help = "The amount to approve in UI units, e.g. 1.5, or ALL for the full account balance."
+    )]
+    pub(crate) amount: Option<Amount>,
+    #[clap(
+        long,
+        help = "Interpret --amount as raw base units instead of UI units."
+    )]
+    pub(crate) raw: bool,
+    #[clap(
+        long,
+        value_parser = SignerSource::from_str,
+        help = "Keypair of the token account owner, in the same formats as --keypair. Defaults to the wallet."
+    )]
+    pub(crate) owner: Option<SignerSource>,
+}
+
+/// Arguments of `CloseAccount`.
+#[derive(Debug, Clone, Default, Args)]
+pub struct CloseAccountArgs {
+    #[clap(long, value_parser = utils::parse_pubkey, help = "The token account to close.")]
+    pub(crate) account: Option<Pubkey>,
+    #[clap(
+        long,
+        value_parser = utils::parse_pubkey,
+        help = "The account receiving the rent. Defaults to the owner."
+    )]
+    pub(crate) destination: Option<Pubkey>,
+    #[clap(
+        long,
+        value_parser = SignerSource::from_str,
+        help = "Keypair of the token account owner, in the same formats as --keypair. Defaults to the wallet."
+    )]
+    pub(crate) owner: Option<SignerSource>,
 }
 
 impl TokenAccountInstructions {
+    /// Names of the instructions, for the interactive prompt.
     pub fn to_select_vec() -> Vec<&'static str> {
-        vec!["Create", "Transfer"]
+        vec![
+            "Create",
+            "Transfer",
+            "Burn",
+            "BurnChecked",
+            "Approve",
+            "ApproveChecked",
+            "Revoke",
+            "CloseAccount",
+            "FreezeAccount",
+            "ThawAccount",
+        ]
     }
 
+    /// The instruction picked at the prompt, with its arguments left to be
+    /// prompted for.
     pub fn from_select_str(select_str: &str) -> anyhow::Result<Self> {
         match select_str {
-            "Create" => Ok(Self::Create),
-            "Transfer" => Ok(Self::Transfer),
+            "Create" => Ok(Self::Create(CreateTokenAccountArgs::default())),
+            "Transfer" => Ok(Self::Transfer(TransferArgs::default())),
+            "Burn" => Ok(Self::Burn(BurnArgs::default())),
+            "BurnChecked" => Ok(Self::BurnChecked(BurnArgs::default())),
+            "Approve" => Ok(Self::Approve(ApproveArgs::default())),
+            "ApproveChecked" => Ok(Self::ApproveChecked(ApproveArgs::default())),
+            "Revoke" => Ok(Self::Revoke(RevokeArgs::default())),
+            "CloseAccount" => Ok(Self::CloseAccount(CloseAccountArgs::default())),
+            "FreezeAccount" => Ok(Self::FreezeAccount(FreezeAccountArgs::default())),
+            "ThawAccount" => Ok(Self::ThawAccount(FreezeAccountArgs::default())),
 
-            _ => Err(anyhow::anyhow!("Invalid mint instruction: {}", select_str)),
+            _ => Err(anyhow::anyhow!(
+                "Invalid token account instruction: {}",
+                select_str
+            )),
         }
     }
 
-    pub async fn process_ix(&self, rpc: &SolanaRpcArgs) -> anyhow::Result<()> {
-        let local_wallet = LocalWallet::fetch()?;
-        let ix = match self {
-            TokenAccountInstructions::Create => {
-                println!("Create token account");
+    /// Builds the instruction, prompting for missing arguments, then signs
+    /// and sends it.
+    pub async fn process_ix(&self, config: &Config) -> anyhow::Result<()> {
+        let local_wallet = LocalWallet::fetch(config)?;
+        let rpc = &config.rpc_client()?;
+        let (ix, signers) = match self {
+            TokenAccountInstructions::Create(args) => {
+                output::print_heading("Create token account", config.output);
 
-                let mint_pubkey = get_pubkey_from_prompt("Mint account pubkey")?;
+                let mint_pubkey = pubkey_or_prompt(args.mint, "--mint", "Mint account pubkey")?;
+                let mint_acc = source::require_account(rpc, &mint_pubkey).await?;
 
-                spl_associated_token_account::instruction::create_associated_token_account(
-                    &local_wallet.pubkey(),
-                    &local_wallet.pubkey(),
+                let ix = build_create_ix(
+                    &local_wallet.fee_payer.pubkey(),
+                    &args.wallet_address.unwrap_or(local_wallet.pubkey()),
                     &mint_pubkey,
-                    &spl_token_2022::ID,
-                )
+                    &mint_acc,
+                )?;
+
+                (ix, vec![])
             }
-            TokenAccountInstructions::Transfer => {
-                println!("Transfer token account");
+            TokenAccountInstructions::Transfer(args) => {
+                output::print_heading("Transfer token account", config.output);
 
-                let mint_pubkey = get_pubkey_from_prompt("Mint account pubkey")?;
+                let mint_pubkey = pubkey_or_prompt(args.mint, "--mint", "Mint account pubkey")?;
 
-                let source_pubkey = get_pubkey_from_prompt("Source account pubkey")?;
-                let dest_pubkey = get_pubkey_from_prompt("Destination account pubkey")?;
+                let source_pubkey = pubkey_or_prompt(args.from, "--from", "Source account pubkey")?;
+                let dest_pubkey = pubkey_or_prompt(args.to, "--to", "Destination account pubkey")?;
 
-                let mut amount = inquire::Text::new("Amount to transfer without decimals")
-                    .prompt()
-                    .expect("Failed to get amount to transfer")
-                    .parse::<u64>()?;
+                let amount = value_or_prompt(
+                    args.amount.clone(),
+                    "--amount",
+                    "Amount to transfer (e.g. 1.5, or ALL)",
+                    None,
+                )?;
 
                 if source_pubkey.eq(&dest_pubkey) {
                     return Err(anyhow::anyhow!(
@@ -64,37 +270,740 @@ impl TokenAccountInstructions {
 
                 let spinner = utils::get_spinner("Fetching mint data...")?;
                 spinner.enable_steady_tick(Duration::from_millis(100));
-                let mint_acc = FetchMint {
-                    mint_pubkey,
-                    solana: rpc.clone(),
-                }
-                .process_fetch_and_parse()
-                .await?;
+                let mint = MintWithExtensions::try_parse_mint_with_extensions(
+                    source::require_account(rpc, &mint_pubkey).await?,
+                    None,
+                )?;
+                let decimals = mint.base.get_decimals();
+                let balance = match amount {
+                    Amount::All => Some(
+                        fetch_token_account(rpc, &source_pubkey)
+                            .await?
+                            .1
+                            .base
+                            .get_amount(),
+                    ),
+                    Amount::Decimal(_) => None,
+                };
                 spinner.finish_and_clear();
 
-                amount = amount
-                    .checked_mul(10u64.pow(mint_acc.base.get_decimals() as u32))
-                    .ok_or_else(|| anyhow::anyhow!("Failed to calculate amount"))?;
+                let amount = amount.to_raw_amount(decimals, balance, args.raw)?;
+                let owner = local_wallet.authority(args.owner.as_ref(), "owner")?;
 
-                spl_token_2022::instruction::transfer_checked(
-                    &TokenProgram::Token2022.into(),
+                let ix = build_transfer_ix(
                     &source_pubkey,
                     &mint_pubkey,
+                    &mint,
                     &dest_pubkey,
-                    &local_wallet.pubkey(),
-                    &[&local_wallet.pubkey()],
+                    &owner.pubkey,
+                    &owner.multisig_signers(),
                     amount,
-                    mint_acc.base.get_decimals(),
-                )?
+                )?;
+
+                (ix, owner.signers)
+            }
+            TokenAccountInstructions::Burn(args) | TokenAccountInstructions::BurnChecked(args) => {
+                output::print_heading("Burn tokens", config.output);
+
+                let account_pubkey =
+                    pubkey_or_prompt(args.account, "--account", "Token account pubkey")?;
+                let amount = value_or_prompt(
+                    args.amount.clone(),
+                    "--amount",
+                    "Amount to burn (e.g. 1.5, or ALL)",
+                    None,
+                )?;
+
+                let spinner = utils::get_spinner("Fetching account data...")?;
+                spinner.enable_steady_tick(Duration::from_millis(100));
+                let (_, token_account) = fetch_token_account(rpc, &account_pubkey).await?;
+                let mint_pubkey = token_account.base.get_mint();
+                let mint = fetch_mint(rpc, &mint_pubkey).await?;
+                spinner.finish_and_clear();
+
+                let amount = amount.to_raw_amount(
+                    mint.base.get_decimals(),
+                    Some(token_account.base.get_amount()),
+                    args.raw,
+                )?;
+                let owner = local_wallet.authority(args.owner.as_ref(), "owner")?;
+
+                let ix = if let TokenAccountInstructions::Burn(_) = self {
+                    build_burn_ix(
+                        &account_pubkey,
+                        &mint_pubkey,
+                        &mint,
+                        &owner.pubkey,
+                        &owner.multisig_signers(),
+                        amount,
+                    )?
+                } else {
+                    build_burn_checked_ix(
+                        &account_pubkey,
+                        &mint_pubkey,
+                        &mint,
+                        &owner.pubkey,
+                        &owner.multisig_signers(),
+                        amount,
+                    )?
+                };
+
+                (ix, owner.signers)
+            }
+            TokenAccountInstructions::Approve(args)
+            | TokenAccountInstructions::ApproveChecked(args) => {
+                output::print_heading("Approve delegate", config.output);
+
+                let account_pubkey =
+                    pubkey_or_prompt(args.account, "--account", "Token account pubkey")?;
+                let delegate_pubkey =
+                    pubkey_or_prompt(args.delegate, "--delegate", "Delegate pubkey")?;
+                let amount = value_or_prompt(
+                    args.amount.clone(),
+                    "--amount",
+                    "Amount to approve (e.g. 1.5, or ALL)",
+                    None,
+                )?;
+
+                let spinner = utils::get_spinner("Fetching account data...")?;
+                spinner.enable_steady_tick(Duration::from_millis(100));
+                let (_, token_account) = fetch_token_account(rpc, &account_pubkey).await?;
+                let mint_pubkey = token_account.base.get_mint();
+                let mint = fetch_mint(rpc, &mint_pubkey).await?;
+                spinner.finish_and_clear();
+
+                let amount = amount.to_raw_amount(
+                    mint.base.get_decimals(),
+                    Some(token_account.base.get_amount()),
+                    args.raw,
+                )?;
+                let owner = local_wallet.authority(args.owner.as_ref(), "owner")?;
+
+                let ix = if let TokenAccountInstructions::Approve(_) = self {
+                    build_approve_ix(
+                        &account_pubkey,
+                        &mint,
+                        &delegate_pubkey,
+                        &owner.pubkey,
+                        &owner.multisig_signers(),
+                        amount,
+                    )?
+                } else {
+                    build_approve_checked_ix(
+                        &account_pubkey,
+                        &mint_pubkey,
+                        &mint,
+                        &delegate_pubkey,
+                        &owner.pubkey,
+                        &owner.multisig_signers(),
+                        amount,
+                    )?
+                };
+
+                (ix, owner.signers)
+            }
+            TokenAccountInstructions::Revoke(args) => {
+                output::print_heading("Revoke delegate", config.output);
+
+                let account_pubkey =
+                    pubkey_or_prompt(args.account, "--account", "Token account pubkey")?;
+                let (program_id, _) = fetch_token_account(rpc, &account_pubkey).await?;
+                let owner = local_wallet.authority(args.owner.as_ref(), "owner")?;
+
+                let ix = build_revoke_ix(
+                    &program_id,
+                    &account_pubkey,
+                    &owner.pubkey,
+                    &owner.multisig_signers(),
+                )?;
+
+                (ix, owner.signers)
+            }
+            TokenAccountInstructions::CloseAccount(args) => {
+                output::print_heading("Close token account", config.output);
+
+                let account_pubkey =
+                    pubkey_or_prompt(args.account, "--account", "Token account pubkey")?;
+                let (program_id, token_account) = fetch_token_account(rpc, &account_pubkey).await?;
+                ensure_closable(&account_pubkey, &token_account)?;
+
+                let owner = local_wallet.authority(args.owner.as_ref(), "owner")?;
+
+                let ix = build_close_account_ix(
+                    &program_id,
+                    &account_pubkey,
+                    // A multisig can't spend lamports, so its rent goes to the wallet.
+                    &args.destination.unwrap_or(match owner.is_multisig() {
+                        true => local_wallet.pubkey(),
+                        false => owner.pubkey,
+                    }),
+                    &owner.pubkey,
+                    &owner.multisig_signers(),
+                )?;
+
+                (ix, owner.signers)
+            }
+            TokenAccountInstructions::FreezeAccount(args)
+            | TokenAccountInstructions::ThawAccount(args) => {
+                let account_pubkey =
+                    pubkey_or_prompt(args.account, "--account", "Token account pubkey")?;
+                let (program_id, token_account) = fetch_token_account(rpc, &account_pubkey).await?;
+                let mint_pubkey = token_account.base.get_mint();
+                let authority =
+                    local_wallet.authority(args.authority.as_ref(), "freeze authority")?;
+
+                let ix = if let TokenAccountInstructions::FreezeAccount(_) = self {
+                    output::print_heading("Freeze token account", config.output);
+
+                    build_freeze_ix(
+                        &program_id,
+                        &account_pubkey,
+                        &mint_pubkey,
+                        &authority.pubkey,
+                        &authority.multisig_signers(),
+                    )?
+                } else {
+                    output::print_heading("Thaw token account", config.output);
+
+                    build_thaw_ix(
+                        &program_id,
+                        &account_pubkey,
+                        &mint_pubkey,
+                        &authority.pubkey,
+                        &authority.multisig_signers(),
+                    )?
+                };
+
+                (ix, authority.signers)
             }
         };
 
-        let spinner = utils::get_spinner("Sending tx...")?;
-        spinner.enable_steady_tick(Duration::from_millis(100));
-        local_wallet
-            .sign_and_send_ixs(vec![ix], &rpc.solana_rpc_url)
-            .await?;
-        spinner.finish_and_clear();
+        if let Some(sig) = local_wallet
+            .sign_and_send_ixs(vec![ix], config, &signers)
+            .await?
+        {
+            transaction::print_signature(&sig, config.output)?;
+        }
         Ok(())
     }
 }
+
+/// Builds the instruction creating `owner`'s associated token account for
+/// `mint`, against the token program owning the mint.
+pub fn build_create_ix(
+    payer: &Pubkey,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    mint_acc: &Account,
+) -> anyhow::Result<Instruction> {
+    let program_id: Pubkey = TokenProgram::try_from(mint_acc.owner)?.into();
+
+    Ok(
+        spl_associated_token_account::instruction::create_associated_token_account(
+            payer,
+            owner,
+            mint,
+            &program_id,
+        ),
+    )
+}
+
+/// Builds a `TransferChecked` of `amount` base units against the token
+/// program owning the parsed `mint_data`. `multisig_signers` is empty unless
+/// `authority` is a multisig.
+pub fn build_transfer_ix(
+    source: &Pubkey,
+    mint: &Pubkey,
+    mint_data: &MintWithExtensions,
+    destination: &Pubkey,
+    authority: &Pubkey,
+    multisig_signers: &[&Pubkey],
+    amount: u64,
+) -> anyhow::Result<Instruction> {
+    Ok(spl_token_2022::instruction::transfer_checked(
+        &mint_data.base.token_program().into(),
+        source,
+        mint,
+        destination,
+        authority,
+        multisig_signers,
+        amount,
+        mint_data.base.get_decimals(),
+    )?)
+}
+
+/// Builds a `Burn` of `amount` base units against the token program owning
+/// the parsed `mint_data`. `multisig_signers` is empty unless `owner` is a
+/// multisig.
+pub fn build_burn_ix(
+    account: &Pubkey,
+    mint: &Pubkey,
+    mint_data: &MintWithExtensions,
+    owner: &Pubkey,
+    multisig_signers: &[&Pubkey],
+    amount: u64,
+) -> anyhow::Result<Instruction> {
+    Ok(spl_token_2022::instruction::burn(
+        &mint_data.base.token_program().into(),
+        account,
+        mint,
+        owner,
+        multisig_signers,
+        amount,
+    )?)
+}
+
+/// Same as [`build_burn_ix`], but the instruction also asserts the decimals
+/// of `mint_data`.
+pub fn build_burn_checked_ix(
+    account: &Pubkey,
+    mint: &Pubkey,
+    mint_data: &MintWithExtensions,
+    owner: &Pubkey,
+    multisig_signers: &[&Pubkey],
+    amount: u64,
+) -> anyhow::Result<Instruction> {
+    Ok(spl_token_2022::instruction::burn_checked(
+        &mint_data.base.token_program().into(),
+        account,
+        mint,
+        owner,
+        multisig_signers,
+        amount,
+        mint_data.base.get_decimals(),
+    )?)
+}
+
+/// Builds an `Approve` letting `delegate` spend `amount` base units of
+/// `account`, against the token program owning the parsed `mint_data`.
+pub fn build_approve_ix(
+    account: &Pubkey,
+    mint_data: &MintWithExtensions,
+    delegate: &Pubkey,
+    owner: &Pubkey,
+    multisig_signers: &[&Pubkey],
+    amount: u64,
+) -> anyhow::Result<Instruction> {
+    Ok(spl_token_2022::instruction::approve(
+        &mint_data.base.token_program().into(),
+        account,
+        delegate,
+        owner,
+        multisig_signers,
+        amount,
+    )?)
+}
+
+/// Same as [`build_approve_ix`], but the instruction also asserts the mint
+/// and decimals of `mint_data`.
+pub fn build_approve_checked_ix(
+    account: &Pubkey,
+    mint: &Pubkey,
+    mint_data: &MintWithExtensions,
+    delegate: &Pubkey,
+    owner: &Pubkey,
+    multisig_signers: &[&Pubkey],
+    amount: u64,
+) -> anyhow::Result<Instruction> {
+    Ok(spl_token_2022::instruction::approve_checked(
+        &mint_data.base.token_program().into(),
+        account,
+        mint,
+        delegate,
+        owner,
+        multisig_signers,
+        amount,
+        mint_data.base.get_decimals(),
+    )?)
+}
+
+/// Builds a `Revoke` of the delegate of `account`.
+pub fn build_revoke_ix(
+    program_id: &Pubkey,
+    account: &Pubkey,
+    owner: &Pubkey,
+    multisig_signers: &[&Pubkey],
+) -> anyhow::Result<Instruction> {
+    Ok(spl_token_2022::instruction::revoke(
+        program_id,
+        account,
+        owner,
+        multisig_signers,
+    )?)
+}
+
+/// Builds a `CloseAccount` sending the rent of `account` to `destination`.
+pub fn build_close_account_ix(
+    program_id: &Pubkey,
+    account: &Pubkey,
+    destination: &Pubkey,
+    owner: &Pubkey,
+    multisig_signers: &[&Pubkey],
+) -> anyhow::Result<Instruction> {
+    Ok(spl_token_2022::instruction::close_account(
+        program_id,
+        account,
+        destination,
+        owner,
+        multisig_signers,
+    )?)
+}
+
+/// Builds a `FreezeAccount` of `account` signed by the freeze authority of
+/// `mint`.
+pub fn build_freeze_ix(
+    program_id: &Pubkey,
+    account: &Pubkey,
+    mint: &Pubkey,
+    freeze_authority: &Pubkey,
+    multisig_signers: &[&Pubkey],
+) -> anyhow::Result<Instruction> {
+    Ok(spl_token_2022::instruction::freeze_account(
+        program_id,
+        account,
+        mint,
+        freeze_authority,
+        multisig_signers,
+    )?)
+}
+
+/// Builds a `ThawAccount` of `account` signed by the freeze authority of
+/// `mint`.
+pub fn build_thaw_ix(
+    program_id: &Pubkey,
+    account: &Pubkey,
+    mint: &Pubkey,
+    freeze_authority: &Pubkey,
+    multisig_signers: &[&Pubkey],
+) -> anyhow::Result<Instruction> {
+    Ok(spl_token_2022::instruction::thaw_account(
+        program_id,
+        account,
+        mint,
+        freeze_authority,
+        multisig_signers,
+    )?)
+}
+
+/// Fails if `token_account` still holds tokens, which `CloseAccount` would
+/// reject on chain. Native accounts hand their wrapped lamports to the
+/// destination on close, so they are exempt.
+fn ensure_closable(
+    account: &Pubkey,
+    token_account: &TokenAccountWithExtensions,
+) -> anyhow::Result<()> {
+    let balance = token_account.base.get_amount();
+    if balance != 0 && !token_account.base.is_native() {
+        return Err(anyhow::anyhow!(
+            "Token account {} still holds {} base units, burn or transfer them before closing",
+            account,
+            balance
+        ));
+    }
+
+    Ok(())
+}
+
+/// Fetches a token account and returns the token program owning it along
+/// with the parsed account.
+async fn fetch_token_account<S: AccountSource + ?Sized>(
+    source: &S,
+    token_account: &Pubkey,
+) -> anyhow::Result<(Pubkey, TokenAccountWithExtensions)> {
+    let acc = source::require_account(source, token_account).await?;
+    let program_id: Pubkey = TokenProgram::try_from(acc.owner)?.into();
+
+    Ok((
+        program_id,
+        TokenAccountWithExtensions::try_parse_token_account_with_extensions(acc)?,
+    ))
+}
+
+async fn fetch_mint<S: AccountSource + ?Sized>(
+    source: &S,
+    mint: &Pubkey,
+) -> anyhow::Result<MintWithExtensions> {
+    let acc = source::require_account(source, mint).await?;
+
+    MintWithExtensions::try_parse_mint_with_extensions(acc, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program_test::{ProgramTest, ProgramTestContext};
+    use solana_sdk::signature::{Keypair, Signer};
+    use spl_associated_token_account::get_associated_token_address_with_program_id;
+    use spl_token_2022::{
+        extension::StateWithExtensionsOwned, solana_program::program_option::COption,
+        state::AccountState,
+    };
+
+    use super::*;
+    use crate::{
+        multisig::PrettyMultisig,
+        test_fixtures::{
+            create_mint, create_multisig, get_account, process, token_account, token_account_native,
+        },
+    };
+
+    async fn token_balance(ctx: &mut ProgramTestContext, pubkey: &Pubkey) -> u64 {
+        let acc = get_account(ctx, pubkey).await;
+        TokenAccountWithExtensions::try_parse_token_account_with_extensions(acc)
+            .unwrap()
+            .base
+            .get_amount()
+    }
+
+    async fn create_and_transfer(program: TokenProgram) {
+        let program_id: Pubkey = program.into();
+        let mut ctx = ProgramTest::default().start_with_context().await;
+        let payer = ctx.payer.pubkey();
+        let mint = create_mint(&mut ctx, &program_id, 6, None).await;
+        let recipient = Keypair::new().pubkey();
+
+        let mint_acc = get_account(&mut ctx, &mint).await;
+        assert_eq!(mint_acc.owner, program_id);
+
+        let source = get_associated_token_address_with_program_id(&payer, &mint, &program_id);
+        let destination =
+            get_associated_token_address_with_program_id(&recipient, &mint, &program_id);
+        let create_ixs = [
+            build_create_ix(&payer, &payer, &mint, &mint_acc).unwrap(),
+            build_create_ix(&payer, &recipient, &mint, &mint_acc).unwrap(),
+            spl_token_2022::instruction::mint_to(
+                &program_id,
+                &mint,
+                &source,
+                &payer,
+                &[],
+                5_000_000,
+            )
+            .unwrap(),
+        ];
+        process(&mut ctx, &create_ixs, &[]).await;
+        assert_eq!(get_account(&mut ctx, &source).await.owner, program_id);
+        assert_eq!(get_account(&mut ctx, &destination).await.owner, program_id);
+
+        let transfer_ix = build_transfer_ix(
+            &source,
+            &mint,
+            &MintWithExtensions::try_parse_mint_with_extensions(mint_acc, None).unwrap(),
+            &destination,
+            &payer,
+            &[],
+            1_500_000,
+        )
+        .unwrap();
+        assert_eq!(transfer_ix.program_id, program_id);
+        process(&mut ctx, &[transfer_ix], &[]).await;
+
+        assert_eq!(token_balance(&mut ctx, &source).await, 3_500_000);
+        assert_eq!(token_balance(&mut ctx, &destination).await, 1_500_000);
+    }
+
+    #[tokio::test]
+    async fn legacy_token_create_and_transfer() {
+        create_and_transfer(TokenProgram::LegacyToken).await;
+    }
+
+    #[tokio::test]
+    async fn token_2022_create_and_transfer() {
+        create_and_transfer(TokenProgram::Token2022).await;
+    }
+
+    async fn base_account(
+        ctx: &mut ProgramTestContext,
+        pubkey: &Pubkey,
+    ) -> spl_token_2022::state::Account {
+        let acc = get_account(ctx, pubkey).await;
+        StateWithExtensionsOwned::<spl_token_2022::state::Account>::unpack(acc.data)
+            .unwrap()
+            .base
+    }
+
+    async fn burn_approve_freeze_and_close(program: TokenProgram) {
+        let program_id: Pubkey = program.into();
+        let mut ctx = ProgramTest::default().start_with_context().await;
+        let payer = ctx.payer.pubkey();
+        let mint = create_mint(&mut ctx, &program_id, 2, Some(&payer)).await;
+        let delegate = Keypair::new().pubkey();
+
+        let mint_acc = get_account(&mut ctx, &mint).await;
+        let mint_data =
+            MintWithExtensions::try_parse_mint_with_extensions(mint_acc.clone(), None).unwrap();
+        let account = get_associated_token_address_with_program_id(&payer, &mint, &program_id);
+        process(
+            &mut ctx,
+            &[
+                build_create_ix(&payer, &payer, &mint, &mint_acc).unwrap(),
+                spl_token_2022::instruction::mint_to(
+                    &program_id,
+                    &mint,
+                    &account,
+                    &payer,
+                    &[],
+                    1_000,
+                )
+                .unwrap(),
+            ],
+            &[],
+        )
+        .await;
+
+        let ix = build_burn_ix(&account, &mint, &mint_data, &payer, &[], 100).unwrap();
+        assert_eq!(ix.program_id, program_id);
+        process(&mut ctx, &[ix], &[]).await;
+        assert_eq!(token_balance(&mut ctx, &account).await, 900);
+
+        let ix = build_burn_checked_ix(&account, &mint, &mint_data, &payer, &[], 100).unwrap();
+        process(&mut ctx, &[ix], &[]).await;
+        assert_eq!(token_balance(&mut ctx, &account).await, 800);
+
+        let ix = build_approve_ix(&account, &mint_data, &delegate, &payer, &[], 300).unwrap();
+        process(&mut ctx, &[ix], &[]).await;
+        let base = base_account(&mut ctx, &account).await;
+        assert_eq!(
+            (base.delegate, base.delegated_amount),
+            (COption::Some(delegate), 300)
+        );
+
+        let ix = build_approve_checked_ix(&account, &mint, &mint_data, &delegate, &payer, &[], 200)
+            .unwrap();
+        process(&mut ctx, &[ix], &[]).await;
+        assert_eq!(base_account(&mut ctx, &account).await.delegated_amount, 200);
+
+        let ix = build_revoke_ix(&program_id, &account, &payer, &[]).unwrap();
+        process(&mut ctx, &[ix], &[]).await;
+        let base = base_account(&mut ctx, &account).await;
+        assert_eq!((base.delegate, base.delegated_amount), (COption::None, 0));
+
+        let ix = build_freeze_ix(&program_id, &account, &mint, &payer, &[]).unwrap();
+        process(&mut ctx, &[ix], &[]).await;
+        assert_eq!(
+            base_account(&mut ctx, &account).await.state,
+            AccountState::Frozen
+        );
+
+        let ix = build_thaw_ix(&program_id, &account, &mint, &payer, &[]).unwrap();
+        process(&mut ctx, &[ix], &[]).await;
+        assert_eq!(
+            base_account(&mut ctx, &account).await.state,
+            AccountState::Initialized
+        );
+
+        process(
+            &mut ctx,
+            &[
+                build_burn_ix(&account, &mint, &mint_data, &payer, &[], 800).unwrap(),
+                build_close_account_ix(&program_id, &account, &payer, &payer, &[]).unwrap(),
+            ],
+            &[],
+        )
+        .await;
+        assert!(ctx
+            .banks_client
+            .get_account(account)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn legacy_token_burn_approve_freeze_and_close() {
+        burn_approve_freeze_and_close(TokenProgram::LegacyToken).await;
+    }
+
+    #[tokio::test]
+    async fn token_2022_burn_approve_freeze_and_close() {
+        burn_approve_freeze_and_close(TokenProgram::Token2022).await;
+    }
+
+    #[test]
+    fn close_rejects_a_non_zero_balance() {
+        let account = Pubkey::new_unique();
+        let parse = |acc| TokenAccountWithExtensions::try_parse_token_account_with_extensions(acc);
+
+        let empty = parse(token_account(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            0,
+        ))
+        .unwrap();
+        assert!(ensure_closable(&account, &empty).is_ok());
+
+        let funded = parse(token_account(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            5,
+        ))
+        .unwrap();
+        let err = ensure_closable(&account, &funded).unwrap_err();
+        assert!(err.to_string().contains("still holds 5 base units"));
+
+        // Wrapped SOL goes back to the destination along with the rent.
+        let native = parse(token_account_native(&Pubkey::new_unique(), 5)).unwrap();
+        assert!(ensure_closable(&account, &native).is_ok());
+    }
+
+    #[tokio::test]
+    async fn multisig_owner_transfer() {
+        let program_id = spl_token_2022::ID;
+        let mut ctx = ProgramTest::default().start_with_context().await;
+        let payer = ctx.payer.pubkey();
+        let signers = [Keypair::new(), Keypair::new(), Keypair::new()];
+        let signer_pubkeys: Vec<Pubkey> = signers.iter().map(|s| s.pubkey()).collect();
+        let multisig = create_multisig(&mut ctx, &program_id, 2, &signer_pubkeys).await;
+        let mint = create_mint(&mut ctx, &program_id, 0, None).await;
+
+        let multisig_acc = get_account(&mut ctx, &multisig).await;
+        let parsed = PrettyMultisig::try_parse(Some(&multisig), &multisig_acc).unwrap();
+        assert_eq!((parsed.m, parsed.n), (2, 3));
+        assert_eq!(parsed.signers[2], signer_pubkeys[2].to_string());
+
+        let mint_acc = get_account(&mut ctx, &mint).await;
+        let source = get_associated_token_address_with_program_id(&multisig, &mint, &program_id);
+        let destination = get_associated_token_address_with_program_id(&payer, &mint, &program_id);
+        process(
+            &mut ctx,
+            &[
+                build_create_ix(&payer, &multisig, &mint, &mint_acc).unwrap(),
+                build_create_ix(&payer, &payer, &mint, &mint_acc).unwrap(),
+                spl_token_2022::instruction::mint_to(&program_id, &mint, &source, &payer, &[], 10)
+                    .unwrap(),
+            ],
+            &[],
+        )
+        .await;
+
+        let transfer_ix = build_transfer_ix(
+            &source,
+            &mint,
+            &MintWithExtensions::try_parse_mint_with_extensions(mint_acc, None).unwrap(),
+            &destination,
+            &multisig,
+            &[&signer_pubkeys[0], &signer_pubkeys[2]],
+            4,
+        )
+        .unwrap();
+        process(&mut ctx, &[transfer_ix], &[&signers[0], &signers[2]]).await;
+
+        assert_eq!(token_balance(&mut ctx, &source).await, 6);
+        assert_eq!(token_balance(&mut ctx, &destination).await, 4);
+    }
+
+    #[test]
+    fn rejects_mint_owned_by_unknown_program() {
+        let mint_acc = Account {
+            owner: Pubkey::new_unique(),
+            ..Account::default()
+        };
+
+        assert!(build_create_ix(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &mint_acc
+        )
+        .is_err());
+    }
+}
@@ -1,71 +1,126 @@
+/// Token account parsing and printing.
 pub mod account;
+/// Token account instructions.
 pub mod ixs;
 
-use std::str::FromStr;
-
 pub use account::*;
 use clap::Subcommand;
-use inquire::Select;
 pub use ixs::*;
-use solana_client::nonblocking::rpc_client::RpcClient;
+use serde_json::json;
+use solana_account::Account;
 use spl_token::solana_program::pubkey::Pubkey;
 
 use crate::{
-    cli::SolanaRpcArgs,
-    mint::{MintWithExtensions, MintWithPubkey, PrettyMint, TokenMetadata},
+    config::Config,
+    input,
+    mint::{pretty_mint, MintWithExtensions, PrettyMint, TokenMetadata},
+    output::{self, OutputFormat},
+    source::AccountSource,
+    utils,
 };
 
+/// Fetch and send instructions to token accounts
 #[derive(Debug, Subcommand)]
 pub enum TokenAccountCommands {
+    /// Fetch one or more token accounts along with their mints
     Fetch(FetchTokenAccount),
-    Ix(SolanaRpcArgs),
+    /// Send a single token account instruction
+    Ix(Box<TokenAccountIxArgs>),
 }
 
-impl TokenAccountCommands {
-    pub async fn process(&self) -> anyhow::Result<()> {
-        match self {
-            TokenAccountCommands::Fetch(f) => {
-                let (token_account, mint) = f.process_fetch().await?;
+/// Builds the printable token account together with its mint, fetching the
+/// mint's Metaplex metadata and the cluster time for accrued interest from
+/// `source` when it has them.
+pub async fn pretty_token_account<S: AccountSource + ?Sized>(
+    source: &S,
+    account_pubkey: Option<&Pubkey>,
+    token_account: Account,
+    mint: Account,
+) -> anyhow::Result<(PrettyMint, PrettyTokenAccount)> {
+    let token_acc_data =
+        TokenAccountWithExtensions::try_parse_token_account_with_extensions(token_account)?;
 
-                let token_acc_data =
-                    TokenAccountWithExtensions::try_parse_token_account_with_extensions(
-                        token_account,
-                    )?;
+    let mint_pubkey = token_acc_data.base.get_mint();
+    let token_metadata = TokenMetadata::fetch_and_parse(mint_pubkey, source).await;
 
-                let token_account: PrettyTokenAccount = TokenAccountWithPubkey {
-                    token_account: token_acc_data,
-                    pubkey: f.account_pubkey.to_string(),
-                }
-                .into();
+    let mint_acc_data = MintWithExtensions::try_parse_mint_with_extensions(mint, token_metadata)?;
+    let mint = pretty_mint(source, Some(&mint_pubkey), mint_acc_data).await?;
+    let token_account = pretty_token_account_with_mint(token_acc_data, account_pubkey, &mint);
+
+    Ok((mint, token_account))
+}
+
+/// Builds the printable token account from its parsed data and its already
+/// built mint.
+pub fn pretty_token_account_with_mint(
+    token_account: TokenAccountWithExtensions,
+    account_pubkey: Option<&Pubkey>,
+    mint: &PrettyMint,
+) -> PrettyTokenAccount {
+    let mut token_account: PrettyTokenAccount = TokenAccountWithPubkey {
+        token_account,
+        pubkey: input::display_pubkey(account_pubkey),
+        decimals: mint.decimals,
+    }
+    .into();
+    if mint.interest_bearing_config().is_some() {
+        token_account.interest_accrued_amount =
+            mint.interest_accrued_ui_amount(token_account.amount);
+    }
 
-                let token_metadata = TokenMetadata::fetch_and_parse(
-                    Pubkey::from_str(&token_account.mint)?,
-                    &RpcClient::new(f.solana.solana_rpc_url.clone()),
-                )
-                .await;
+    token_account
+}
 
-                let mint_acc_data =
-                    MintWithExtensions::try_parse_mint_with_extensions(mint, token_metadata)?;
+impl TokenAccountCommands {
+    /// Runs the subcommand and prints its result.
+    pub async fn process(&self, config: &Config) -> anyhow::Result<()> {
+        let output = config.output;
+        match self {
+            TokenAccountCommands::Fetch(f) if f.is_batch() => {
+                let spinner = utils::get_spinner("Fetching token accounts...")?;
+                let token_accounts = f.fetch_batch(&config.rpc_client()?).await?;
+                spinner.finish_and_clear();
 
-                let mint: PrettyMint = MintWithPubkey {
-                    mint_data: mint_acc_data,
-                    pubkey: token_account.mint.to_string(),
+                match output {
+                    OutputFormat::Table => print_token_accounts(&token_accounts),
+                    _ => output::print_json(
+                        &output::batch_to_json(&token_accounts, |(mint, token_account)| {
+                            json!({
+                                "mint": mint.to_json(),
+                                "tokenAccount": token_account.to_json(),
+                            })
+                        }),
+                        output,
+                    )?,
                 }
-                .into();
+            }
+            TokenAccountCommands::Fetch(f) => {
+                let (mint, token_account) = f.process_fetch(config).await?;
 
-                mint.print();
-                token_account.print();
+                match output {
+                    OutputFormat::Table => {
+                        mint.print();
+                        token_account.print();
+                    }
+                    _ => output::print_json(
+                        &json!({
+                            "mint": mint.to_json(),
+                            "tokenAccount": token_account.to_json(),
+                        }),
+                        output,
+                    )?,
+                }
             }
-            TokenAccountCommands::Ix(rpc) => {
-                let ix = TokenAccountInstructions::from_select_str(
-                    Select::new(
-                        "Select an instruction to execute",
-                        TokenAccountInstructions::to_select_vec(),
-                    )
-                    .prompt()?,
+            TokenAccountCommands::Ix(args) => {
+                let ix = utils::select_or_prompt(
+                    args.ix.clone(),
+                    "<instruction>",
+                    "Select an instruction to execute",
+                    TokenAccountInstructions::to_select_vec(),
+                    TokenAccountInstructions::from_select_str,
                 )?;
 
-                ix.process_ix(rpc).await?;
+                ix.process_ix(config).await?;
             }
         }
 
@@ -0,0 +1,50 @@
+use clap::ValueEnum;
+use spl_token::solana_program::pubkey::Pubkey;
+
+/// One of the two SPL token programs.
+#[derive(Debug, Clone, ValueEnum)]
+pub enum TokenProgram {
+    /// The Token-2022 program, with extensions
+    Token2022,
+    /// The original Token program
+    LegacyToken,
+}
+
+impl From<TokenProgram> for Pubkey {
+    fn from(token_program: TokenProgram) -> Self {
+        match token_program {
+            TokenProgram::Token2022 => spl_token_2022::ID,
+            TokenProgram::LegacyToken => spl_token::ID,
+        }
+    }
+}
+
+impl TryFrom<Pubkey> for TokenProgram {
+    type Error = anyhow::Error;
+    fn try_from(pubkey: Pubkey) -> anyhow::Result<Self> {
+        match pubkey {
+            spl_token::ID => Ok(TokenProgram::LegacyToken),
+            spl_token_2022::ID => Ok(TokenProgram::Token2022),
+            _ => Err(anyhow::anyhow!(
+                "Account is owned by {}, which is not a token program, try `surfer account`",
+                pubkey
+            )),
+        }
+    }
+}
+
+impl TokenProgram {
+    /// Names of the programs, for interactive prompts.
+    pub fn _to_select_vec() -> Vec<&'static str> {
+        vec!["Token2022", "LegacyToken"]
+    }
+
+    /// Parses a name returned by [`TokenProgram::_to_select_vec`].
+    pub fn _from_select_str(select_str: &str) -> anyhow::Result<Self> {
+        match select_str {
+            "Token2022" => Ok(TokenProgram::Token2022),
+            "LegacyToken" => Ok(TokenProgram::LegacyToken),
+            _ => Err(anyhow::anyhow!("Invalid token program: {}", select_str)),
+        }
+    }
+}
@@ -0,0 +1,1453 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use clap::{Args, Subcommand};
+use prettytable::{Row, Table};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use solana_account::Account;
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::{
+    nonblocking::rpc_client::RpcClient,
+    rpc_config::{RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig},
+};
+use solana_sdk::{
+    borsh1::try_from_slice_unchecked,
+    commitment_config::CommitmentConfig,
+    compute_budget::{self, ComputeBudgetInstruction},
+    hash::Hash,
+    instruction::{AccountMeta, Instruction},
+    message::Message,
+    program_utils::limited_deserialize,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::Signature,
+    signer::{Signer, SignerError},
+    system_instruction::SystemInstruction,
+    system_program,
+    transaction::Transaction,
+};
+
+use crate::{
+    config::Config,
+    mint::MintWithExtensions,
+    output::{self, OutputFormat, UiAmount},
+    signer::{default_signer_source, SignerArgs, SignerSource},
+    source::AccountSource,
+    token_account::TokenAccountWithExtensions,
+    utils,
+};
+
+/// Upper bound on compute units for a single transaction.
+const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// The signer and transaction flags, flattened into the commands that send
+/// a transaction.
+#[derive(Debug, Clone, Default, Args)]
+pub struct SendArgs {
+    /// Who signs and pays for the transaction.
+    #[clap(flatten)]
+    pub signer: SignerArgs,
+
+    /// How the transaction is built and whether it is sent.
+    #[clap(flatten)]
+    pub transaction: TransactionArgs,
+}
+
+impl SendArgs {
+    /// Fails if any flag outside `supported` was given, for commands that
+    /// only take some of them.
+    fn reject_unsupported(&self, command: &str, supported: &[&str]) -> anyhow::Result<()> {
+        let (signer, tx) = (&self.signer, &self.transaction);
+        let given = [
+            ("--keypair", signer.keypair.is_some()),
+            ("--fee-payer", signer.fee_payer.is_some()),
+            ("--nonce-authority", signer.nonce_authority.is_some()),
+            ("--multisig-signer", !signer.multisig_signers.is_empty()),
+            ("--signer", !signer.presigners.is_empty()),
+            ("--dry-run", tx.dry_run),
+            ("--sign-only", tx.sign_only),
+            ("--blockhash", tx.blockhash.is_some()),
+            ("--nonce", tx.nonce.is_some()),
+            ("--tx-file", tx.tx_file.is_some()),
+            ("--priority-fee", tx.priority_fee.is_some()),
+            ("--compute-unit-limit", tx.compute_unit_limit.is_some()),
+        ];
+        match given
+            .iter()
+            .find(|(flag, given)| *given && !supported.contains(flag))
+        {
+            Some((flag, _)) => Err(anyhow::anyhow!("`{}` does not take {}", command, flag)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Flags controlling how a transaction is built, and whether it is sent,
+/// simulated or only signed.
+#[derive(Debug, Clone, Default, Args)]
+pub struct TransactionArgs {
+    /// Simulate the transaction and print the result instead of sending it
+    #[clap(long, global = true, conflicts_with = "sign_only")]
+    pub dry_run: bool,
+
+    /// Sign with the available signers and print the signatures instead of sending
+    #[clap(long, global = true)]
+    pub sign_only: bool,
+
+    /// Blockhash to build the transaction with instead of fetching the latest one
+    #[clap(long, global = true, value_parser = parse_blockhash)]
+    pub blockhash: Option<Hash>,
+
+    /// Durable nonce account whose stored blockhash is used instead of a recent one
+    #[clap(long, global = true, value_parser = utils::parse_pubkey)]
+    pub nonce: Option<Pubkey>,
+
+    /// Write the --sign-only transaction to this file, as JSON if it ends in `.json` and base64 otherwise
+    #[clap(long, global = true)]
+    pub tx_file: Option<PathBuf>,
+
+    /// Compute unit price in micro-lamports, or `auto` to derive it from recent prioritization fees
+    #[clap(long, global = true, value_parser = PriorityFee::from_str)]
+    pub priority_fee: Option<PriorityFee>,
+
+    /// Compute unit limit, or `auto` to size it from a simulation
+    #[clap(long, global = true, value_parser = ComputeUnitLimit::from_str)]
+    pub compute_unit_limit: Option<ComputeUnitLimit>,
+}
+
+impl TransactionArgs {
+    /// Whether signatures may be missing: `--sign-only` collects them later
+    /// and `--dry-run` simulates without verifying them.
+    pub fn allows_missing_signatures(&self) -> bool {
+        self.sign_only || self.dry_run
+    }
+}
+
+/// The compute unit price given with `--priority-fee`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PriorityFee {
+    /// Derived from the recent prioritization fees of the written accounts.
+    Auto,
+    /// A fixed price in micro-lamports per compute unit.
+    MicroLamports(u64),
+}
+
+impl FromStr for PriorityFee {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "auto" => Ok(PriorityFee::Auto),
+            s => s.parse().map(PriorityFee::MicroLamports).map_err(|_| {
+                anyhow::anyhow!(
+                    "Invalid priority fee `{}`, expected micro-lamports or auto",
+                    s
+                )
+            }),
+        }
+    }
+}
+
+/// The compute unit limit given with `--compute-unit-limit`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ComputeUnitLimit {
+    /// Sized from the units a simulation consumed.
+    Auto,
+    /// A fixed number of compute units.
+    Units(u32),
+}
+
+impl FromStr for ComputeUnitLimit {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let limit = match s {
+            "auto" => return Ok(ComputeUnitLimit::Auto),
+            s => s.parse::<u32>().map_err(|_| {
+                anyhow::anyhow!(
+                    "Invalid compute unit limit `{}`, expected a number of units or auto",
+                    s
+                )
+            })?,
+        };
+        if limit > MAX_COMPUTE_UNIT_LIMIT {
+            return Err(anyhow::anyhow!(
+                "Compute unit limit {} exceeds the maximum of {}",
+                limit,
+                MAX_COMPUTE_UNIT_LIMIT
+            ));
+        }
+
+        Ok(ComputeUnitLimit::Units(limit))
+    }
+}
+
+/// The rent-exempt minimum for `space` bytes. Fully offline signing can't ask
+/// the cluster, so it assumes the default rent parameters, which all public
+/// clusters use.
+pub async fn minimum_balance(config: &Config, space: usize) -> anyhow::Result<u64> {
+    if config.transaction.sign_only && config.transaction.blockhash.is_some() {
+        return Ok(Rent::default().minimum_balance(space));
+    }
+
+    let spinner = utils::get_spinner("Fetching rent...")?;
+    let lamports = config
+        .rpc_client()?
+        .get_minimum_balance_for_rent_exemption(space)
+        .await?;
+    spinner.finish_and_clear();
+
+    Ok(lamports)
+}
+
+fn parse_blockhash(blockhash: &str) -> anyhow::Result<Hash> {
+    Hash::from_str(blockhash)
+        .map_err(|e| anyhow::anyhow!("Invalid blockhash `{}`: {}", blockhash, e))
+}
+
+/// Prepends the ComputeBudget instructions requested by `--compute-unit-limit`
+/// and `--priority-fee` to `ixs`, after a leading nonce advance if any.
+/// Leaves `ixs` untouched if neither is set.
+pub async fn with_compute_budget(
+    config: &Config,
+    ixs: Vec<Instruction>,
+    payer: &Pubkey,
+) -> anyhow::Result<Vec<Instruction>> {
+    let args = &config.transaction;
+    let limit = match args.compute_unit_limit {
+        Some(ComputeUnitLimit::Units(units)) => Some(units),
+        Some(ComputeUnitLimit::Auto) => {
+            Some(estimate_compute_units(&config.rpc_client()?, &ixs, payer).await?)
+        }
+        None => None,
+    };
+    let price = match args.priority_fee {
+        Some(PriorityFee::MicroLamports(price)) => Some(price),
+        Some(PriorityFee::Auto) => Some(estimate_priority_fee(&config.rpc_client()?, &ixs).await?),
+        None => None,
+    };
+
+    let mut budget_ixs = Vec::new();
+    if let Some(limit) = limit {
+        budget_ixs.push(ComputeBudgetInstruction::set_compute_unit_limit(limit));
+    }
+    if let Some(price) = price {
+        budget_ixs.push(ComputeBudgetInstruction::set_compute_unit_price(price));
+    }
+    // A durable nonce advance must stay the first instruction.
+    let mut ixs = ixs;
+    let rest = ixs.split_off(usize::from(ixs.first().is_some_and(is_advance_nonce)));
+    ixs.extend(budget_ixs);
+    ixs.extend(rest);
+
+    Ok(ixs)
+}
+
+fn is_advance_nonce(ix: &Instruction) -> bool {
+    ix.program_id == system_program::id()
+        && matches!(
+            limited_deserialize::<SystemInstruction>(&ix.data),
+            Ok(SystemInstruction::AdvanceNonceAccount)
+        )
+}
+
+/// Simulates `ixs` with the maximum limit, plus the budget instructions
+/// themselves, and adds a 10% margin to the units consumed.
+async fn estimate_compute_units(
+    rpc_client: &RpcClient,
+    ixs: &[Instruction],
+    payer: &Pubkey,
+) -> anyhow::Result<u32> {
+    let mut sim_ixs = vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(MAX_COMPUTE_UNIT_LIMIT),
+        ComputeBudgetInstruction::set_compute_unit_price(0),
+    ];
+    sim_ixs.extend_from_slice(ixs);
+    let tx = Transaction::new_with_payer(&sim_ixs, Some(payer));
+
+    let result = rpc_client
+        .simulate_transaction_with_config(
+            &tx,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                commitment: Some(rpc_client.commitment()),
+                ..RpcSimulateTransactionConfig::default()
+            },
+        )
+        .await?
+        .value;
+    if let Some(err) = result.err {
+        return Err(anyhow::anyhow!(
+            "Simulation for the compute unit limit failed: {}\n{}",
+            err,
+            result.logs.unwrap_or_default().join("\n")
+        ));
+    }
+    let units = result
+        .units_consumed
+        .ok_or_else(|| anyhow::anyhow!("Simulation did not report the compute units consumed"))?;
+
+    Ok((units.saturating_mul(11) / 10).min(MAX_COMPUTE_UNIT_LIMIT as u64) as u32)
+}
+
+/// Returns the median of the recent prioritization fees paid by transactions
+/// locking the writable accounts of `ixs`.
+async fn estimate_priority_fee(rpc_client: &RpcClient, ixs: &[Instruction]) -> anyhow::Result<u64> {
+    let mut fees: Vec<u64> = rpc_client
+        .get_recent_prioritization_fees(&writable_accounts(ixs))
+        .await?
+        .iter()
+        .map(|fee| fee.prioritization_fee)
+        .collect();
+    if fees.is_empty() {
+        return Ok(0);
+    }
+    fees.sort_unstable();
+
+    Ok(fees[fees.len() / 2])
+}
+
+/// The writable accounts of `ixs` in order of first use.
+fn writable_accounts(ixs: &[Instruction]) -> Vec<Pubkey> {
+    let mut accounts: Vec<Pubkey> = Vec::new();
+    for meta in ixs.iter().flat_map(|ix| &ix.accounts) {
+        if meta.is_writable && !accounts.contains(&meta.pubkey) {
+            accounts.push(meta.pubkey);
+        }
+    }
+
+    accounts
+}
+
+/// The balance of a token account before and after a simulated transaction.
+struct TokenBalanceChange {
+    account: Pubkey,
+    mint: Pubkey,
+    decimals: u8,
+    pre: u64,
+    post: u64,
+}
+
+impl TokenBalanceChange {
+    fn change(&self) -> String {
+        let (sign, diff) = if self.post >= self.pre {
+            ("+", self.post - self.pre)
+        } else {
+            ("-", self.pre - self.post)
+        };
+
+        format!(
+            "{}{}",
+            sign,
+            UiAmount::new(diff, self.decimals).ui_amount_string
+        )
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "account": self.account.to_string(),
+            "mint": self.mint.to_string(),
+            "preBalance": UiAmount::new(self.pre, self.decimals),
+            "postBalance": UiAmount::new(self.post, self.decimals),
+        })
+    }
+}
+
+/// The outcome of a simulated transaction.
+pub struct SimulationReport {
+    err: Option<String>,
+    units_consumed: Option<u64>,
+    logs: Vec<String>,
+    ixs: Vec<Instruction>,
+    token_balances: Vec<TokenBalanceChange>,
+}
+
+/// Simulates a signed or partially signed transaction without submitting it.
+pub async fn simulate(
+    rpc_client: &RpcClient,
+    tx: &Transaction,
+    ixs: &[Instruction],
+    commitment: CommitmentConfig,
+) -> anyhow::Result<SimulationReport> {
+    let writable_accounts = writable_accounts(ixs);
+    let pre_accounts = rpc_client
+        .get_multiple_accounts_with_commitment(&writable_accounts, commitment)
+        .await?
+        .value;
+    let result = rpc_client
+        .simulate_transaction_with_config(
+            tx,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                commitment: Some(commitment),
+                accounts: Some(RpcSimulateTransactionAccountsConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    addresses: writable_accounts.iter().map(|p| p.to_string()).collect(),
+                }),
+                ..RpcSimulateTransactionConfig::default()
+            },
+        )
+        .await?
+        .value;
+    let post_accounts: Vec<Option<Account>> = match result.accounts {
+        Some(accounts) => accounts
+            .iter()
+            .map(|acc| acc.as_ref().and_then(|acc| acc.decode()))
+            .collect(),
+        None => vec![None; writable_accounts.len()],
+    };
+
+    Ok(SimulationReport {
+        err: result.err.map(|err| err.to_string()),
+        units_consumed: result.units_consumed,
+        logs: result.logs.unwrap_or_default(),
+        ixs: ixs.to_vec(),
+        token_balances: token_balance_changes(
+            rpc_client,
+            &writable_accounts,
+            &pre_accounts,
+            &post_accounts,
+        )
+        .await?,
+    })
+}
+
+impl SimulationReport {
+    /// The report as the JSON document printed by `--dry-run`.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "err": self.err,
+            "unitsConsumed": self.units_consumed,
+            "logs": self.logs,
+            "instructions": self.ixs.iter().map(instruction_to_json).collect::<Vec<_>>(),
+            "tokenBalances": self.token_balances.iter().map(|b| b.to_json()).collect::<Vec<_>>(),
+        })
+    }
+
+    /// Prints the report as a table.
+    pub fn print(&self) {
+        let mut table = Table::new();
+        table.add_row(Row::new(vec![utils::to_header_cell("Simulation")]));
+        table.add_row(Row::new(vec![
+            utils::to_key_cell("Status"),
+            utils::to_value_cell(
+                &self
+                    .err
+                    .as_ref()
+                    .map_or("Success".to_string(), |err| format!("Failed: {}", err)),
+            ),
+        ]));
+        table.add_row(Row::new(vec![
+            utils::to_key_cell("Compute Units"),
+            utils::to_value_cell(
+                &self
+                    .units_consumed
+                    .map_or("Unknown".to_string(), |units| units.to_string()),
+            ),
+        ]));
+        table.printstd();
+
+        let mut table = Table::new();
+        table.add_row(Row::new(vec![utils::to_header_cell("Instructions")]));
+        for (i, ix) in self.ixs.iter().enumerate() {
+            let (program, instruction) = decode_instruction(ix);
+            let accounts = ix
+                .accounts
+                .iter()
+                .map(|meta| {
+                    let mut flags = Vec::new();
+                    if meta.is_signer {
+                        flags.push("signer");
+                    }
+                    if meta.is_writable {
+                        flags.push("writable");
+                    }
+                    match flags.is_empty() {
+                        true => meta.pubkey.to_string(),
+                        false => format!("{} ({})", meta.pubkey, flags.join(", ")),
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            table.add_row(Row::new(vec![
+                utils::to_key_cell(&format!("#{} {}", i + 1, program)),
+                utils::to_value_cell(&format!("{}\n{}", instruction, accounts)),
+            ]));
+        }
+        table.printstd();
+
+        if !self.token_balances.is_empty() {
+            let mut table = Table::new();
+            table.add_row(Row::new(vec![utils::to_header_cell("Token Balances")]));
+            table.add_row(Row::new(vec![
+                utils::to_key_cell("Account"),
+                utils::to_key_cell("Mint"),
+                utils::to_key_cell("Before"),
+                utils::to_key_cell("After"),
+                utils::to_key_cell("Change"),
+            ]));
+            for balance in &self.token_balances {
+                table.add_row(Row::new(vec![
+                    utils::to_value_cell(&balance.account.to_string()),
+                    utils::to_value_cell(&balance.mint.to_string()),
+                    utils::to_value_cell(
+                        &UiAmount::new(balance.pre, balance.decimals).ui_amount_string,
+                    ),
+                    utils::to_value_cell(
+                        &UiAmount::new(balance.post, balance.decimals).ui_amount_string,
+                    ),
+                    utils::to_value_cell(&balance.change()),
+                ]));
+            }
+            table.printstd();
+        }
+
+        let mut table = Table::new();
+        table.add_row(Row::new(vec![utils::to_header_cell("Logs")]));
+        for log in &self.logs {
+            table.add_row(Row::new(vec![utils::to_value_cell(log)]));
+        }
+        table.printstd();
+    }
+}
+
+/// Pairs the pre and post state of every writable token account. Mint
+/// decimals come from the simulated state first, so mints created by the
+/// transaction itself resolve too, and from `source` otherwise.
+async fn token_balance_changes<S: AccountSource + ?Sized>(
+    source: &S,
+    addresses: &[Pubkey],
+    pre_accounts: &[Option<Account>],
+    post_accounts: &[Option<Account>],
+) -> anyhow::Result<Vec<TokenBalanceChange>> {
+    let parse_token_account = |acc: &Option<Account>| {
+        acc.clone().and_then(|acc| {
+            TokenAccountWithExtensions::try_parse_token_account_with_extensions(acc)
+                .ok()
+                .map(|token_account| {
+                    (
+                        token_account.base.get_mint(),
+                        token_account.base.get_amount(),
+                    )
+                })
+        })
+    };
+
+    let mut decimals: HashMap<Pubkey, u8> = HashMap::new();
+    for (address, acc) in addresses.iter().zip(post_accounts) {
+        if let Some(mint) = acc
+            .clone()
+            .and_then(|acc| MintWithExtensions::try_parse_mint_with_extensions(acc, None).ok())
+        {
+            decimals.insert(*address, mint.base.get_decimals());
+        }
+    }
+
+    let mut balances = Vec::new();
+    for ((address, pre), post) in addresses.iter().zip(pre_accounts).zip(post_accounts) {
+        let (pre, post) = (parse_token_account(pre), parse_token_account(post));
+        let Some(mint) = pre.or(post).map(|(mint, _)| mint) else {
+            continue;
+        };
+
+        balances.push(TokenBalanceChange {
+            account: *address,
+            mint,
+            decimals: 0,
+            pre: pre.map_or(0, |(_, amount)| amount),
+            post: post.map_or(0, |(_, amount)| amount),
+        });
+    }
+
+    let missing_mints: Vec<Pubkey> = balances
+        .iter()
+        .map(|b| b.mint)
+        .filter(|mint| !decimals.contains_key(mint))
+        .collect();
+    if !missing_mints.is_empty() {
+        let mint_accounts = source.get_multiple_accounts(&missing_mints).await?;
+        for (mint, acc) in missing_mints.iter().zip(mint_accounts) {
+            if let Some(mint_acc) = acc
+                .and_then(|acc| MintWithExtensions::try_parse_mint_with_extensions(acc, None).ok())
+            {
+                decimals.insert(*mint, mint_acc.base.get_decimals());
+            }
+        }
+    }
+
+    for balance in &mut balances {
+        balance.decimals = decimals.get(&balance.mint).copied().unwrap_or_default();
+    }
+
+    Ok(balances)
+}
+
+/// Returns a readable program name and instruction for the programs surfer
+/// builds instructions for.
+pub fn decode_instruction(ix: &Instruction) -> (String, String) {
+    let unknown = || "Unknown instruction".to_string();
+
+    match ix.program_id {
+        system_program::ID => (
+            "System Program".to_string(),
+            limited_deserialize::<SystemInstruction>(&ix.data)
+                .map_or_else(|_| unknown(), |ix| format!("{:?}", ix)),
+        ),
+        spl_token::ID => (
+            "Token Program".to_string(),
+            spl_token::instruction::TokenInstruction::unpack(&ix.data)
+                .map_or_else(|_| unknown(), |ix| format!("{:?}", ix)),
+        ),
+        spl_token_2022::ID => (
+            "Token-2022 Program".to_string(),
+            spl_token_2022::instruction::TokenInstruction::unpack(&ix.data)
+                .map_or_else(|_| unknown(), |ix| format!("{:?}", ix)),
+        ),
+        compute_budget::ID => (
+            "Compute Budget Program".to_string(),
+            try_from_slice_unchecked::<ComputeBudgetInstruction>(&ix.data)
+                .map_or_else(|_| unknown(), |ix| format!("{:?}", ix)),
+        ),
+        spl_associated_token_account::ID => (
+            "Associated Token Account Program".to_string(),
+            match ix.data.first() {
+                None | Some(0) => "Create".to_string(),
+                Some(1) => "CreateIdempotent".to_string(),
+                Some(2) => "RecoverNested".to_string(),
+                Some(_) => unknown(),
+            },
+        ),
+        program_id => (program_id.to_string(), unknown()),
+    }
+}
+
+fn instruction_to_json(ix: &Instruction) -> Value {
+    let (program, instruction) = decode_instruction(ix);
+
+    json!({
+        "programId": ix.program_id.to_string(),
+        "program": program,
+        "instruction": instruction,
+        "accounts": ix.accounts.iter().map(|meta| json!({
+            "pubkey": meta.pubkey.to_string(),
+            "isSigner": meta.is_signer,
+            "isWritable": meta.is_writable,
+        })).collect::<Vec<_>>(),
+    })
+}
+
+/// A possibly partially signed transaction as printed by `--sign-only` and
+/// stored in `.json` transaction files.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncodedTransaction {
+    /// The recent blockhash or nonce the transaction was built with.
+    pub blockhash: String,
+    /// Signatures present, as `<PUBKEY>=<SIGNATURE>`.
+    pub signers: Vec<String>,
+    /// Pubkeys of the signers that still have to sign.
+    pub absent_signers: Vec<String>,
+    /// The bincode serialized transaction, base64 encoded.
+    pub transaction: String,
+}
+
+impl EncodedTransaction {
+    /// Encodes `tx`, splitting its signers into those that signed and those
+    /// that haven't.
+    pub fn new(tx: &Transaction) -> anyhow::Result<Self> {
+        let mut signers = Vec::new();
+        let mut absent_signers = Vec::new();
+        for (pubkey, signature) in tx.message.account_keys.iter().zip(&tx.signatures) {
+            if *signature == Signature::default() {
+                absent_signers.push(pubkey.to_string());
+            } else {
+                signers.push(format!("{}={}", pubkey, signature));
+            }
+        }
+
+        Ok(EncodedTransaction {
+            blockhash: tx.message.recent_blockhash.to_string(),
+            signers,
+            absent_signers,
+            transaction: BASE64_STANDARD.encode(bincode::serialize(tx)?),
+        })
+    }
+}
+
+/// Prints the signature of a sent transaction.
+pub fn print_signature(sig: &Signature, output: OutputFormat) -> anyhow::Result<()> {
+    match output {
+        OutputFormat::Table => println!("Transaction signature: {}", sig),
+        _ => output::print_json(&json!({ "signature": sig.to_string() }), output)?,
+    }
+
+    Ok(())
+}
+
+/// Prints a `--sign-only` transaction: its blockhash, the signatures present
+/// and the signers still missing, along with the encoded transaction.
+pub fn print_signed_transaction(tx: &Transaction, output: OutputFormat) -> anyhow::Result<()> {
+    let encoded = EncodedTransaction::new(tx)?;
+    if output != OutputFormat::Table {
+        return output::print_json(&encoded, output);
+    }
+
+    let none_or_lines = |lines: &[String]| match lines.is_empty() {
+        true => "None".to_string(),
+        false => lines.join("\n"),
+    };
+
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![utils::to_header_cell("Signed Transaction")]));
+    table.add_row(Row::new(vec![
+        utils::to_key_cell("Blockhash"),
+        utils::to_value_cell(&encoded.blockhash),
+    ]));
+    table.add_row(Row::new(vec![
+        utils::to_key_cell("Signers (Pubkey=Signature)"),
+        utils::to_value_cell(&none_or_lines(&encoded.signers)),
+    ]));
+    table.add_row(Row::new(vec![
+        utils::to_key_cell("Absent Signers"),
+        utils::to_value_cell(&none_or_lines(&encoded.absent_signers)),
+    ]));
+    table.printstd();
+    println!("{}", encoded.transaction);
+
+    Ok(())
+}
+
+/// Writes `tx` as JSON if `path` ends in `.json`, and as base64 otherwise.
+pub fn write_transaction_file(path: &Path, tx: &Transaction) -> anyhow::Result<()> {
+    let encoded = EncodedTransaction::new(tx)?;
+    let contents = match path.extension().is_some_and(|ext| ext == "json") {
+        true => serde_json::to_string_pretty(&encoded)?,
+        false => encoded.transaction,
+    };
+
+    fs::write(path, contents + "\n")
+        .map_err(|e| anyhow::anyhow!("Failed to write transaction to {}: {}", path.display(), e))
+}
+
+/// Reads a transaction written by [`write_transaction_file`], detecting the
+/// format from the contents.
+pub fn read_transaction_file(path: &Path) -> anyhow::Result<Transaction> {
+    let contents = fs::read_to_string(path).map_err(|e| {
+        anyhow::anyhow!("Failed to read transaction from {}: {}", path.display(), e)
+    })?;
+    let contents = contents.trim();
+    let base64 = match contents.starts_with('{') {
+        true => serde_json::from_str::<EncodedTransaction>(contents)?.transaction,
+        false => contents.to_string(),
+    };
+
+    let bytes = BASE64_STANDARD
+        .decode(base64)
+        .map_err(|e| anyhow::anyhow!("Invalid base64 transaction in {}: {}", path.display(), e))?;
+    bincode::deserialize(&bytes)
+        .map_err(|e| anyhow::anyhow!("Invalid transaction in {}: {}", path.display(), e))
+}
+
+/// Turns the opaque presigner error into a hint at the usual cause, a
+/// `--signer` signature made over a different message.
+pub fn signing_error(e: SignerError) -> anyhow::Error {
+    match e {
+        SignerError::PresignerError(_) => anyhow::anyhow!(
+            "A --signer signature does not match the transaction, check that it was produced with the same arguments and --blockhash"
+        ),
+        e => e.into(),
+    }
+}
+
+/// Rebuilds the instructions of a compiled message, for decoding and
+/// simulating transactions read from files.
+fn decompile_instructions(message: &Message) -> Vec<Instruction> {
+    message
+        .instructions
+        .iter()
+        .map(|ix| Instruction {
+            program_id: message.account_keys[ix.program_id_index as usize],
+            accounts: ix
+                .accounts
+                .iter()
+                .map(|&i| AccountMeta {
+                    pubkey: message.account_keys[i as usize],
+                    is_signer: message.is_signer(i as usize),
+                    is_writable: message.is_maybe_writable(i as usize, None),
+                })
+                .collect(),
+            data: ix.data.clone(),
+        })
+        .collect()
+}
+
+/// Sign and send transaction files written by --sign-only
+#[derive(Debug, Subcommand)]
+pub enum TxCommands {
+    /// Add signatures from --keypair, --fee-payer and --signer to a transaction file
+    Sign(TxFileArgs),
+    /// Broadcast a signed transaction file, or simulate it with --dry-run
+    Send(TxFileArgs),
+}
+
+/// Arguments of `surfer tx sign` and `surfer tx send`.
+#[derive(Debug, Args)]
+pub struct TxFileArgs {
+    #[clap(help = "A transaction file, base64 or JSON as written by --tx-file.")]
+    pub(crate) file: PathBuf,
+    /// The signer and transaction flags.
+    #[clap(flatten)]
+    pub send: SendArgs,
+}
+
+impl TxFileArgs {
+    /// The keypair sources `surfer tx sign` loads. The wallet keypair is only
+    /// loaded when `--keypair` is given or nothing else can sign, so an
+    /// air-gapped host using `--fee-payer` or `--signer` needs no default
+    /// keypair file.
+    fn signer_sources(
+        &self,
+        config: &Config,
+        required: &[Pubkey],
+    ) -> anyhow::Result<Vec<SignerSource>> {
+        let presigned: Vec<Pubkey> = config
+            .signer
+            .presigners
+            .iter()
+            .map(|p| p.pubkey())
+            .collect();
+        let wallet_needed = self.send.signer.keypair.is_some()
+            || (config.signer.fee_payer.is_none()
+                && required.iter().any(|pubkey| !presigned.contains(pubkey)));
+
+        let mut sources = Vec::new();
+        if wallet_needed {
+            sources.push(default_signer_source(&config.signer)?);
+        }
+        sources.extend(config.signer.fee_payer.clone());
+
+        Ok(sources)
+    }
+}
+
+impl TxCommands {
+    /// Runs the subcommand and prints its result.
+    pub async fn process(&self, config: &Config) -> anyhow::Result<()> {
+        match self {
+            TxCommands::Sign(args) => {
+                args.send.reject_unsupported(
+                    "surfer tx sign",
+                    &["--keypair", "--fee-payer", "--signer", "--tx-file"],
+                )?;
+                let mut tx = read_transaction_file(&args.file)?;
+                let required: Vec<Pubkey> = tx.message.signer_keys().into_iter().copied().collect();
+
+                let mut keypairs: Vec<Box<dyn Signer>> = Vec::new();
+                for source in args.signer_sources(config, &required)? {
+                    if !matches!(source, SignerSource::Pubkey(_)) {
+                        keypairs.push(Box::new(source.load("signer")?));
+                    }
+                }
+                keypairs.extend(
+                    config
+                        .signer
+                        .presigners
+                        .iter()
+                        .map(|p| Box::new(p.clone()) as Box<dyn Signer>),
+                );
+
+                let signers: Vec<&dyn Signer> = keypairs
+                    .iter()
+                    .map(|k| k.as_ref())
+                    .filter(|k| required.contains(&k.pubkey()))
+                    .collect();
+                if signers.is_empty() {
+                    return Err(anyhow::anyhow!(
+                        "None of the given signers is required by the transaction, required: {}",
+                        required
+                            .iter()
+                            .map(|p| p.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ));
+                }
+                // Signing over the message's own blockhash keeps existing
+                // signatures valid.
+                tx.try_partial_sign(&signers, tx.message.recent_blockhash)
+                    .map_err(signing_error)?;
+
+                print_signed_transaction(&tx, config.output)?;
+                if let Some(path) = &config.transaction.tx_file {
+                    write_transaction_file(path, &tx)?;
+                }
+            }
+            TxCommands::Send(args) => {
+                args.send
+                    .reject_unsupported("surfer tx send", &["--signer", "--dry-run"])?;
+                let mut tx = read_transaction_file(&args.file)?;
+                for presigner in &config.signer.presigners {
+                    tx.try_partial_sign(&[presigner], tx.message.recent_blockhash)
+                        .map_err(signing_error)?;
+                }
+                let rpc_client = config.rpc_client()?;
+
+                if config.transaction.dry_run {
+                    let spinner = utils::get_spinner("Simulating tx...")?;
+                    let ixs = decompile_instructions(&tx.message);
+                    let report = simulate(&rpc_client, &tx, &ixs, config.commitment).await?;
+                    spinner.finish_and_clear();
+
+                    return match config.output {
+                        OutputFormat::Table => {
+                            report.print();
+                            Ok(())
+                        }
+                        _ => output::print_json(&report.to_json(), config.output),
+                    };
+                }
+
+                let absent_signers = EncodedTransaction::new(&tx)?.absent_signers;
+                if !absent_signers.is_empty() {
+                    return Err(anyhow::anyhow!(
+                        "Transaction is missing signatures from: {}",
+                        absent_signers.join(", ")
+                    ));
+                }
+
+                let spinner = utils::get_spinner("Sending tx...")?;
+                let sig = rpc_client.send_and_confirm_transaction(&tx).await?;
+                spinner.finish_and_clear();
+
+                print_signature(&sig, config.output)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_client::{
+        rpc_request::RpcRequest,
+        rpc_response::{
+            Response, RpcPrioritizationFee, RpcResponseContext, RpcSimulateTransactionResult,
+        },
+    };
+    use solana_program_test::ProgramTest;
+    use solana_sdk::{
+        signature::{Keypair, Presigner},
+        system_instruction,
+    };
+    use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+    use super::*;
+    use crate::{
+        source::MemorySource,
+        test_fixtures::{create_mint, get_account, process},
+        token_account::{build_create_ix, build_transfer_ix},
+        token_program::TokenProgram,
+    };
+
+    fn config(transaction: TransactionArgs) -> Config {
+        Config {
+            rpc_url: None,
+            commitment: CommitmentConfig::confirmed(),
+            signer: SignerArgs::default(),
+            transaction,
+            output: OutputFormat::Json,
+            profile: None,
+        }
+    }
+
+    fn mock_rpc(request: RpcRequest, value: Value) -> RpcClient {
+        RpcClient::new_mock_with_mocks("succeeds".to_string(), HashMap::from([(request, value)]))
+    }
+
+    fn simulated_units(units: u64) -> RpcClient {
+        mock_rpc(
+            RpcRequest::SimulateTransaction,
+            json!(Response {
+                context: RpcResponseContext {
+                    slot: 1,
+                    api_version: None,
+                },
+                value: RpcSimulateTransactionResult {
+                    err: None,
+                    logs: None,
+                    accounts: None,
+                    units_consumed: Some(units),
+                    return_data: None,
+                    inner_instructions: None,
+                    replacement_blockhash: None,
+                },
+            }),
+        )
+    }
+
+    fn recent_fees(fees: &[u64]) -> RpcClient {
+        let fees: Vec<RpcPrioritizationFee> = fees
+            .iter()
+            .map(|&prioritization_fee| RpcPrioritizationFee {
+                slot: 1,
+                prioritization_fee,
+            })
+            .collect();
+        mock_rpc(RpcRequest::GetRecentPrioritizationFees, json!(fees))
+    }
+
+    #[test]
+    fn parses_priority_fee() {
+        assert_eq!("auto".parse::<PriorityFee>().unwrap(), PriorityFee::Auto);
+        assert_eq!(
+            "5000".parse::<PriorityFee>().unwrap(),
+            PriorityFee::MicroLamports(5_000)
+        );
+        for invalid in ["", "Auto", "-1", "1.5"] {
+            let err = invalid.parse::<PriorityFee>().unwrap_err();
+            assert!(err.to_string().starts_with("Invalid priority fee"));
+        }
+    }
+
+    #[test]
+    fn parses_compute_unit_limit() {
+        assert_eq!(
+            "auto".parse::<ComputeUnitLimit>().unwrap(),
+            ComputeUnitLimit::Auto
+        );
+        assert_eq!(
+            "200000".parse::<ComputeUnitLimit>().unwrap(),
+            ComputeUnitLimit::Units(200_000)
+        );
+        assert_eq!(
+            "1400000".parse::<ComputeUnitLimit>().unwrap(),
+            ComputeUnitLimit::Units(MAX_COMPUTE_UNIT_LIMIT)
+        );
+
+        let err = "1400001".parse::<ComputeUnitLimit>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Compute unit limit 1400001 exceeds the maximum of 1400000"
+        );
+        let err = "many".parse::<ComputeUnitLimit>().unwrap_err();
+        assert!(err.to_string().starts_with("Invalid compute unit limit"));
+    }
+
+    #[test]
+    fn lists_writable_accounts_once_in_order() {
+        let (first, second, read_only) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let ix = |accounts| Instruction::new_with_bytes(Pubkey::new_unique(), &[], accounts);
+        let ixs = [
+            ix(vec![
+                AccountMeta::new_readonly(read_only, true),
+                AccountMeta::new(first, false),
+            ]),
+            ix(vec![
+                AccountMeta::new(second, false),
+                AccountMeta::new(first, true),
+            ]),
+        ];
+
+        assert_eq!(writable_accounts(&ixs), vec![first, second]);
+        assert!(writable_accounts(&[]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn adds_compute_budget_after_the_nonce_advance() {
+        let payer = Pubkey::new_unique();
+        let transfer = system_instruction::transfer(&payer, &Pubkey::new_unique(), 1);
+        let advance = system_instruction::advance_nonce_account(&Pubkey::new_unique(), &payer);
+        let limit = ComputeBudgetInstruction::set_compute_unit_limit(300_000);
+        let price = ComputeBudgetInstruction::set_compute_unit_price(1_000);
+        let budget = config(TransactionArgs {
+            priority_fee: Some(PriorityFee::MicroLamports(1_000)),
+            compute_unit_limit: Some(ComputeUnitLimit::Units(300_000)),
+            ..TransactionArgs::default()
+        });
+
+        let ixs = with_compute_budget(&budget, vec![transfer.clone()], &payer)
+            .await
+            .unwrap();
+        assert_eq!(ixs, vec![limit.clone(), price.clone(), transfer.clone()]);
+
+        let ixs = with_compute_budget(&budget, vec![advance.clone(), transfer.clone()], &payer)
+            .await
+            .unwrap();
+        assert_eq!(ixs, vec![advance.clone(), limit, price, transfer.clone()]);
+
+        let ixs = with_compute_budget(
+            &config(TransactionArgs::default()),
+            vec![advance.clone(), transfer.clone()],
+            &payer,
+        )
+        .await
+        .unwrap();
+        assert_eq!(ixs, vec![advance, transfer]);
+    }
+
+    #[tokio::test]
+    async fn estimates_compute_units_with_a_margin() {
+        let ixs = [system_instruction::transfer(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            1,
+        )];
+        let payer = Pubkey::new_unique();
+
+        let units = estimate_compute_units(&simulated_units(200_000), &ixs, &payer)
+            .await
+            .unwrap();
+        assert_eq!(units, 220_000);
+
+        let units = estimate_compute_units(&simulated_units(1_300_000), &ixs, &payer)
+            .await
+            .unwrap();
+        assert_eq!(units, MAX_COMPUTE_UNIT_LIMIT);
+    }
+
+    #[tokio::test]
+    async fn estimates_priority_fee_from_the_median() {
+        let ixs = [system_instruction::transfer(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            1,
+        )];
+
+        let fee = estimate_priority_fee(&recent_fees(&[500, 100, 300]), &ixs)
+            .await
+            .unwrap();
+        assert_eq!(fee, 300);
+
+        // The upper median of an even number of fees.
+        let fee = estimate_priority_fee(&recent_fees(&[400, 0, 100, 200]), &ixs)
+            .await
+            .unwrap();
+        assert_eq!(fee, 200);
+
+        let fee = estimate_priority_fee(&recent_fees(&[]), &ixs)
+            .await
+            .unwrap();
+        assert_eq!(fee, 0);
+    }
+
+    /// A lamport transfer to `authority` and a token transfer signed by it,
+    /// signed by the payer only.
+    fn partially_signed(
+        payer: &Keypair,
+        authority: &Pubkey,
+        token_accounts: [Pubkey; 2],
+    ) -> Transaction {
+        let ixs = [
+            system_instruction::transfer(&payer.pubkey(), authority, 1),
+            spl_token::instruction::transfer(
+                &spl_token::ID,
+                &token_accounts[0],
+                &token_accounts[1],
+                authority,
+                &[],
+                5,
+            )
+            .unwrap(),
+        ];
+        let mut tx = Transaction::new_with_payer(&ixs, Some(&payer.pubkey()));
+        tx.try_partial_sign(&[payer], Hash::new_unique()).unwrap();
+        tx
+    }
+
+    #[test]
+    fn encodes_present_and_absent_signers() {
+        let payer = Keypair::new();
+        let authority = Pubkey::new_unique();
+        let tx = partially_signed(
+            &payer,
+            &authority,
+            [Pubkey::new_unique(), Pubkey::new_unique()],
+        );
+
+        let encoded = EncodedTransaction::new(&tx).unwrap();
+        assert_eq!(encoded.blockhash, tx.message.recent_blockhash.to_string());
+        assert_eq!(
+            encoded.signers,
+            vec![format!("{}={}", payer.pubkey(), tx.signatures[0])]
+        );
+        assert_eq!(encoded.absent_signers, vec![authority.to_string()]);
+        assert_eq!(
+            bincode::deserialize::<Transaction>(
+                &BASE64_STANDARD.decode(&encoded.transaction).unwrap()
+            )
+            .unwrap(),
+            tx
+        );
+    }
+
+    #[test]
+    fn round_trips_transaction_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let payer = Keypair::new();
+        let authority = Keypair::new();
+        let tx = partially_signed(
+            &payer,
+            &authority.pubkey(),
+            [Pubkey::new_unique(), Pubkey::new_unique()],
+        );
+
+        for name in ["tx.json", "tx.b64", "tx"] {
+            let path = dir.path().join(name);
+            write_transaction_file(&path, &tx).unwrap();
+            assert_eq!(read_transaction_file(&path).unwrap(), tx);
+
+            let contents = fs::read_to_string(&path).unwrap();
+            assert_eq!(contents.starts_with('{'), name.ends_with(".json"));
+        }
+
+        // The second signer completes the transaction read back from the file.
+        let path = dir.path().join("tx.json");
+        let mut tx = read_transaction_file(&path).unwrap();
+        tx.try_partial_sign(&[&authority], tx.message.recent_blockhash)
+            .unwrap();
+        write_transaction_file(&path, &tx).unwrap();
+        let tx = read_transaction_file(&path).unwrap();
+        assert!(tx.verify().is_ok());
+        assert!(EncodedTransaction::new(&tx)
+            .unwrap()
+            .absent_signers
+            .is_empty());
+
+        fs::write(dir.path().join("bad"), "not base64").unwrap();
+        let err = read_transaction_file(&dir.path().join("bad")).unwrap_err();
+        assert!(err.to_string().starts_with("Invalid base64 transaction"));
+        let err = read_transaction_file(&dir.path().join("missing")).unwrap_err();
+        assert!(err.to_string().starts_with("Failed to read transaction"));
+    }
+
+    #[test]
+    fn decompiles_instructions_from_a_message() {
+        let payer = Keypair::new();
+        let authority = Pubkey::new_unique();
+        let [source, destination] = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let tx = partially_signed(&payer, &authority, [source, destination]);
+
+        let ixs = decompile_instructions(&tx.message);
+        // The message merges the flags of an account across instructions, so
+        // the lamport recipient comes back as a signer.
+        assert_eq!(
+            ixs[0].accounts,
+            vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new(authority, true),
+            ]
+        );
+        assert_eq!(
+            ixs[1].accounts,
+            vec![
+                AccountMeta::new(source, false),
+                AccountMeta::new(destination, false),
+                AccountMeta::new(authority, true),
+            ]
+        );
+        assert_eq!(
+            ixs.iter().map(|ix| ix.program_id).collect::<Vec<_>>(),
+            vec![system_program::ID, spl_token::ID]
+        );
+        assert_eq!(decode_instruction(&ixs[0]).1, "Transfer { lamports: 1 }");
+        assert_eq!(decode_instruction(&ixs[1]).1, "Transfer { amount: 5 }");
+    }
+
+    #[test]
+    fn tx_sign_loads_the_wallet_only_when_needed() {
+        let wallet = SignerSource::File(PathBuf::from("/keys/solana.json"));
+        let fee_payer = SignerSource::File(PathBuf::from("/keys/fee-payer.json"));
+        let (payer, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let presigner = Presigner::new(&other, &Signature::new_unique());
+        // The wallet comes from the Solana CLI config rather than --keypair.
+        let resolved = |fee_payer: Option<SignerSource>, presigners: Vec<Presigner>| Config {
+            signer: SignerArgs {
+                keypair: Some(wallet.clone()),
+                fee_payer,
+                presigners,
+                ..SignerArgs::default()
+            },
+            ..config(TransactionArgs::default())
+        };
+        let args = |keypair: Option<SignerSource>| TxFileArgs {
+            file: PathBuf::from("tx.json"),
+            send: SendArgs {
+                signer: SignerArgs {
+                    keypair,
+                    ..SignerArgs::default()
+                },
+                ..SendArgs::default()
+            },
+        };
+
+        let sources = args(None)
+            .signer_sources(&resolved(Some(fee_payer.clone()), vec![]), &[payer, other])
+            .unwrap();
+        assert_eq!(sources, vec![fee_payer.clone()]);
+
+        let sources = args(None)
+            .signer_sources(&resolved(None, vec![presigner.clone()]), &[other])
+            .unwrap();
+        assert!(sources.is_empty());
+
+        let sources = args(None)
+            .signer_sources(&resolved(None, vec![presigner.clone()]), &[payer, other])
+            .unwrap();
+        assert_eq!(sources, vec![wallet.clone()]);
+
+        let sources = args(Some(wallet.clone()))
+            .signer_sources(
+                &resolved(Some(fee_payer.clone()), vec![presigner]),
+                &[other],
+            )
+            .unwrap();
+        assert_eq!(sources, vec![wallet, fee_payer]);
+    }
+
+    async fn simulation_report(program: TokenProgram) {
+        let program_name = match program {
+            TokenProgram::LegacyToken => "Token Program",
+            TokenProgram::Token2022 => "Token-2022 Program",
+        };
+        let program_id: Pubkey = program.into();
+        let mut ctx = ProgramTest::default().start_with_context().await;
+        let payer = ctx.payer.pubkey();
+        let mint = create_mint(&mut ctx, &program_id, 6, None).await;
+        let recipient = Keypair::new().pubkey();
+
+        let mint_acc = get_account(&mut ctx, &mint).await;
+        let source = get_associated_token_address_with_program_id(&payer, &mint, &program_id);
+        let destination =
+            get_associated_token_address_with_program_id(&recipient, &mint, &program_id);
+        process(
+            &mut ctx,
+            &[
+                build_create_ix(&payer, &payer, &mint, &mint_acc).unwrap(),
+                spl_token_2022::instruction::mint_to(
+                    &program_id,
+                    &mint,
+                    &source,
+                    &payer,
+                    &[],
+                    5_000_000,
+                )
+                .unwrap(),
+            ],
+            &[],
+        )
+        .await;
+
+        let ixs = vec![
+            build_create_ix(&payer, &recipient, &mint, &mint_acc).unwrap(),
+            build_transfer_ix(
+                &source,
+                &mint,
+                &MintWithExtensions::try_parse_mint_with_extensions(mint_acc.clone(), None)
+                    .unwrap(),
+                &destination,
+                &payer,
+                &[],
+                1_500_000,
+            )
+            .unwrap(),
+        ];
+        let blockhash = ctx.banks_client.get_latest_blockhash().await.unwrap();
+        let tx = Transaction::new_signed_with_payer(&ixs, Some(&payer), &[&ctx.payer], blockhash);
+
+        let writable = writable_accounts(&ixs);
+        let mut pre_accounts = Vec::new();
+        for pubkey in &writable {
+            pre_accounts.push(ctx.banks_client.get_account(*pubkey).await.unwrap());
+        }
+        let simulation = ctx
+            .banks_client
+            .simulate_transaction(tx.clone())
+            .await
+            .unwrap();
+        let details = simulation.simulation_details.unwrap();
+        assert!(matches!(simulation.result, Some(Ok(()))));
+        ctx.banks_client.process_transaction(tx).await.unwrap();
+        let mut post_accounts = Vec::new();
+        for pubkey in &writable {
+            post_accounts.push(ctx.banks_client.get_account(*pubkey).await.unwrap());
+        }
+
+        // The mint is read-only here, so its decimals come from the source.
+        let mints = MemorySource::new().with_account(mint, mint_acc);
+        let report = SimulationReport {
+            err: None,
+            units_consumed: Some(details.units_consumed),
+            logs: details.logs,
+            ixs: ixs.clone(),
+            token_balances: token_balance_changes(&mints, &writable, &pre_accounts, &post_accounts)
+                .await
+                .unwrap(),
+        };
+        let json = report.to_json();
+
+        assert!(json["err"].is_null());
+        assert!(details.units_consumed > 0);
+        assert_eq!(json["unitsConsumed"], details.units_consumed);
+        assert!(!json["logs"].as_array().unwrap().is_empty());
+
+        let instructions = json["instructions"].as_array().unwrap();
+        assert_eq!(instructions.len(), 2);
+        assert_eq!(
+            instructions[0]["program"],
+            "Associated Token Account Program"
+        );
+        assert_eq!(instructions[0]["instruction"], "Create");
+        assert_eq!(instructions[1]["programId"], program_id.to_string());
+        assert_eq!(instructions[1]["program"], program_name);
+        assert_eq!(
+            instructions[1]["instruction"],
+            "TransferChecked { amount: 1500000, decimals: 6 }"
+        );
+        assert_eq!(
+            instructions[1]["accounts"][0],
+            json!({ "pubkey": source.to_string(), "isSigner": false, "isWritable": true })
+        );
+
+        // The new account only shows up after the transaction.
+        assert_eq!(
+            json["tokenBalances"],
+            json!([
+                {
+                    "account": destination.to_string(),
+                    "mint": mint.to_string(),
+                    "preBalance": UiAmount::new(0, 6),
+                    "postBalance": UiAmount::new(1_500_000, 6),
+                },
+                {
+                    "account": source.to_string(),
+                    "mint": mint.to_string(),
+                    "preBalance": UiAmount::new(5_000_000, 6),
+                    "postBalance": UiAmount::new(3_500_000, 6),
+                },
+            ])
+        );
+        assert_eq!(report.token_balances[1].change(), "-1.5");
+    }
+
+    #[tokio::test]
+    async fn legacy_token_simulation_report() {
+        simulation_report(TokenProgram::LegacyToken).await;
+    }
+
+    #[tokio::test]
+    async fn token_2022_simulation_report() {
+        simulation_report(TokenProgram::Token2022).await;
+    }
+}
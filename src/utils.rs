@@ -1,8 +1,16 @@
-use std::time::Duration;
+use std::{io::IsTerminal, str::FromStr, time::Duration};
 
 use indicatif::ProgressBar;
+use prettytable::{color, Attr, Cell};
+use solana_sdk::{
+    account::from_account,
+    sysvar::clock::{self, Clock},
+};
 use spl_pod::solana_pubkey::Pubkey;
 
+use crate::source::AccountSource;
+
+/// A spinner showing `msg`, already ticking.
 pub fn get_spinner(msg: &str) -> anyhow::Result<ProgressBar> {
     let mut spinner = ProgressBar::new_spinner();
     spinner.set_tab_width(16);
@@ -17,9 +25,159 @@ pub fn get_spinner(msg: &str) -> anyhow::Result<ProgressBar> {
     Ok(spinner)
 }
 
+/// A bold white table cell for headings.
+pub fn to_header_cell(header: &str) -> Cell {
+    Cell::new(header)
+        .with_style(Attr::Bold)
+        .with_style(Attr::ForegroundColor(color::WHITE))
+}
+
+/// A bold green table cell for keys.
+pub fn to_key_cell(key: &str) -> Cell {
+    Cell::new(key)
+        .with_style(Attr::Bold)
+        .with_style(Attr::ForegroundColor(color::GREEN))
+}
+
+/// A bold cyan table cell for values.
+pub fn to_value_cell(value: &str) -> Cell {
+    Cell::new(value)
+        .with_style(Attr::Bold)
+        .with_style(Attr::ForegroundColor(color::BRIGHT_CYAN))
+}
+
+/// A bold red table cell for errors.
+pub fn to_error_cell(error: &str) -> Cell {
+    Cell::new(error)
+        .with_style(Attr::Bold)
+        .with_style(Attr::ForegroundColor(color::RED))
+}
+
+/// Formats basis points as a percentage, e.g. `50` as `0.50% (50 bps)`.
+pub fn format_basis_points(basis_points: i32) -> String {
+    format!("{:.2}% ({} bps)", basis_points as f64 / 100.0, basis_points)
+}
+
+/// Formats a unix timestamp as `<timestamp> (<UTC date>)`.
+pub fn format_unix_timestamp(timestamp: i64) -> String {
+    match chrono::DateTime::from_timestamp(timestamp, 0) {
+        Some(date) => format!("{} ({})", timestamp, date.format("%Y-%m-%d %H:%M:%S UTC")),
+        None => timestamp.to_string(),
+    }
+}
+
+/// Fetches the cluster's current unix timestamp from the clock sysvar, or
+/// `None` if `source` doesn't have it.
+pub async fn fetch_unix_timestamp<S: AccountSource + ?Sized>(
+    source: &S,
+) -> anyhow::Result<Option<i64>> {
+    let Some(clock_acc) = source.get_account(&clock::ID).await? else {
+        return Ok(None);
+    };
+    let clock: Clock = from_account(&clock_acc)
+        .ok_or_else(|| anyhow::anyhow!("Failed to deserialize clock sysvar"))?;
+
+    Ok(Some(clock.unix_timestamp))
+}
+
+/// Prompts are only shown when stdin is attached to a terminal, so scripts
+/// and CI runs fail fast on missing arguments instead of hanging.
+pub fn can_prompt() -> bool {
+    std::io::stdin().is_terminal()
+}
+
+/// Parses a base58 pubkey given on the command line.
+pub fn parse_pubkey(pubkey_str: &str) -> anyhow::Result<Pubkey> {
+    match Pubkey::from_str(pubkey_str) {
+        Ok(pubkey) => Ok(pubkey),
+        Err(err) => Err(anyhow::anyhow!("Invalid pubkey: {}", err)),
+    }
+}
+
+/// Prompts for a pubkey.
 pub fn get_pubkey_from_prompt(msg: &str) -> anyhow::Result<Pubkey> {
-    Ok(inquire::Text::new(msg)
-        .prompt()
-        .expect("Failed to get pubkey")
-        .parse::<Pubkey>()?)
+    Ok(inquire::Text::new(msg).prompt()?.trim().parse::<Pubkey>()?)
+}
+
+/// Prompts for a pubkey, resolving an empty answer to `None`.
+pub fn get_optional_pubkey_from_prompt(msg: &str) -> anyhow::Result<Option<Pubkey>> {
+    let input = inquire::Text::new(msg).prompt()?;
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(input.parse::<Pubkey>()?))
+}
+
+/// Returns `value` if it was passed on the command line, otherwise prompts
+/// for it.
+pub fn pubkey_or_prompt(value: Option<Pubkey>, arg: &str, msg: &str) -> anyhow::Result<Pubkey> {
+    match value {
+        Some(pubkey) => Ok(pubkey),
+        None if can_prompt() => get_pubkey_from_prompt(msg),
+        None => Err(missing_arg_error(arg)),
+    }
+}
+
+/// Like [`pubkey_or_prompt`] but an empty answer, or a missing value when
+/// prompting is not possible, resolves to `None`.
+pub fn optional_pubkey_or_prompt(
+    value: Option<Pubkey>,
+    msg: &str,
+) -> anyhow::Result<Option<Pubkey>> {
+    match value {
+        Some(pubkey) => Ok(Some(pubkey)),
+        None if can_prompt() => get_optional_pubkey_from_prompt(msg),
+        None => Ok(None),
+    }
+}
+
+/// Returns `value` if it was passed on the command line, otherwise prompts
+/// for it. Without a terminal to prompt on, `default` is used if given.
+pub fn value_or_prompt<T>(
+    value: Option<T>,
+    arg: &str,
+    msg: &str,
+    default: Option<&str>,
+) -> anyhow::Result<T>
+where
+    T: FromStr,
+    T::Err: Into<anyhow::Error>,
+{
+    match (value, default) {
+        (Some(value), _) => Ok(value),
+        (None, _) if can_prompt() => {
+            let mut text = inquire::Text::new(msg);
+            if let Some(default) = default {
+                text = text.with_default(default);
+            }
+            text.prompt()?.trim().parse::<T>().map_err(Into::into)
+        }
+        (None, Some(default)) => default.parse::<T>().map_err(Into::into),
+        (None, None) => Err(missing_arg_error(arg)),
+    }
+}
+
+/// Returns `value` if it was passed on the command line, otherwise asks to
+/// pick one of `options`, parsed with `from_select_str`.
+pub fn select_or_prompt<T>(
+    value: Option<T>,
+    arg: &str,
+    msg: &str,
+    options: Vec<&'static str>,
+    from_select_str: impl Fn(&str) -> anyhow::Result<T>,
+) -> anyhow::Result<T> {
+    match value {
+        Some(value) => Ok(value),
+        None if can_prompt() => from_select_str(inquire::Select::new(msg, options).prompt()?),
+        None => Err(missing_arg_error(arg)),
+    }
+}
+
+fn missing_arg_error(arg: &str) -> anyhow::Error {
+    anyhow::anyhow!(
+        "Missing required argument `{}` (prompting is disabled because stdin is not a terminal)",
+        arg
+    )
 }
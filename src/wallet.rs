@@ -0,0 +1,435 @@
+use std::{cmp::Ordering, collections::HashMap};
+
+use clap::{Args, Subcommand, ValueEnum};
+use mpl_token_metadata::accounts::Metadata;
+use prettytable::{Row, Table};
+use serde::Serialize;
+use serde_json::json;
+use solana_account::Account;
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::{
+    nonblocking::rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcTokenAccountsFilter},
+    rpc_request::RpcRequest,
+    rpc_response::{Response, RpcKeyedAccount},
+};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_token::solana_program::pubkey::Pubkey;
+
+use crate::{
+    config::Config,
+    mint::{parse_metadata, MintWithExtensions, TokenMetadata},
+    output::{self, OutputFormat, UiAmount},
+    source::AccountSource,
+    token_account::TokenAccountWithExtensions,
+    utils,
+};
+
+/// Inspect the token holdings of a wallet
+#[derive(Debug, Subcommand)]
+pub enum WalletCommands {
+    /// List the token accounts of a wallet across both token programs
+    Balances(WalletBalancesArgs),
+}
+
+/// Order of the accounts listed by `surfer wallet balances`.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum BalanceSort {
+    /// By symbol, then by mint
+    Symbol,
+    /// Largest UI balance first
+    Balance,
+    /// By mint address
+    Mint,
+}
+
+/// Arguments of `surfer wallet balances`.
+#[derive(Debug, Args)]
+pub struct WalletBalancesArgs {
+    #[clap(
+        value_parser = utils::parse_pubkey,
+        help = "The wallet whose token accounts to list."
+    )]
+    pub(crate) owner: Pubkey,
+    #[clap(
+        long,
+        value_enum,
+        default_value = "symbol",
+        help = "Order of the listed accounts."
+    )]
+    pub(crate) sort: BalanceSort,
+    #[clap(long, help = "Leave out accounts with a zero balance.")]
+    pub(crate) hide_zero: bool,
+}
+
+/// A token account of a wallet with the details of its mint.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletBalance {
+    /// Address of the token account.
+    pub account: String,
+    /// Mint of the token account.
+    pub mint: String,
+    /// Address of the owning token program.
+    pub program: String,
+    /// Name from the Metaplex metadata or the TokenMetadata extension, if any.
+    pub name: Option<String>,
+    /// Symbol from the Metaplex metadata or the TokenMetadata extension, if any.
+    pub symbol: Option<String>,
+    /// Balance in UI units.
+    pub balance: UiAmount,
+    /// Whether the account is frozen.
+    pub is_frozen: bool,
+    /// Whether the account is the owner's associated token account for the
+    /// mint, rather than an auxiliary account.
+    pub is_associated: bool,
+}
+
+impl WalletBalance {
+    fn ui_amount(&self) -> f64 {
+        self.balance.ui_amount_string.parse().unwrap_or_default()
+    }
+}
+
+/// Fetches every token account `owner` holds under `program_id`.
+pub async fn fetch_token_accounts_by_owner(
+    rpc_client: &RpcClient,
+    owner: &Pubkey,
+    program_id: &Pubkey,
+) -> anyhow::Result<Vec<(Pubkey, Account)>> {
+    // `RpcClient::get_token_accounts_by_owner` asks for jsonParsed data,
+    // which the parsers can't read, so request base64 directly.
+    let config = RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        commitment: Some(rpc_client.commitment()),
+        ..RpcAccountInfoConfig::default()
+    };
+    let response: Response<Vec<RpcKeyedAccount>> = rpc_client
+        .send(
+            RpcRequest::GetTokenAccountsByOwner,
+            json!([
+                owner.to_string(),
+                RpcTokenAccountsFilter::ProgramId(program_id.to_string()),
+                config
+            ]),
+        )
+        .await?;
+
+    response
+        .value
+        .into_iter()
+        .map(|keyed| {
+            let pubkey = keyed.pubkey.parse::<Pubkey>()?;
+            let account = keyed
+                .account
+                .decode::<Account>()
+                .ok_or_else(|| anyhow::anyhow!("Failed to decode token account {}", pubkey))?;
+            Ok((pubkey, account))
+        })
+        .collect()
+}
+
+/// Builds the balances of `owner`'s token accounts, fetching their mints and
+/// Metaplex metadata from `source` in one batch. An account that can't be
+/// parsed, or whose mint is missing or can't be parsed, gets an error in
+/// place of its balance; only a failed fetch fails the whole call.
+pub async fn wallet_balances<S: AccountSource + ?Sized>(
+    source: &S,
+    owner: &Pubkey,
+    token_accounts: Vec<(Pubkey, Account)>,
+) -> anyhow::Result<Vec<(Pubkey, anyhow::Result<WalletBalance>)>> {
+    let token_accounts: Vec<_> = token_accounts
+        .into_iter()
+        .map(|(pubkey, account)| {
+            let program_id = account.owner;
+            let parsed =
+                TokenAccountWithExtensions::try_parse_token_account_with_extensions(account)
+                    .map_err(|e| anyhow::anyhow!("Error parsing token account {}: {}", pubkey, e));
+            (pubkey, program_id, parsed)
+        })
+        .collect();
+
+    let mut mints: Vec<Pubkey> = token_accounts
+        .iter()
+        .filter_map(|(_, _, parsed)| Some(parsed.as_ref().ok()?.base.get_mint()))
+        .collect();
+    mints.sort();
+    mints.dedup();
+    let metadata_pdas: Vec<Pubkey> = mints
+        .iter()
+        .map(|mint| Metadata::find_pda(mint).0)
+        .collect();
+    let accounts = source
+        .get_multiple_accounts(&[mints.as_slice(), metadata_pdas.as_slice()].concat())
+        .await?;
+    let (mint_accounts, metadata_accounts) = accounts.split_at(mints.len());
+
+    let mut mint_data = HashMap::with_capacity(mints.len());
+    for ((mint, mint_account), metadata_account) in
+        mints.iter().zip(mint_accounts).zip(metadata_accounts)
+    {
+        let token_metadata = metadata_account.as_ref().map(|account| TokenMetadata {
+            metadata: parse_metadata(&account.data),
+            master_edition: None,
+        });
+        let parsed = mint_account
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("Mint {} does not exist", mint))
+            .and_then(|mint_account| {
+                MintWithExtensions::try_parse_mint_with_extensions(mint_account, token_metadata)
+                    .map_err(|e| anyhow::anyhow!("Error parsing mint {}: {}", mint, e))
+            });
+        mint_data.insert(*mint, parsed);
+    }
+
+    Ok(token_accounts
+        .into_iter()
+        .map(|(pubkey, program_id, parsed)| {
+            let balance = parsed.and_then(|parsed| {
+                let mint = parsed.base.get_mint();
+                let mint_data = mint_data[&mint]
+                    .as_ref()
+                    .map_err(|e| anyhow::anyhow!("{}", e))?;
+                let (name, symbol) = mint_data.name_and_symbol().unzip();
+
+                Ok(WalletBalance {
+                    account: pubkey.to_string(),
+                    mint: mint.to_string(),
+                    program: program_id.to_string(),
+                    name,
+                    symbol,
+                    balance: UiAmount::new(parsed.base.get_amount(), mint_data.base.get_decimals()),
+                    is_frozen: parsed.base.is_frozen(),
+                    is_associated: pubkey
+                        == get_associated_token_address_with_program_id(owner, &mint, &program_id),
+                })
+            });
+            (pubkey, balance)
+        })
+        .collect())
+}
+
+/// Sorts `balances` in place, with the accounts that failed last. Ties are
+/// broken by mint and then account so the order is stable across runs.
+pub fn sort_balances(balances: &mut [(Pubkey, anyhow::Result<WalletBalance>)], sort: BalanceSort) {
+    balances.sort_by(|(a_pubkey, a), (b_pubkey, b)| match (a, b) {
+        (Ok(a), Ok(b)) => compare_balances(a, b, sort),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a_pubkey.cmp(b_pubkey),
+    });
+}
+
+fn compare_balances(a: &WalletBalance, b: &WalletBalance, sort: BalanceSort) -> Ordering {
+    let ordering = match sort {
+        BalanceSort::Symbol => match (&a.symbol, &b.symbol) {
+            (Some(a), Some(b)) => a.to_lowercase().cmp(&b.to_lowercase()),
+            // Tokens without a symbol go last.
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        },
+        BalanceSort::Balance => b.ui_amount().total_cmp(&a.ui_amount()),
+        BalanceSort::Mint => Ordering::Equal,
+    };
+    ordering
+        .then_with(|| a.mint.cmp(&b.mint))
+        .then_with(|| a.account.cmp(&b.account))
+}
+
+fn print_balances(owner: &Pubkey, balances: &[(Pubkey, anyhow::Result<WalletBalance>)]) {
+    println!();
+    let headers = [
+        "Symbol", "Name", "Balance", "Mint", "Account", "ATA", "Frozen",
+    ];
+    let mut table = Table::new();
+    table.add_row(Row::new(
+        headers.into_iter().map(utils::to_header_cell).collect(),
+    ));
+    for (pubkey, result) in balances {
+        let balance = match result {
+            Ok(balance) => balance,
+            Err(e) => {
+                table.add_row(Row::new(vec![
+                    utils::to_key_cell(&pubkey.to_string()),
+                    utils::to_error_cell(&e.to_string()).with_hspan(headers.len() - 1),
+                ]));
+                continue;
+            }
+        };
+        table.add_row(Row::new(vec![
+            utils::to_key_cell(balance.symbol.as_deref().unwrap_or("-")),
+            utils::to_value_cell(balance.name.as_deref().unwrap_or("-")),
+            utils::to_value_cell(&balance.balance.ui_amount_string),
+            utils::to_value_cell(&balance.mint),
+            utils::to_value_cell(&balance.account),
+            utils::to_value_cell(if balance.is_associated { "yes" } else { "no" }),
+            utils::to_value_cell(if balance.is_frozen { "yes" } else { "no" }),
+        ]));
+    }
+    table.printstd();
+    println!(" {} token accounts owned by {}", balances.len(), owner);
+}
+
+impl WalletCommands {
+    /// Runs the subcommand and prints its result.
+    pub async fn process(&self, config: &Config) -> anyhow::Result<()> {
+        let output = config.output;
+        match self {
+            WalletCommands::Balances(args) => {
+                let rpc_client = config.rpc_client()?;
+                let spinner = utils::get_spinner("Fetching token accounts...")?;
+                let (legacy, token_2022) = tokio::join!(
+                    fetch_token_accounts_by_owner(&rpc_client, &args.owner, &spl_token::ID),
+                    fetch_token_accounts_by_owner(&rpc_client, &args.owner, &spl_token_2022::ID)
+                );
+                let token_accounts = [legacy?, token_2022?].concat();
+
+                spinner.set_message("Fetching mints...");
+                let mut balances =
+                    wallet_balances(&rpc_client, &args.owner, token_accounts).await?;
+                spinner.finish_and_clear();
+
+                if args.hide_zero {
+                    balances.retain(
+                        |(_, balance)| !matches!(balance, Ok(b) if b.balance.amount == "0"),
+                    );
+                }
+                sort_balances(&mut balances, args.sort);
+
+                match output {
+                    OutputFormat::Table => print_balances(&args.owner, &balances),
+                    _ => {
+                        output::print_json(&output::batch_to_json(&balances, |b| json!(b)), output)?
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use spl_token::state::AccountState;
+
+    use super::*;
+    use crate::{
+        source::MemorySource,
+        test_fixtures::{mint_account, token_account_with_state},
+    };
+
+    #[tokio::test]
+    async fn balances_of_associated_and_auxiliary_accounts() {
+        let (owner, usdc, bonk) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let usdc_ata = get_associated_token_address_with_program_id(&owner, &usdc, &spl_token::ID);
+        let bonk_aux = Pubkey::new_unique();
+        let source = MemorySource::new()
+            .with_account(usdc, mint_account(6, 0))
+            .with_account(bonk, mint_account(5, 0));
+
+        let mut balances = wallet_balances(
+            &source,
+            &owner,
+            vec![
+                (
+                    bonk_aux,
+                    token_account_with_state(&bonk, &owner, 1_000_000_000, AccountState::Frozen),
+                ),
+                (
+                    usdc_ata,
+                    token_account_with_state(&usdc, &owner, 25_500_000, AccountState::Initialized),
+                ),
+            ],
+        )
+        .await
+        .unwrap();
+        sort_balances(&mut balances, BalanceSort::Balance);
+
+        let summary: Vec<_> = balances
+            .iter()
+            .map(|(_, b)| {
+                let b = b.as_ref().unwrap();
+                (
+                    b.account.clone(),
+                    b.balance.ui_amount_string.clone(),
+                    b.is_associated,
+                    b.is_frozen,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (bonk_aux.to_string(), "10000".to_string(), false, true),
+                (usdc_ata.to_string(), "25.5".to_string(), true, false),
+            ]
+        );
+        assert!(balances
+            .iter()
+            .all(|(_, b)| b.as_ref().unwrap().symbol.is_none()));
+    }
+
+    #[tokio::test]
+    async fn balances_report_failures_per_account() {
+        let (owner, mint, missing_mint) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let (ok, orphan, garbage) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let source = MemorySource::new().with_account(mint, mint_account(6, 0));
+        let account = |mint| token_account_with_state(mint, &owner, 1, AccountState::Initialized);
+
+        let mut balances = wallet_balances(
+            &source,
+            &owner,
+            vec![
+                (orphan, account(&missing_mint)),
+                (
+                    garbage,
+                    Account {
+                        data: vec![1; 10],
+                        ..account(&mint)
+                    },
+                ),
+                (ok, account(&mint)),
+            ],
+        )
+        .await
+        .unwrap();
+        sort_balances(&mut balances, BalanceSort::Symbol);
+
+        assert_eq!(balances[0].0, ok);
+        assert_eq!(
+            balances[0].1.as_ref().unwrap().balance.ui_amount_string,
+            "0.000001"
+        );
+        // Failed accounts come last, ordered by address.
+        let errors: Vec<_> = balances[1..]
+            .iter()
+            .map(|(pubkey, b)| (*pubkey, b.as_ref().unwrap_err().to_string()))
+            .collect();
+        let mut expected = vec![
+            (orphan, format!("Mint {} does not exist", missing_mint)),
+            (
+                garbage,
+                format!(
+                    "Error parsing token account {}: Error unpacking token account: An account's data contents was invalid",
+                    garbage
+                ),
+            ),
+        ];
+        expected.sort();
+        assert_eq!(errors, expected);
+    }
+}